@@ -0,0 +1,6 @@
+-- Add migration script here
+
+ALTER TABLE sessions
+    ALTER COLUMN expires_at TYPE TIMESTAMPTZ USING expires_at AT TIME ZONE 'UTC';
+
+CREATE INDEX idx_sessions_expires_at ON sessions (expires_at);
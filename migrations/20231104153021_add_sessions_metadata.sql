@@ -0,0 +1,9 @@
+-- Add migration script here
+
+ALTER TABLE sessions
+    ADD COLUMN created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN user_agent   VARCHAR,
+    ADD COLUMN ip_address   VARCHAR;
+
+CREATE INDEX idx_sessions_user_id ON sessions (user_id);
@@ -0,0 +1,19 @@
+-- Add migration script here
+
+CREATE TABLE personal_access_tokens
+(
+    id           UUID PRIMARY KEY,
+    user_id      UUID        NOT NULL,
+    name         VARCHAR     NOT NULL,
+    token_hash   VARCHAR     NOT NULL UNIQUE,
+    token_hint   VARCHAR     NOT NULL,
+    scopes       VARCHAR[]   NOT NULL,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    expires_at   TIMESTAMPTZ,
+
+    CONSTRAINT fk_user_id
+        FOREIGN KEY (user_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE
+)
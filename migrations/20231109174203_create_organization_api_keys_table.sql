@@ -0,0 +1,25 @@
+-- Add migration script here
+
+CREATE TABLE organization_api_keys
+(
+    id              UUID PRIMARY KEY,
+    organization_id UUID        NOT NULL,
+    name            VARCHAR     NOT NULL,
+    key_hash        VARCHAR     NOT NULL UNIQUE,
+    key_hint        VARCHAR     NOT NULL,
+    role            SMALLINT    NOT NULL DEFAULT 0,
+    created_by      UUID,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at    TIMESTAMPTZ,
+    expires_at      TIMESTAMPTZ,
+
+    CONSTRAINT fk_organization_id
+        FOREIGN KEY (organization_id)
+            REFERENCES organizations (id)
+            ON DELETE CASCADE,
+
+    CONSTRAINT fk_created_by
+        FOREIGN KEY (created_by)
+            REFERENCES users (id)
+            ON DELETE SET NULL
+)
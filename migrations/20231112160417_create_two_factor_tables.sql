@@ -0,0 +1,32 @@
+-- Add migration script here
+
+CREATE TABLE user_totp
+(
+    user_id      UUID PRIMARY KEY,
+    secret       VARCHAR     NOT NULL,
+    enabled      BOOLEAN     NOT NULL DEFAULT FALSE,
+    last_counter BIGINT,
+    created_at   TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_user_id
+        FOREIGN KEY (user_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE
+);
+
+CREATE TABLE user_recovery_codes
+(
+    id        UUID PRIMARY KEY,
+    user_id   UUID    NOT NULL,
+    code_hash VARCHAR NOT NULL,
+    used_at   TIMESTAMPTZ,
+
+    CONSTRAINT fk_user_id
+        FOREIGN KEY (user_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE
+);
+
+ALTER TABLE sessions
+    ADD COLUMN two_factor_pending  BOOLEAN  NOT NULL DEFAULT FALSE,
+    ADD COLUMN two_factor_attempts SMALLINT NOT NULL DEFAULT 0;
@@ -0,0 +1,24 @@
+-- Add migration script here
+
+ALTER TABLE users
+    ADD COLUMN email_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+/* accounts created before verification existed are trusted */
+UPDATE users
+SET email_verified = TRUE;
+
+CREATE TABLE user_tokens
+(
+    id         UUID PRIMARY KEY,
+    user_id    UUID        NOT NULL,
+    kind       VARCHAR     NOT NULL,
+    token_hash VARCHAR     NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at    TIMESTAMPTZ,
+
+    CONSTRAINT fk_user_id
+        FOREIGN KEY (user_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE
+)
@@ -0,0 +1,12 @@
+-- Add migration script here
+
+CREATE TABLE login_attempts
+(
+    scope           VARCHAR     NOT NULL,
+    key             VARCHAR     NOT NULL,
+    failures        INTEGER     NOT NULL DEFAULT 0,
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_until    TIMESTAMPTZ,
+
+    PRIMARY KEY (scope, key)
+)
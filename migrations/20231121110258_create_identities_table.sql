@@ -0,0 +1,35 @@
+-- Add migration script here
+
+CREATE TABLE identities
+(
+    id            UUID PRIMARY KEY,
+    user_id       UUID        NOT NULL,
+    provider      VARCHAR     NOT NULL,
+    subject       VARCHAR     NOT NULL,
+    email         VARCHAR,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_login_at TIMESTAMPTZ,
+
+    CONSTRAINT fk_user_id
+        FOREIGN KEY (user_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE,
+
+    CONSTRAINT unique_identity_provider_subject
+        UNIQUE (provider, subject)
+);
+
+CREATE TABLE oidc_login_states
+(
+    state_hash    VARCHAR PRIMARY KEY,
+    provider      VARCHAR     NOT NULL,
+    code_verifier VARCHAR     NOT NULL,
+    user_id       UUID,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at    TIMESTAMPTZ NOT NULL,
+
+    CONSTRAINT fk_user_id
+        FOREIGN KEY (user_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE
+)
@@ -0,0 +1,29 @@
+-- Add migration script here
+
+ALTER TABLE sessions
+    ADD COLUMN impersonator_id         UUID,
+    ADD COLUMN impersonator_session_id UUID,
+    ADD CONSTRAINT fk_impersonator_id
+        FOREIGN KEY (impersonator_id)
+            REFERENCES users (id)
+            ON DELETE CASCADE,
+    ADD CONSTRAINT fk_impersonator_session_id
+        FOREIGN KEY (impersonator_session_id)
+            REFERENCES sessions (id)
+            ON DELETE CASCADE;
+
+/* no foreign keys, the trail has to outlive the sessions and users it mentions */
+CREATE TABLE impersonation_audit_log
+(
+    id              UUID PRIMARY KEY,
+    session_id      UUID        NOT NULL,
+    impersonator_id UUID        NOT NULL,
+    user_id         UUID        NOT NULL,
+    method          VARCHAR     NOT NULL,
+    path            VARCHAR     NOT NULL,
+    allowed         BOOLEAN     NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX impersonation_audit_log_user_id_idx
+    ON impersonation_audit_log (user_id, created_at);
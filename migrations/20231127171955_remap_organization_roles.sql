@@ -0,0 +1,9 @@
+-- Add migration script here
+
+/* owners used to be stored as 2 and every other member as 0 with full write access,
+   the scale is now viewer 0, developer 1, admin 2, owner 3 */
+UPDATE organization_members
+SET role = CASE WHEN role >= 2 THEN 3 ELSE 1 END;
+
+UPDATE organization_api_keys
+SET role = CASE WHEN role >= 2 THEN 3 ELSE 1 END;
@@ -0,0 +1,25 @@
+-- Add migration script here
+
+CREATE TABLE organization_custom_roles
+(
+    id              UUID PRIMARY KEY,
+    organization_id UUID        NOT NULL,
+    name            VARCHAR     NOT NULL,
+    permissions     VARCHAR[]   NOT NULL DEFAULT '{}',
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_organization_id
+        FOREIGN KEY (organization_id)
+            REFERENCES organizations (id)
+            ON DELETE CASCADE,
+
+    CONSTRAINT unique_organization_custom_role_name
+        UNIQUE (organization_id, name)
+);
+
+ALTER TABLE organization_members
+    ADD COLUMN custom_role_id UUID,
+    ADD CONSTRAINT fk_custom_role_id
+        FOREIGN KEY (custom_role_id)
+            REFERENCES organization_custom_roles (id)
+            ON DELETE SET NULL;
@@ -0,0 +1,31 @@
+-- Add migration script here
+
+/*
+ email is NULL for shareable links, anyone holding the token can join until
+ the invitation expires or runs out of uses
+*/
+CREATE TABLE organization_invitations
+(
+    id              UUID PRIMARY KEY,
+    organization_id UUID        NOT NULL,
+    email           VARCHAR,
+    token_hash      VARCHAR     NOT NULL UNIQUE,
+    role            SMALLINT    NOT NULL DEFAULT 1,
+    invited_by      UUID,
+    max_uses        INTEGER,
+    uses            INTEGER     NOT NULL DEFAULT 0,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at      TIMESTAMPTZ NOT NULL,
+
+    CONSTRAINT fk_organization_id
+        FOREIGN KEY (organization_id)
+            REFERENCES organizations (id)
+            ON DELETE CASCADE,
+
+    CONSTRAINT fk_invited_by
+        FOREIGN KEY (invited_by)
+            REFERENCES users (id)
+            ON DELETE SET NULL
+);
+
+CREATE INDEX organization_invitations_email_idx ON organization_invitations (LOWER(email));
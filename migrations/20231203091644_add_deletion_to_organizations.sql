@@ -0,0 +1,28 @@
+-- Add migration script here
+
+/*
+ deleting an organization only schedules it, cluster and bridge resources are torn down
+ once the grace period is over and the row is removed after that succeeded
+*/
+ALTER TABLE organizations
+    ADD COLUMN deletion_scheduled_at TIMESTAMPTZ,
+    ADD COLUMN deletion_requested_by UUID,
+    ADD CONSTRAINT fk_deletion_requested_by
+        FOREIGN KEY (deletion_requested_by)
+            REFERENCES users (id)
+            ON DELETE SET NULL;
+
+/* bridges.organization_id is NOT NULL, SET NULL could never succeed */
+ALTER TABLE bridges
+    DROP CONSTRAINT fk_organization_id,
+    ADD CONSTRAINT fk_organization_id
+        FOREIGN KEY (organization_id)
+            REFERENCES organizations (id)
+            ON DELETE CASCADE;
+
+ALTER TABLE proxies
+    DROP CONSTRAINT fk_organization_id,
+    ADD CONSTRAINT fk_organization_id
+        FOREIGN KEY (organization_id)
+            REFERENCES organizations (id)
+            ON DELETE CASCADE;
@@ -0,0 +1,22 @@
+-- Add migration script here
+
+/*
+ the namespace used to be derived from the slug, existing organizations keep their
+ namespace name and new ones get one keyed by the organization id so slugs can change
+*/
+ALTER TABLE organizations
+    ADD COLUMN namespace     VARCHAR,
+    ADD COLUMN display_name  VARCHAR,
+    ADD COLUMN description   VARCHAR,
+    ADD COLUMN avatar_url    VARCHAR,
+    ADD COLUMN contact_email VARCHAR;
+
+UPDATE organizations
+SET namespace    = 'org-' || slug,
+    display_name = slug;
+
+ALTER TABLE organizations
+    ALTER COLUMN namespace SET NOT NULL,
+    ALTER COLUMN display_name SET NOT NULL,
+    ADD CONSTRAINT unique_organization_namespace
+        UNIQUE (namespace);
@@ -0,0 +1,18 @@
+-- Add migration script here
+
+/* organizations without a row use the defaults from consts.rs */
+CREATE TABLE organization_quotas
+(
+    organization_id     UUID PRIMARY KEY,
+    max_proxies         INTEGER     NOT NULL,
+    max_proxy_templates INTEGER     NOT NULL,
+    max_bridges         INTEGER     NOT NULL,
+    cpu_millis          BIGINT      NOT NULL,
+    memory_mib          BIGINT      NOT NULL,
+    updated_at          TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_organization_id
+        FOREIGN KEY (organization_id)
+            REFERENCES organizations (id)
+            ON DELETE CASCADE
+);
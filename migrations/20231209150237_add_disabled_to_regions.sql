@@ -0,0 +1,16 @@
+-- Add migration script here
+
+/* disabled regions keep serving their organizations but accept no new ones */
+ALTER TABLE regions
+    ADD COLUMN disabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE regions
+    RENAME CONSTRAINT regions_slug_key TO unique_region_slug;
+
+/* SET NULL could never apply to the NOT NULL column, a region in use must not be deleted */
+ALTER TABLE organizations
+    DROP CONSTRAINT fk_region_id,
+    ADD CONSTRAINT fk_region_id
+        FOREIGN KEY (region_id)
+            REFERENCES regions (id)
+            ON DELETE RESTRICT;
@@ -0,0 +1,32 @@
+-- Add migration script here
+
+/* tag is the value of the kube.ork.gg/tier label on the nodes that run a tier, empty for any node */
+ALTER TABLE tiers
+    ADD COLUMN display_name       VARCHAR NOT NULL DEFAULT '',
+    ADD COLUMN cpu_millis         BIGINT  NOT NULL DEFAULT 1000,
+    ADD COLUMN memory_mib         BIGINT  NOT NULL DEFAULT 1024,
+    ADD COLUMN disk_mib           BIGINT  NOT NULL DEFAULT 10240,
+    ADD COLUMN hourly_price_cents INTEGER NOT NULL DEFAULT 0;
+
+UPDATE tiers
+SET display_name       = 'Ryzen 9 7900X',
+    cpu_millis         = 2000,
+    memory_mib         = 4096,
+    disk_mib           = 20480,
+    hourly_price_cents = 4
+WHERE slug = 'r9-7900x';
+
+ALTER TABLE tiers
+    ALTER COLUMN display_name DROP DEFAULT,
+    ALTER COLUMN cpu_millis DROP DEFAULT,
+    ALTER COLUMN memory_mib DROP DEFAULT,
+    ALTER COLUMN disk_mib DROP DEFAULT,
+    ALTER COLUMN hourly_price_cents DROP DEFAULT;
+
+/* templates without a tier run with the namespace LimitRange defaults on any node */
+ALTER TABLE proxy_templates
+    ADD COLUMN tier_id UUID,
+    ADD CONSTRAINT fk_tier_id
+        FOREIGN KEY (tier_id)
+            REFERENCES tiers (id)
+            ON DELETE SET NULL;
@@ -0,0 +1,12 @@
+-- Add migration script here
+
+/* what the login state was started for, login, link or delete_account */
+ALTER TABLE oidc_login_states
+    ADD COLUMN intent VARCHAR NOT NULL DEFAULT 'login';
+
+UPDATE oidc_login_states
+SET intent = 'link'
+WHERE user_id IS NOT NULL;
+
+ALTER TABLE oidc_login_states
+    ALTER COLUMN intent DROP DEFAULT;
@@ -0,0 +1,12 @@
+-- Add migration script here
+
+/*
+ The id is the session cookie value, sessions are listed and revoked by a separate public id.
+ */
+ALTER TABLE sessions
+    ADD COLUMN public_id UUID NOT NULL DEFAULT gen_random_uuid();
+
+ALTER TABLE sessions
+    ALTER COLUMN public_id DROP DEFAULT;
+
+CREATE UNIQUE INDEX idx_sessions_public_id ON sessions (public_id);
@@ -1,6 +1,7 @@
 use ork_bridge_service::domains::namespace::{CreateNamespaceData, Namespace};
 use ork_bridge_service::domains::proxy::{CreateProxyData, Proxy};
 use reqwest::StatusCode;
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct BridgeServiceClient {
@@ -16,6 +17,15 @@ impl BridgeServiceClient {
         }
     }
 
+    // any answer short of a server error means the service is up
+    pub async fn ping(&self) -> BridgeServiceResult<()> {
+        match self.reqwest.get(&self.base_path).send().await {
+            Ok(res) if !res.status().is_server_error() => Ok(()),
+            Ok(res) => Err(BridgeServiceError::Unknown(res.status().to_string())),
+            Err(err) => Err(BridgeServiceError::Unknown(err.to_string())),
+        }
+    }
+
     pub async fn create_namespace(
         &self,
         data: &CreateNamespaceData,
@@ -51,6 +61,27 @@ impl BridgeServiceClient {
             Err(err) => Err(BridgeServiceError::Unknown(err.to_string())),
         }
     }
+
+    pub async fn delete_namespace(&self, namespace_id: &Uuid) -> BridgeServiceResult<()> {
+        self.delete(format!("{}/namespaces/{}", &self.base_path, namespace_id))
+            .await
+    }
+
+    pub async fn delete_proxy(&self, proxy_id: &Uuid) -> BridgeServiceResult<()> {
+        self.delete(format!("{}/proxies/{}", &self.base_path, proxy_id))
+            .await
+    }
+
+    // deleting something the bridge service doesn't know is a success, teardowns get retried
+    async fn delete(&self, url: String) -> BridgeServiceResult<()> {
+        match self.reqwest.delete(url).send().await {
+            Ok(res) => match res.status() {
+                StatusCode::OK | StatusCode::NO_CONTENT | StatusCode::NOT_FOUND => Ok(()),
+                _ => Err(BridgeServiceError::Unknown(res.status().to_string())),
+            },
+            Err(err) => Err(BridgeServiceError::Unknown(err.to_string())),
+        }
+    }
 }
 
 pub type BridgeServiceResult<R> = Result<R, BridgeServiceError>;
@@ -1,27 +1,60 @@
-use crate::consts::AsNamespaceName;
+use crate::consts::{
+    PROXY_CPU_MILLIS, PROXY_MEMORY_MIB, PROXY_POD_DELETION_POLL, PROXY_POD_DELETION_TIMEOUT,
+};
 use crate::domains::organization::Organization;
+use crate::domains::organization_quota::OrganizationQuota;
 use crate::domains::proxy::Proxy;
 use crate::domains::proxy_template::ProxyTemplate;
+use crate::domains::region_capacity::NodeCapacity;
+use crate::domains::tier::Tier;
 use k8s_openapi::api::core::v1::{
-    Container, Namespace, Pod, PodSpec, Service, ServicePort, ServiceSpec,
+    Container, LimitRange, LimitRangeItem, LimitRangeSpec, Namespace, Node, Pod, PodSpec,
+    ResourceQuota, ResourceQuotaSpec, ResourceRequirements, Service, ServicePort, ServiceSpec,
+};
+use k8s_openapi::api::networking::v1::{
+    IPBlock, NetworkPolicy, NetworkPolicyEgressRule, NetworkPolicyIngressRule, NetworkPolicyPeer,
+    NetworkPolicyPort, NetworkPolicySpec,
+};
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{
+    LabelSelector, LabelSelectorRequirement, ObjectMeta,
 };
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
 use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
-use kube::api::PostParams;
+use kube::api::{DeleteParams, ListParams, Patch, PatchParams, PostParams};
 use kube::Api;
 use maplit::btreemap;
+use std::collections::{BTreeMap, HashMap};
+
+const ORGANIZATION_QUOTA_NAME: &str = "ork-quota";
+const PROXY_LABEL: &str = "kube.ork.gg/proxies";
+const PROXY_CONTAINER_PORT: i32 = 25577;
+const NAMESPACE_NAME_LABEL: &str = "kubernetes.io/metadata.name";
+const TIER_NODE_LABEL: &str = "kube.ork.gg/tier";
 
 #[derive(Clone)]
 pub struct KubeWrappedClient {
     client: kube::Client,
+    bridge_namespace: String,
 }
 
 impl KubeWrappedClient {
-    pub fn new(client: kube::Client) -> Self {
-        Self { client }
+    pub fn new(client: kube::Client, bridge_namespace: String) -> Self {
+        Self {
+            client,
+            bridge_namespace,
+        }
     }
 
-    pub async fn create_organization_namespace(&self, organization: &Organization) {
+    pub async fn ping(&self) -> kube::Result<()> {
+        self.client.apiserver_version().await?;
+
+        Ok(())
+    }
+
+    pub async fn create_organization_namespace(
+        &self,
+        organization: &Organization,
+    ) -> kube::Result<()> {
         let namespaces: Api<Namespace> = Api::all(self.client.clone());
 
         namespaces
@@ -29,54 +62,346 @@ impl KubeWrappedClient {
                 &PostParams::default(),
                 &Namespace {
                     metadata: ObjectMeta {
-                        name: Some(organization.slug.as_namespace_name()),
+                        name: Some(organization.namespace.clone()),
                         ..Default::default()
                     },
                     spec: None,
                     status: None,
                 },
             )
-            .await
-            .unwrap();
+            .await?;
 
-        // TODO: HANDLE ERRORS
+        Ok(())
     }
 
-    pub async fn create_proxy_pod(
+    pub async fn apply_organization_quota(
         &self,
         organization: &Organization,
-        template: &ProxyTemplate,
-        proxy: &Proxy,
-    ) {
-        let pods: Api<Pod> =
-            Api::namespaced(self.client.clone(), &organization.slug.as_namespace_name());
+        quota: &OrganizationQuota,
+    ) -> kube::Result<()> {
+        let params = PatchParams::apply("ork").force();
+        let cpu = Quantity(format!("{}m", quota.cpu_millis));
+        let memory = Quantity(format!("{}Mi", quota.memory_mib));
 
-        pods.create(
-            &PostParams::default(),
-            &Pod {
-                metadata: ObjectMeta {
-                    name: Some(proxy.slug.clone()),
-                    labels: Some(btreemap! {
-                        "kube.ork.gg/proxies".to_string() => proxy.id.to_string()
+        let resource_quotas: Api<ResourceQuota> =
+            Api::namespaced(self.client.clone(), &organization.namespace);
+        resource_quotas
+            .patch(
+                ORGANIZATION_QUOTA_NAME,
+                &params,
+                &Patch::Apply(ResourceQuota {
+                    metadata: ObjectMeta {
+                        name: Some(ORGANIZATION_QUOTA_NAME.to_string()),
+                        ..Default::default()
+                    },
+                    spec: Some(ResourceQuotaSpec {
+                        hard: Some(btreemap! {
+                            "pods".to_string() => Quantity(quota.max_proxies.to_string()),
+                            "requests.cpu".to_string() => cpu.clone(),
+                            "limits.cpu".to_string() => cpu,
+                            "requests.memory".to_string() => memory.clone(),
+                            "limits.memory".to_string() => memory,
+                        }),
+                        ..Default::default()
                     }),
+                    status: None,
+                }),
+            )
+            .await?;
+
+        // the quota above only admits pods with requests and limits, tierless proxies get them
+        // from here while tiered ones bring their own
+        let proxy_resources = btreemap! {
+            "cpu".to_string() => Quantity(format!("{}m", PROXY_CPU_MILLIS)),
+            "memory".to_string() => Quantity(format!("{}Mi", PROXY_MEMORY_MIB)),
+        };
+        let limit_ranges: Api<LimitRange> =
+            Api::namespaced(self.client.clone(), &organization.namespace);
+        limit_ranges
+            .patch(
+                ORGANIZATION_QUOTA_NAME,
+                &params,
+                &Patch::Apply(LimitRange {
+                    metadata: ObjectMeta {
+                        name: Some(ORGANIZATION_QUOTA_NAME.to_string()),
+                        ..Default::default()
+                    },
+                    spec: Some(LimitRangeSpec {
+                        limits: vec![LimitRangeItem {
+                            type_: "Container".to_string(),
+                            default: Some(proxy_resources.clone()),
+                            default_request: Some(proxy_resources),
+                            ..Default::default()
+                        }],
+                    }),
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Denies all traffic in the organization namespace except between its own pods, to the
+    /// cluster DNS, with the bridge service and towards the proxy port from the NodePort ingress.
+    /// Policies select pods by label, so proxies are covered as soon as they are created.
+    pub async fn apply_organization_network_policies(
+        &self,
+        organization: &Organization,
+    ) -> kube::Result<()> {
+        let params = PatchParams::apply("ork").force();
+        let network_policies: Api<NetworkPolicy> =
+            Api::namespaced(self.client.clone(), &organization.namespace);
+
+        for network_policy in self.organization_network_policies() {
+            let name = network_policy.metadata.name.clone().unwrap();
+            network_policies
+                .patch(&name, &params, &Patch::Apply(network_policy))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    fn organization_network_policies(&self) -> Vec<NetworkPolicy> {
+        let network_policy =
+            |name: &str, pod_selector: LabelSelector, spec: NetworkPolicySpec| NetworkPolicy {
+                metadata: ObjectMeta {
+                    name: Some(name.to_string()),
                     ..Default::default()
                 },
-                spec: Some(PodSpec {
-                    containers: vec![Container {
-                        name: proxy.slug.clone(),
-                        image: Some(template.image.clone()),
-                        ..Default::default()
-                    }],
+                spec: Some(NetworkPolicySpec {
+                    pod_selector,
+                    ..spec
+                }),
+                ..Default::default()
+            };
+        let namespace_peer =
+            |namespace: &str, pod_selector: Option<LabelSelector>| NetworkPolicyPeer {
+                namespace_selector: Some(LabelSelector {
+                    match_labels: Some(btreemap! {
+                        NAMESPACE_NAME_LABEL.to_string() => namespace.to_string()
+                    }),
                     ..Default::default()
                 }),
+                pod_selector,
                 ..Default::default()
-            },
-        )
-        .await
-        .unwrap();
+            };
+        let same_namespace_peer = NetworkPolicyPeer {
+            pod_selector: Some(LabelSelector::default()),
+            ..Default::default()
+        };
+        let dns_ports = ["UDP", "TCP"]
+            .into_iter()
+            .map(|protocol| NetworkPolicyPort {
+                port: Some(IntOrString::Int(53)),
+                protocol: Some(protocol.to_string()),
+                ..Default::default()
+            })
+            .collect();
+
+        vec![
+            network_policy(
+                "ork-default-deny",
+                LabelSelector::default(),
+                NetworkPolicySpec {
+                    policy_types: Some(vec!["Ingress".to_string(), "Egress".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            network_policy(
+                "ork-allow-organization",
+                LabelSelector::default(),
+                NetworkPolicySpec {
+                    ingress: Some(vec![NetworkPolicyIngressRule {
+                        from: Some(vec![same_namespace_peer.clone()]),
+                        ..Default::default()
+                    }]),
+                    egress: Some(vec![NetworkPolicyEgressRule {
+                        to: Some(vec![same_namespace_peer]),
+                        ..Default::default()
+                    }]),
+                    policy_types: Some(vec!["Ingress".to_string(), "Egress".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            network_policy(
+                "ork-allow-dns",
+                LabelSelector::default(),
+                NetworkPolicySpec {
+                    egress: Some(vec![NetworkPolicyEgressRule {
+                        to: Some(vec![namespace_peer(
+                            "kube-system",
+                            Some(LabelSelector {
+                                match_labels: Some(btreemap! {
+                                    "k8s-app".to_string() => "kube-dns".to_string()
+                                }),
+                                ..Default::default()
+                            }),
+                        )]),
+                        ports: Some(dns_ports),
+                    }]),
+                    policy_types: Some(vec!["Egress".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            network_policy(
+                "ork-allow-bridge-service",
+                LabelSelector::default(),
+                NetworkPolicySpec {
+                    ingress: Some(vec![NetworkPolicyIngressRule {
+                        from: Some(vec![namespace_peer(&self.bridge_namespace, None)]),
+                        ..Default::default()
+                    }]),
+                    egress: Some(vec![NetworkPolicyEgressRule {
+                        to: Some(vec![namespace_peer(&self.bridge_namespace, None)]),
+                        ..Default::default()
+                    }]),
+                    policy_types: Some(vec!["Ingress".to_string(), "Egress".to_string()]),
+                    ..Default::default()
+                },
+            ),
+            // NodePort traffic reaches the pod with a node address as source, only the proxy
+            // port is opened and only on proxy pods
+            network_policy(
+                "ork-allow-proxy-ingress",
+                LabelSelector {
+                    match_expressions: Some(vec![LabelSelectorRequirement {
+                        key: PROXY_LABEL.to_string(),
+                        operator: "Exists".to_string(),
+                        values: None,
+                    }]),
+                    ..Default::default()
+                },
+                NetworkPolicySpec {
+                    ingress: Some(vec![NetworkPolicyIngressRule {
+                        from: Some(vec![NetworkPolicyPeer {
+                            ip_block: Some(IPBlock {
+                                cidr: "0.0.0.0/0".to_string(),
+                                except: None,
+                            }),
+                            ..Default::default()
+                        }]),
+                        ports: Some(vec![NetworkPolicyPort {
+                            port: Some(IntOrString::Int(PROXY_CONTAINER_PORT)),
+                            protocol: Some("TCP".to_string()),
+                            ..Default::default()
+                        }]),
+                    }]),
+                    policy_types: Some(vec!["Ingress".to_string()]),
+                    ..Default::default()
+                },
+            ),
+        ]
+    }
+
+    /// Ready, schedulable nodes proxies can land on, with the cpu and memory requested by the
+    /// pods running on them.
+    pub async fn node_capacities(&self) -> kube::Result<Vec<NodeCapacity>> {
+        let nodes: Api<Node> = Api::all(self.client.clone());
+        let pods: Api<Pod> = Api::all(self.client.clone());
+
+        let mut requested: HashMap<String, (i64, i64)> = HashMap::new();
+        let active = ListParams::default().fields("status.phase!=Succeeded,status.phase!=Failed");
+        for pod in pods.list(&active).await? {
+            let Some(spec) = pod.spec else { continue };
+            let Some(node_name) = spec.node_name else {
+                continue;
+            };
+
+            let entry = requested.entry(node_name).or_default();
+            for container in spec.containers {
+                // a container with limits but no requests gets its limits as requests
+                let resources = container.resources.unwrap_or_default();
+                let requests = resources.requests.or(resources.limits).unwrap_or_default();
+                entry.0 += cpu_millis(&requests);
+                entry.1 += memory_mib(&requests);
+            }
+        }
+
+        let capacities = nodes
+            .list(&ListParams::default())
+            .await?
+            .into_iter()
+            .filter(|node| {
+                let schedulable = !node
+                    .spec
+                    .as_ref()
+                    .and_then(|spec| spec.unschedulable)
+                    .unwrap_or(false);
+                let ready = node
+                    .status
+                    .as_ref()
+                    .and_then(|status| status.conditions.as_ref())
+                    .into_iter()
+                    .flatten()
+                    .any(|condition| condition.type_ == "Ready" && condition.status == "True");
+                // proxy pods carry no tolerations, any hard taint keeps them off the node
+                let untainted = node
+                    .spec
+                    .as_ref()
+                    .and_then(|spec| spec.taints.as_ref())
+                    .into_iter()
+                    .flatten()
+                    .all(|taint| taint.effect != "NoSchedule" && taint.effect != "NoExecute");
+                schedulable && ready && untainted
+            })
+            .map(|node| {
+                let name = node.metadata.name.unwrap_or_default();
+                let allocatable = node
+                    .status
+                    .and_then(|status| status.allocatable)
+                    .unwrap_or_default();
+                let (requested_cpu_millis, requested_memory_mib) =
+                    requested.get(&name).copied().unwrap_or_default();
+
+                NodeCapacity {
+                    tier_tag: node
+                        .metadata
+                        .labels
+                        .and_then(|mut labels| labels.remove(TIER_NODE_LABEL)),
+                    allocatable_cpu_millis: cpu_millis(&allocatable),
+                    allocatable_memory_mib: memory_mib(&allocatable),
+                    requested_cpu_millis,
+                    requested_memory_mib,
+                    name,
+                }
+            })
+            .collect();
+
+        Ok(capacities)
+    }
+
+    /// Returns whether the namespace is gone, a namespace keeps terminating for a while after
+    /// its deletion was accepted.
+    pub async fn delete_organization_namespace(
+        &self,
+        organization: &Organization,
+    ) -> kube::Result<bool> {
+        let namespaces: Api<Namespace> = Api::all(self.client.clone());
 
-        let services: Api<Service> =
-            Api::namespaced(self.client.clone(), &organization.slug.as_namespace_name());
+        match namespaces
+            .delete(&organization.namespace, &DeleteParams::foreground())
+            .await
+        {
+            Ok(_) => Ok(false),
+            Err(kube::Error::Api(err)) if err.code == 404 => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn create_proxy_pod(
+        &self,
+        organization: &Organization,
+        template: &ProxyTemplate,
+        tier: Option<&Tier>,
+        proxy: &Proxy,
+    ) -> kube::Result<()> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), &organization.namespace);
+
+        pods.create(&PostParams::default(), &proxy_pod(template, tier, proxy))
+            .await?;
+
+        let services: Api<Service> = Api::namespaced(self.client.clone(), &organization.namespace);
 
         services
             .create(
@@ -88,13 +413,13 @@ impl KubeWrappedClient {
                     },
                     spec: Some(ServiceSpec {
                         selector: Some(btreemap! {
-                            "kube.ork.gg/proxies".to_string() => proxy.id.to_string()
+                            PROXY_LABEL.to_string() => proxy.id.to_string()
                         }),
                         type_: Some("NodePort".to_string()),
                         ports: Some(vec![ServicePort {
                             protocol: Some("TCP".to_string()),
                             port: 25565,
-                            target_port: Some(IntOrString::Int(25577)),
+                            target_port: Some(IntOrString::Int(PROXY_CONTAINER_PORT)),
                             ..Default::default()
                         }]),
                         ..Default::default()
@@ -102,9 +427,214 @@ impl KubeWrappedClient {
                     status: None,
                 },
             )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Bare pods are never restarted by the cluster, the pod is replaced by a new one. It gets a
+    /// generated name as the old one can take a while to terminate.
+    pub async fn restart_proxy_pod(
+        &self,
+        organization: &Organization,
+        template: &ProxyTemplate,
+        tier: Option<&Tier>,
+        proxy: &Proxy,
+    ) -> kube::Result<()> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), &organization.namespace);
+
+        // a terminating pod still counts against the ResourceQuota, at full quota the
+        // replacement would be rejected while the old pod shuts down
+        let selector = proxy_selector(proxy);
+        pods.delete_collection(
+            &DeleteParams {
+                grace_period_seconds: Some(0),
+                ..Default::default()
+            },
+            &selector,
+        )
+        .await?;
+
+        let deadline = tokio::time::Instant::now() + PROXY_POD_DELETION_TIMEOUT;
+        while !pods.list(&selector).await?.items.is_empty() {
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(PROXY_POD_DELETION_POLL).await;
+        }
+
+        let mut pod = proxy_pod(template, tier, proxy);
+        pod.metadata.name = None;
+        pod.metadata.generate_name = Some(format!("{}-", proxy.slug));
+        pods.create(&PostParams::default(), &pod).await?;
+
+        Ok(())
+    }
+
+    /// Deleting a proxy that is already gone from the cluster is a success.
+    pub async fn delete_proxy(
+        &self,
+        organization: &Organization,
+        proxy: &Proxy,
+    ) -> kube::Result<()> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), &organization.namespace);
+        let services: Api<Service> = Api::namespaced(self.client.clone(), &organization.namespace);
+
+        pods.delete_collection(&DeleteParams::default(), &proxy_selector(proxy))
+            .await?;
+
+        match services
+            .delete(&format!("{}-svc", &proxy.slug), &DeleteParams::default())
             .await
-            .unwrap();
+        {
+            Ok(_) => Ok(()),
+            Err(kube::Error::Api(err)) if err.code == 404 => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+// pods are found by label, a restarted proxy's pod no longer has the proxy slug as its name
+fn proxy_selector(proxy: &Proxy) -> ListParams {
+    ListParams::default().labels(&format!("{}={}", PROXY_LABEL, proxy.id))
+}
+
+fn proxy_pod(template: &ProxyTemplate, tier: Option<&Tier>, proxy: &Proxy) -> Pod {
+    // requests equal limits so a tier gets exactly the hardware it advertises
+    let resources = tier.map(|tier| {
+        let resources = btreemap! {
+            "cpu".to_string() => Quantity(format!("{}m", tier.cpu_millis)),
+            "memory".to_string() => Quantity(format!("{}Mi", tier.memory_mib)),
+            "ephemeral-storage".to_string() => Quantity(format!("{}Mi", tier.disk_mib)),
+        };
+        ResourceRequirements {
+            limits: Some(resources.clone()),
+            requests: Some(resources),
+            ..Default::default()
+        }
+    });
+    let node_selector = tier.filter(|tier| !tier.tag.is_empty()).map(|tier| {
+        btreemap! {
+            TIER_NODE_LABEL.to_string() => tier.tag.clone()
+        }
+    });
+
+    Pod {
+        metadata: ObjectMeta {
+            name: Some(proxy.slug.clone()),
+            labels: Some(btreemap! {
+                PROXY_LABEL.to_string() => proxy.id.to_string()
+            }),
+            ..Default::default()
+        },
+        spec: Some(PodSpec {
+            containers: vec![Container {
+                name: proxy.slug.clone(),
+                image: Some(template.image.clone()),
+                resources,
+                ..Default::default()
+            }],
+            node_selector,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn cpu_millis(resources: &BTreeMap<String, Quantity>) -> i64 {
+    resources
+        .get("cpu")
+        .and_then(parse_quantity)
+        .map(|cores| (cores * 1000.0).ceil() as i64)
+        .unwrap_or_default()
+}
+
+fn memory_mib(resources: &BTreeMap<String, Quantity>) -> i64 {
+    resources
+        .get("memory")
+        .and_then(parse_quantity)
+        .map(|bytes| (bytes / (1024.0 * 1024.0)).ceil() as i64)
+        .unwrap_or_default()
+}
+
+// quantities are a decimal number with an optional binary (Ki), decimal (k, m) or exponent (e3)
+// suffix, see k8s.io/apimachinery/pkg/api/resource
+fn parse_quantity(quantity: &Quantity) -> Option<f64> {
+    let value = quantity.0.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '+' || c == '-'))
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "Ki" => 1024f64,
+        "Mi" => 1024f64.powi(2),
+        "Gi" => 1024f64.powi(3),
+        "Ti" => 1024f64.powi(4),
+        "Pi" => 1024f64.powi(5),
+        "Ei" => 1024f64.powi(6),
+        "n" => 1e-9,
+        "u" => 1e-6,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        exponent => 10f64.powi(exponent.strip_prefix(['e', 'E'])?.parse().ok()?),
+    };
+
+    Some(number * multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantity(value: &str) -> Quantity {
+        Quantity(value.to_string())
+    }
+
+    #[test]
+    fn parses_quantity_suffixes() {
+        let quantities = [
+            ("500m", 0.5),
+            ("1.5Gi", 1.5 * 1024f64.powi(3)),
+            ("128974848", 128974848.0),
+            ("129e6", 129e6),
+            ("123Mi", 123.0 * 1024f64.powi(2)),
+            ("1E", 1e18),
+        ];
+
+        for (value, expected) in quantities {
+            assert_eq!(
+                parse_quantity(&quantity(value)),
+                Some(expected),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_suffixes() {
+        assert_eq!(parse_quantity(&quantity("12x")), None);
+        assert_eq!(parse_quantity(&quantity("Mi")), None);
+    }
+
+    #[test]
+    fn rounds_resources_up() {
+        let resources = btreemap! {
+            "cpu".to_string() => quantity("250m"),
+            "memory".to_string() => quantity("129e6"),
+        };
 
-        // TODO: HANDLE ERRORS
+        assert_eq!(cpu_millis(&resources), 250);
+        // 129e6 bytes is a little over 123Mi
+        assert_eq!(memory_mib(&resources), 124);
+        assert_eq!(cpu_millis(&BTreeMap::new()), 0);
     }
 }
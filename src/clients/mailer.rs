@@ -0,0 +1,148 @@
+use std::path::PathBuf;
+
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use time::OffsetDateTime;
+use tracing::info;
+
+#[derive(Clone, Debug)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Mail {
+    pub fn email_verification(to: &String, link: &String) -> Self {
+        Self {
+            to: to.clone(),
+            subject: "Verify your ork email address".to_string(),
+            body: format!(
+                "Welcome to ork!\n\nConfirm your email address by opening the link below:\n\n{}\n\nIf you did not create an account you can ignore this email.",
+                link
+            ),
+        }
+    }
+
+    pub fn password_reset(to: &String, link: &String) -> Self {
+        Self {
+            to: to.clone(),
+            subject: "Reset your ork password".to_string(),
+            body: format!(
+                "Someone asked to reset the password of your ork account.\n\nChoose a new password by opening the link below:\n\n{}\n\nIf it wasn't you, you can ignore this email.",
+                link
+            ),
+        }
+    }
+
+    pub fn organization_invitation(
+        to: &String,
+        organization_slug: &String,
+        inviter_name: &String,
+        link: &String,
+    ) -> Self {
+        Self {
+            to: to.clone(),
+            subject: format!("You have been invited to {} on ork", organization_slug),
+            body: format!(
+                "{} invited you to join the {} organization on ork.\n\nAccept the invitation by opening the link below:\n\n{}\n\nIf you weren't expecting this invitation, you can ignore this email.",
+                inviter_name, organization_slug, link
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: &Mail) -> MailerResult<()>;
+}
+
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(url: &str, from: &str) -> MailerResult<Self> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::from_url(url)
+            .map_err(|err| MailerError::Unknown(err.to_string()))?
+            .build();
+        let from = from
+            .parse()
+            .map_err(|_| MailerError::InvalidAddress(from.to_string()))?;
+
+        Ok(Self { transport, from })
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: &Mail) -> MailerResult<()> {
+        let to: Mailbox = mail
+            .to
+            .parse()
+            .map_err(|_| MailerError::InvalidAddress(mail.to.clone()))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&mail.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(mail.body.clone())
+            .map_err(|err| MailerError::Unknown(err.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|err| MailerError::Unknown(err.to_string()))?;
+
+        Ok(())
+    }
+}
+
+// used for local development and tests, mails are logged and optionally written to a directory
+pub struct LogMailer {
+    directory: Option<PathBuf>,
+}
+
+impl LogMailer {
+    pub fn new(directory: Option<PathBuf>) -> Self {
+        Self { directory }
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, mail: &Mail) -> MailerResult<()> {
+        info!("mail to {}: {}\n{}", mail.to, mail.subject, mail.body);
+
+        if let Some(directory) = &self.directory {
+            let file_name = format!(
+                "{}-{}.txt",
+                OffsetDateTime::now_utc().unix_timestamp_nanos(),
+                mail.to
+            );
+            let contents = format!(
+                "To: {}\nSubject: {}\n\n{}\n",
+                mail.to, mail.subject, mail.body
+            );
+
+            tokio::fs::write(directory.join(file_name), contents)
+                .await
+                .map_err(|err| MailerError::Unknown(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub type MailerResult<R> = Result<R, MailerError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailerError {
+    #[error("invalid address: {0}")]
+    InvalidAddress(String),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
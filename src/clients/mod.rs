@@ -1,2 +1,4 @@
 pub mod bridge_service;
 pub mod kube;
+pub mod mailer;
+pub mod oidc;
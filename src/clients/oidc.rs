@@ -0,0 +1,619 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::StatusCode;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+use tracing::error;
+use url::Url;
+
+#[derive(Clone, Debug)]
+pub struct OidcProvider {
+    pub name: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub authorization_url: String,
+    pub token_url: String,
+    pub userinfo_url: String,
+    pub scopes: String,
+    pub claims: OidcClaimNames,
+}
+
+/// Userinfo fields to read, plain OAuth2 providers (GitHub, Discord) don't follow the OIDC names.
+#[derive(Clone, Debug)]
+pub struct OidcClaimNames {
+    pub subject: String,
+    pub email: String,
+    pub email_verified: String,
+    pub name: String,
+}
+
+impl Default for OidcClaimNames {
+    fn default() -> Self {
+        Self {
+            subject: "sub".to_string(),
+            email: "email".to_string(),
+            email_verified: "email_verified".to_string(),
+            name: "name".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExternalIdentity {
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub name: Option<String>,
+    /// Filled in from the tokens, userinfo doesn't carry it.
+    pub auth_time: Option<OffsetDateTime>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OidcTokens {
+    pub access_token: String,
+    /// When the user last signed in at the provider, only OIDC providers send an id_token.
+    pub auth_time: Option<OffsetDateTime>,
+}
+
+#[derive(Clone)]
+pub struct OidcClient {
+    providers: Arc<HashMap<String, OidcProvider>>,
+    reqwest: reqwest::Client,
+}
+
+impl OidcClient {
+    pub fn new(providers: Vec<OidcProvider>) -> Self {
+        Self {
+            providers: Arc::new(
+                providers
+                    .into_iter()
+                    .map(|provider| (provider.name.clone(), provider))
+                    .collect(),
+            ),
+            reqwest: reqwest::Client::builder().build().unwrap(),
+        }
+    }
+
+    /// Reads `ORK_OIDC_PROVIDERS` (comma separated names) and the `ORK_OIDC_<NAME>_*` settings
+    /// of each provider, endpoints are discovered from `_ISSUER` unless set explicitly.
+    pub async fn from_env() -> Self {
+        let reqwest = reqwest::Client::builder().build().unwrap();
+        let mut providers = vec![];
+
+        let names = std::env::var("ORK_OIDC_PROVIDERS").unwrap_or_default();
+        for name in names
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match provider_from_env(&reqwest, name).await {
+                Ok(provider) => providers.push(provider),
+                Err(err) => error!("skipping oidc provider {}: {}", name, err),
+            }
+        }
+
+        Self::new(providers)
+    }
+
+    pub fn provider_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.providers.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn authorization_url(
+        &self,
+        provider: &str,
+        state: &str,
+        code_verifier: &str,
+        redirect_uri: &str,
+        reauthenticate: bool,
+    ) -> OidcClientResult<String> {
+        let provider = self.provider(provider)?;
+
+        let mut url = Url::parse(&provider.authorization_url)
+            .map_err(|err| OidcClientError::InvalidResponse(err.to_string()))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &provider.client_id)
+            .append_pair("redirect_uri", redirect_uri)
+            .append_pair("scope", &provider.scopes)
+            .append_pair("state", state)
+            .append_pair("code_challenge", &code_challenge(code_verifier))
+            .append_pair("code_challenge_method", "S256");
+        if reauthenticate {
+            url.query_pairs_mut()
+                .append_pair("prompt", "login")
+                .append_pair("max_age", "0");
+        }
+
+        Ok(url.to_string())
+    }
+
+    pub async fn exchange_code(
+        &self,
+        provider: &str,
+        code: &str,
+        code_verifier: &str,
+        redirect_uri: &str,
+    ) -> OidcClientResult<OidcTokens> {
+        let provider = self.provider(provider)?;
+
+        let res = self
+            .reqwest
+            .post(&provider.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", &provider.client_id),
+                ("client_secret", &provider.client_secret),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|err| OidcClientError::Unknown(err.to_string()))?;
+
+        match res.status() {
+            StatusCode::OK => {
+                let token: TokenResponse = res
+                    .json()
+                    .await
+                    .map_err(|err| OidcClientError::InvalidResponse(err.to_string()))?;
+                Ok(OidcTokens {
+                    auth_time: match &token.id_token {
+                        Some(id_token) => id_token_auth_time(id_token)?,
+                        None => None,
+                    },
+                    access_token: token.access_token,
+                })
+            }
+            status => Err(OidcClientError::InvalidResponse(status.to_string())),
+        }
+    }
+
+    pub async fn fetch_identity(
+        &self,
+        provider: &str,
+        access_token: &str,
+    ) -> OidcClientResult<ExternalIdentity> {
+        let provider = self.provider(provider)?;
+
+        let res = self
+            .reqwest
+            .get(&provider.userinfo_url)
+            .bearer_auth(access_token)
+            .header(reqwest::header::ACCEPT, "application/json")
+            // GitHub rejects requests without one
+            .header(reqwest::header::USER_AGENT, "ork")
+            .send()
+            .await
+            .map_err(|err| OidcClientError::Unknown(err.to_string()))?;
+
+        if res.status() != StatusCode::OK {
+            return Err(OidcClientError::InvalidResponse(res.status().to_string()));
+        }
+
+        let claims: Value = res
+            .json()
+            .await
+            .map_err(|err| OidcClientError::InvalidResponse(err.to_string()))?;
+
+        let subject = claim_string(&claims, &provider.claims.subject).ok_or_else(|| {
+            OidcClientError::InvalidResponse(format!(
+                "missing subject claim {}",
+                provider.claims.subject
+            ))
+        })?;
+
+        Ok(ExternalIdentity {
+            subject,
+            email: claim_string(&claims, &provider.claims.email),
+            email_verified: match claims.get(&provider.claims.email_verified) {
+                Some(Value::Bool(verified)) => *verified,
+                Some(Value::String(verified)) => verified == "true",
+                _ => false,
+            },
+            name: claim_string(&claims, &provider.claims.name),
+            auth_time: None,
+        })
+    }
+
+    fn provider(&self, name: &str) -> OidcClientResult<&OidcProvider> {
+        self.providers
+            .get(name)
+            .ok_or(OidcClientError::UnknownProvider)
+    }
+}
+
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+// the id_token comes straight from the token endpoint over TLS, which OIDC Core 3.1.3.7 accepts
+// in place of checking its signature
+fn id_token_auth_time(id_token: &str) -> OidcClientResult<Option<OffsetDateTime>> {
+    let invalid = || OidcClientError::InvalidResponse("malformed id_token".to_string());
+
+    let payload = id_token.split('.').nth(1).ok_or_else(invalid)?;
+    let claims: Value =
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?)
+            .map_err(|_| invalid())?;
+
+    match claims.get("auth_time") {
+        Some(auth_time) => {
+            let auth_time = auth_time.as_i64().ok_or_else(invalid)?;
+            Ok(Some(
+                OffsetDateTime::from_unix_timestamp(auth_time).map_err(|_| invalid())?,
+            ))
+        }
+        None => Ok(None),
+    }
+}
+
+// subjects are numbers for some providers
+fn claim_string(claims: &Value, name: &str) -> Option<String> {
+    match claims.get(name)? {
+        Value::String(value) if !value.is_empty() => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+async fn provider_from_env(
+    reqwest: &reqwest::Client,
+    name: &str,
+) -> OidcClientResult<OidcProvider> {
+    let prefix = format!("ORK_OIDC_{}_", name.to_uppercase());
+    let var = |key: &str| std::env::var(format!("{}{}", prefix, key)).ok();
+    let required = |key: &str| {
+        var(key).ok_or_else(|| OidcClientError::Unknown(format!("{}{} is not set", prefix, key)))
+    };
+
+    let discovery = match var("ISSUER") {
+        Some(issuer) => Some(discover(reqwest, &issuer).await?),
+        None => None,
+    };
+    let endpoint = |key: &str, discovered: Option<&String>| {
+        var(key)
+            .or_else(|| discovered.cloned())
+            .ok_or_else(|| OidcClientError::Unknown(format!("{}{} is not set", prefix, key)))
+    };
+
+    let defaults = OidcClaimNames::default();
+
+    Ok(OidcProvider {
+        name: name.to_string(),
+        client_id: required("CLIENT_ID")?,
+        client_secret: required("CLIENT_SECRET")?,
+        authorization_url: endpoint(
+            "AUTHORIZATION_URL",
+            discovery.as_ref().map(|d| &d.authorization_endpoint),
+        )?,
+        token_url: endpoint("TOKEN_URL", discovery.as_ref().map(|d| &d.token_endpoint))?,
+        userinfo_url: endpoint(
+            "USERINFO_URL",
+            discovery.as_ref().map(|d| &d.userinfo_endpoint),
+        )?,
+        scopes: var("SCOPES").unwrap_or_else(|| "openid email profile".to_string()),
+        claims: OidcClaimNames {
+            subject: var("SUBJECT_CLAIM").unwrap_or(defaults.subject),
+            email: var("EMAIL_CLAIM").unwrap_or(defaults.email),
+            email_verified: var("EMAIL_VERIFIED_CLAIM").unwrap_or(defaults.email_verified),
+            name: var("NAME_CLAIM").unwrap_or(defaults.name),
+        },
+    })
+}
+
+async fn discover(reqwest: &reqwest::Client, issuer: &str) -> OidcClientResult<DiscoveryDocument> {
+    let res = reqwest
+        .get(format!(
+            "{}/.well-known/openid-configuration",
+            issuer.trim_end_matches('/')
+        ))
+        .send()
+        .await
+        .map_err(|err| OidcClientError::Unknown(err.to_string()))?;
+
+    match res.status() {
+        StatusCode::OK => res
+            .json()
+            .await
+            .map_err(|err| OidcClientError::InvalidResponse(err.to_string())),
+        status => Err(OidcClientError::InvalidResponse(status.to_string())),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct DiscoveryDocument {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(serde::Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    id_token: Option<String>,
+}
+
+pub type OidcClientResult<R> = Result<R, OidcClientError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcClientError {
+    #[error("unknown provider")]
+    UnknownProvider,
+    #[error("invalid provider response: {0}")]
+    InvalidResponse(String),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::extract::State;
+    use axum::http::header::AUTHORIZATION;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::{get, post};
+    use axum::{Form, Json};
+    use serde_json::{json, Value};
+    use url::Url;
+
+    use super::*;
+
+    const CLIENT_ID: &str = "ork-client";
+    const CLIENT_SECRET: &str = "ork-secret";
+    const REDIRECT_URI: &str = "http://localhost:8080/auth/oidc/mock/callback";
+    const ACCESS_TOKEN: &str = "mock-access-token";
+    const AUTH_TIME: i64 = 1702461600;
+
+    /// Serves discovery, token and userinfo endpoints on a random local port. Like a real
+    /// issuer it ties the code to the PKCE challenge, the code it hands out is the challenge.
+    async fn mock_issuer() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let router = axum::Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/token", post(token))
+            .route("/userinfo", get(userinfo))
+            .with_state(issuer.clone());
+
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        issuer
+    }
+
+    async fn discovery(State(issuer): State<String>) -> Json<Value> {
+        Json(json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "userinfo_endpoint": format!("{}/userinfo", issuer),
+        }))
+    }
+
+    async fn token(Form(form): Form<HashMap<String, String>>) -> Result<Json<Value>, StatusCode> {
+        let field = |key: &str| form.get(key).map(String::as_str).unwrap_or_default();
+
+        if field("grant_type") != "authorization_code"
+            || field("client_id") != CLIENT_ID
+            || field("client_secret") != CLIENT_SECRET
+            || field("redirect_uri") != REDIRECT_URI
+            || code_challenge(field("code_verifier")) != field("code")
+        {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+
+        Ok(Json(json!({
+            "access_token": ACCESS_TOKEN,
+            "token_type": "Bearer",
+            "id_token": id_token(json!({ "sub": "mock-subject", "auth_time": AUTH_TIME })),
+        })))
+    }
+
+    // unsigned, only the payload is read
+    fn id_token(claims: Value) -> String {
+        format!(
+            "{}.{}.",
+            URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        )
+    }
+
+    async fn userinfo(headers: HeaderMap) -> Result<Json<Value>, StatusCode> {
+        if headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            != Some(&format!("Bearer {}", ACCESS_TOKEN))
+        {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+
+        Ok(Json(json!({
+            "sub": "mock-subject",
+            "email": "ada@example.com",
+            "email_verified": "true",
+            "name": "Ada Lovelace",
+            "id": 1815,
+            "login": "ada",
+        })))
+    }
+
+    async fn client(issuer: &str, claims: OidcClaimNames) -> OidcClient {
+        let discovery = discover(&reqwest::Client::new(), &format!("{}/", issuer))
+            .await
+            .unwrap();
+
+        OidcClient::new(vec![OidcProvider {
+            name: "mock".to_string(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: CLIENT_SECRET.to_string(),
+            authorization_url: discovery.authorization_endpoint,
+            token_url: discovery.token_endpoint,
+            userinfo_url: discovery.userinfo_endpoint,
+            scopes: "openid email profile".to_string(),
+            claims,
+        }])
+    }
+
+    fn query(url: &str) -> HashMap<String, String> {
+        Url::parse(url)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn discovers_endpoints() {
+        let issuer = mock_issuer().await;
+        let discovery = discover(&reqwest::Client::new(), &issuer).await.unwrap();
+
+        assert_eq!(
+            discovery.authorization_endpoint,
+            format!("{}/authorize", issuer)
+        );
+        assert_eq!(discovery.token_endpoint, format!("{}/token", issuer));
+        assert_eq!(discovery.userinfo_endpoint, format!("{}/userinfo", issuer));
+    }
+
+    #[tokio::test]
+    async fn completes_pkce_code_flow() {
+        let issuer = mock_issuer().await;
+        let client = client(&issuer, OidcClaimNames::default()).await;
+
+        let url = client
+            .authorization_url("mock", "state", "verifier", REDIRECT_URI, false)
+            .unwrap();
+        assert!(url.starts_with(&format!("{}/authorize?", issuer)));
+
+        let params = query(&url);
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["redirect_uri"], REDIRECT_URI);
+        assert_eq!(params["state"], "state");
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert_eq!(params["code_challenge"], code_challenge("verifier"));
+        assert!(!params.contains_key("prompt"));
+
+        let code = &params["code_challenge"];
+        let tokens = client
+            .exchange_code("mock", code, "verifier", REDIRECT_URI)
+            .await
+            .unwrap();
+        assert_eq!(tokens.access_token, ACCESS_TOKEN);
+        assert_eq!(
+            tokens.auth_time,
+            Some(OffsetDateTime::from_unix_timestamp(AUTH_TIME).unwrap())
+        );
+
+        let identity = client
+            .fetch_identity("mock", &tokens.access_token)
+            .await
+            .unwrap();
+        assert_eq!(identity.subject, "mock-subject");
+        assert_eq!(identity.email.as_deref(), Some("ada@example.com"));
+        assert!(identity.email_verified);
+        assert_eq!(identity.name.as_deref(), Some("Ada Lovelace"));
+    }
+
+    #[tokio::test]
+    async fn asks_for_a_fresh_login_when_reauthenticating() {
+        let issuer = mock_issuer().await;
+        let client = client(&issuer, OidcClaimNames::default()).await;
+
+        let url = client
+            .authorization_url("mock", "state", "verifier", REDIRECT_URI, true)
+            .unwrap();
+
+        let params = query(&url);
+        assert_eq!(params["prompt"], "login");
+        assert_eq!(params["max_age"], "0");
+    }
+
+    #[test]
+    fn reads_auth_time_from_id_tokens() {
+        assert_eq!(
+            id_token_auth_time(&id_token(json!({ "auth_time": AUTH_TIME }))).unwrap(),
+            Some(OffsetDateTime::from_unix_timestamp(AUTH_TIME).unwrap())
+        );
+        assert_eq!(
+            id_token_auth_time(&id_token(json!({ "sub": "mock-subject" }))).unwrap(),
+            None
+        );
+        assert!(matches!(
+            id_token_auth_time("not a token"),
+            Err(OidcClientError::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            id_token_auth_time(&id_token(json!({ "auth_time": "yesterday" }))),
+            Err(OidcClientError::InvalidResponse(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_code_exchanged_with_another_verifier() {
+        let issuer = mock_issuer().await;
+        let client = client(&issuer, OidcClaimNames::default()).await;
+
+        let result = client
+            .exchange_code("mock", &code_challenge("verifier"), "other", REDIRECT_URI)
+            .await;
+
+        assert!(matches!(result, Err(OidcClientError::InvalidResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_invalid_access_token() {
+        let issuer = mock_issuer().await;
+        let client = client(&issuer, OidcClaimNames::default()).await;
+
+        let result = client.fetch_identity("mock", "forged").await;
+
+        assert!(matches!(result, Err(OidcClientError::InvalidResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn maps_custom_claim_names() {
+        let issuer = mock_issuer().await;
+        let client = client(
+            &issuer,
+            OidcClaimNames {
+                subject: "id".to_string(),
+                email: "email".to_string(),
+                email_verified: "verified".to_string(),
+                name: "login".to_string(),
+            },
+        )
+        .await;
+
+        let identity = client.fetch_identity("mock", ACCESS_TOKEN).await.unwrap();
+
+        assert_eq!(identity.subject, "1815");
+        assert!(!identity.email_verified);
+        assert_eq!(identity.name.as_deref(), Some("ada"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_providers() {
+        let issuer = mock_issuer().await;
+        let client = client(&issuer, OidcClaimNames::default()).await;
+
+        assert!(matches!(
+            client.authorization_url("other", "state", "verifier", REDIRECT_URI, false),
+            Err(OidcClientError::UnknownProvider)
+        ));
+    }
+}
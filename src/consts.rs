@@ -1,18 +1,73 @@
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::net::IpAddr;
+use time::Duration;
+use uuid::Uuid;
+
+use crate::utils::hash_password;
 
 lazy_static! {
     pub static ref SLUG_REGEX: Regex = Regex::new(r"^[a-z0-9-]*$").unwrap();
     // pub static ref PASS_REGEX: Regex =
     //     Regex::new(r"^(?=.*?[A-Z])(?=.*?[a-z])(?=.*?[0-9])(?=.*?[#?!@$%^&*-]).{8,}$").unwrap();
     pub static ref SPACE_REGEX: Regex = Regex::new(r"\s+").unwrap();
+    pub static ref API_URL: String =
+        std::env::var("ORK_API_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
+    pub static ref WEBSITE_URL: String =
+        std::env::var("ORK_WEBSITE_URL").unwrap_or_else(|_| "http://localhost:5173".to_string());
+    // reverse proxies allowed to set X-Forwarded-For and X-Real-IP, comma separated
+    pub static ref TRUSTED_PROXIES: Vec<IpAddr> = std::env::var("ORK_TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|ip| !ip.trim().is_empty())
+        .map(|ip| ip.trim().parse().expect("ORK_TRUSTED_PROXIES must list IP addresses"))
+        .collect();
+    // verified against for unknown emails so a login costs the same whether the account exists or not
+    pub static ref DUMMY_PASSWORD_HASH: String = hash_password(&"ork-dummy-password".to_string());
 }
 
+pub const SESSION_COOKIE_NAME: &str = "ork_session_id";
+pub const SESSION_TTL: Duration = Duration::hours(12);
+// sessions with less than this left get renewed by the next authenticated request
+pub const SESSION_RENEWAL_THRESHOLD: Duration = Duration::hours(6);
+// impersonation sessions are never renewed
+pub const IMPERSONATION_TTL: Duration = Duration::minutes(30);
+pub const TWO_FACTOR_PENDING_TTL: Duration = Duration::minutes(5);
+pub const TWO_FACTOR_MAX_ATTEMPTS: i16 = 5;
+pub const OIDC_STATE_COOKIE_NAME: &str = "ork_oidc_state";
+pub const OIDC_STATE_TTL: Duration = Duration::minutes(10);
+// clock skew tolerated between us and a provider when checking a fresh sign in
+pub const OIDC_AUTH_TIME_LEEWAY: Duration = Duration::minutes(1);
+pub const ORGANIZATION_INVITATION_TTL: Duration = Duration::days(7);
+pub const ORGANIZATION_DELETION_GRACE: Duration = Duration::days(7);
+pub const DEFAULT_MAX_PROXIES: i32 = 10;
+pub const DEFAULT_MAX_PROXY_TEMPLATES: i32 = 10;
+pub const DEFAULT_MAX_BRIDGES: i32 = 2;
+pub const DEFAULT_CPU_MILLIS: i64 = 8000;
+pub const DEFAULT_MEMORY_MIB: i64 = 16384;
+// proxy pods of tierless templates get these through the namespace LimitRange
+pub const PROXY_CPU_MILLIS: i64 = 1000;
+pub const PROXY_MEMORY_MIB: i64 = 1024;
+pub const LOGIN_ACCOUNT_MAX_FAILURES: i32 = 5;
+pub const LOGIN_IP_MAX_FAILURES: i32 = 20;
+// failures older than this no longer count towards a lockout
+pub const LOGIN_FAILURE_WINDOW: Duration = Duration::hours(1);
+pub const LOGIN_LOCKOUT_BASE: Duration = Duration::seconds(30);
+pub const LOGIN_LOCKOUT_MAX: Duration = Duration::minutes(30);
+pub const SESSION_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+pub const REGION_PROBE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+// a probe that takes longer than this counts as failed
+pub const REGION_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+// how long a proxy restart waits for the old pod to leave the namespace quota
+pub const PROXY_POD_DELETION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+pub const PROXY_POD_DELETION_POLL: std::time::Duration = std::time::Duration::from_millis(500);
+
 pub trait AsNamespaceName {
     fn as_namespace_name(&self) -> String;
 }
 
-impl AsNamespaceName for String {
+// keyed by the organization id, a slug can be renamed but a namespace can't
+impl AsNamespaceName for Uuid {
     fn as_namespace_name(&self) -> String {
         format!("org-{}", self)
     }
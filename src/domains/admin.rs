@@ -0,0 +1,169 @@
+use crate::domains::error::ErrorResponse;
+use crate::domains::impersonation::ImpersonationError;
+use crate::domains::organization::{Organization, OrganizationError};
+use crate::domains::organization_member::{OrganizationMemberError, OrganizationMemberView};
+use crate::domains::organization_quota::OrganizationQuotaError;
+use crate::domains::proxy::ProxyError;
+use crate::domains::region::RegionError;
+use crate::domains::region_capacity::RegionCapacityError;
+use crate::domains::session::SessionError;
+use crate::domains::user::UserError;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use tracing::error;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 200;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct AdminSearchQuery {
+    pub query: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl AdminSearchQuery {
+    pub fn query(&self) -> Option<&str> {
+        self.query
+            .as_deref()
+            .map(str::trim)
+            .filter(|query| !query.is_empty())
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.page.unwrap_or(0).max(0) * self.limit()
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct ImpersonationAuditQuery {
+    pub user_id: Option<Uuid>,
+    pub impersonator_id: Option<Uuid>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+impl ImpersonationAuditQuery {
+    pub fn limit(&self) -> i64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.page.unwrap_or(0).max(0) * self.limit()
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct AdminOrganization {
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub members: Vec<OrganizationMemberView>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct UpdateUserRoleData {
+    #[validate(range(min = 0, max = 2))]
+    pub role: i16,
+}
+
+pub type AdminResult<R> = Result<R, AdminError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AdminError {
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("user not found")]
+    UserNotFound,
+    #[error("cannot target own account")]
+    SelfTarget,
+    #[error("staff accounts cannot be impersonated")]
+    ImpersonationNotAllowed,
+    #[error("impersonation must be started from a regular session")]
+    SessionRequired,
+    #[error("organization error: {0}")]
+    Organization(#[from] OrganizationError),
+    #[error("proxy error: {0}")]
+    Proxy(#[from] ProxyError),
+    #[error("organization quota error: {0}")]
+    OrganizationQuota(#[from] OrganizationQuotaError),
+    #[error("region error: {0}")]
+    Region(#[from] RegionError),
+    #[error("region capacity error: {0}")]
+    RegionCapacity(#[from] RegionCapacityError),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<UserError> for AdminError {
+    fn from(value: UserError) -> Self {
+        match value {
+            UserError::NotFound => AdminError::UserNotFound,
+            _ => AdminError::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl From<SessionError> for AdminError {
+    fn from(value: SessionError) -> Self {
+        AdminError::Unknown(value.to_string())
+    }
+}
+
+impl From<ImpersonationError> for AdminError {
+    fn from(value: ImpersonationError) -> Self {
+        AdminError::Unknown(value.to_string())
+    }
+}
+
+impl From<OrganizationMemberError> for AdminError {
+    fn from(value: OrganizationMemberError) -> Self {
+        AdminError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for AdminError {
+    fn into_response(self) -> Response {
+        match self {
+            AdminError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
+            AdminError::UserNotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "user not found").into_response()
+            }
+            AdminError::SelfTarget => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, "cannot target own account")
+                    .into_response()
+            }
+            AdminError::ImpersonationNotAllowed => ErrorResponse::of(
+                StatusCode::FORBIDDEN,
+                "staff accounts cannot be impersonated",
+            )
+            .into_response(),
+            AdminError::SessionRequired => ErrorResponse::of(
+                StatusCode::FORBIDDEN,
+                "impersonation must be started from a regular session",
+            )
+            .into_response(),
+            AdminError::Organization(err) => err.into_response(),
+            AdminError::Proxy(err) => err.into_response(),
+            AdminError::OrganizationQuota(err) => err.into_response(),
+            AdminError::Region(err) => err.into_response(),
+            AdminError::RegionCapacity(err) => err.into_response(),
+            AdminError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
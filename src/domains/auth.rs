@@ -1,13 +1,22 @@
 use std::borrow::Cow;
 
+use axum::http::header::RETRY_AFTER;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
+use time::Duration;
 use tracing::error;
+use uuid::Uuid;
 use validator::{HasLen, ValidationError, ValidationErrors};
 
 use crate::domains::error::ErrorResponse;
+use crate::domains::impersonation::Impersonator;
+use crate::domains::login_attempt::LoginAttemptError;
+use crate::domains::organization_invitation::{OrganizationInvitationError, PendingInvitation};
+use crate::domains::organization_member::OrganizationMemberError;
 use crate::domains::session::SessionError;
-use crate::domains::user::UserError;
+use crate::domains::two_factor::TwoFactorError;
+use crate::domains::user::{User, UserError};
+use crate::domains::user_token::UserTokenError;
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize, validator::Validate)]
 pub struct RegisterData {
@@ -34,6 +43,32 @@ fn validate_name(name: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct MeView {
+    #[serde(flatten)]
+    pub user: User,
+    pub impersonated_by: Option<Impersonator>,
+    pub pending_invitations: Vec<PendingInvitation>,
+    /// Absent when authenticated with a token, forms that need it only work with a session.
+    pub csrf_token: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct UpdateMeData {
+    #[validate(custom(function = "validate_name"))]
+    pub name: Option<String>,
+    #[validate(email(message = "invalidEmail"))]
+    pub email: Option<String>,
+    #[validate(length(min = 8, message = "weakPassword"))]
+    pub password: Option<String>,
+    pub current_password: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct DeleteMeData {
+    pub password: String,
+}
+
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize, validator::Validate)]
 pub struct LoginData {
     pub email: String,
@@ -52,8 +87,26 @@ pub enum AuthError {
     EmailAlreadyInUse,
     #[error("wrong password")]
     WrongPassword,
+    #[error("invalid credentials")]
+    InvalidCredentials,
+    #[error("account suspended")]
+    AccountSuspended,
+    #[error("too many login attempts, retry in {0}")]
+    TooManyAttempts(Duration),
     #[error("invalid session")]
     InvalidSession,
+    #[error("session not found")]
+    SessionNotFound,
+    #[error("two factor error: {0}")]
+    TwoFactor(#[from] TwoFactorError),
+    #[error("invalid or expired token")]
+    InvalidToken,
+    #[error("not impersonating")]
+    NotImpersonating,
+    #[error("current password required")]
+    CurrentPasswordRequired,
+    #[error("sole owner of organizations: {0:?}")]
+    SoleOrganizationOwner(Vec<Uuid>),
     #[error("unknown error: {0}")]
     Unknown(String),
 }
@@ -68,6 +121,36 @@ impl From<UserError> for AuthError {
     }
 }
 
+impl From<UserTokenError> for AuthError {
+    fn from(value: UserTokenError) -> Self {
+        match value {
+            UserTokenError::Invalid => AuthError::InvalidToken,
+            UserTokenError::Unknown(err) => AuthError::Unknown(err),
+        }
+    }
+}
+
+impl From<LoginAttemptError> for AuthError {
+    fn from(value: LoginAttemptError) -> Self {
+        match value {
+            LoginAttemptError::Locked(retry_after) => AuthError::TooManyAttempts(retry_after),
+            LoginAttemptError::Unknown(err) => AuthError::Unknown(err),
+        }
+    }
+}
+
+impl From<OrganizationInvitationError> for AuthError {
+    fn from(value: OrganizationInvitationError) -> Self {
+        AuthError::Unknown(value.to_string())
+    }
+}
+
+impl From<OrganizationMemberError> for AuthError {
+    fn from(value: OrganizationMemberError) -> Self {
+        AuthError::Unknown(value.to_string())
+    }
+}
+
 impl From<password_hash::Error> for AuthError {
     fn from(value: password_hash::Error) -> Self {
         match value {
@@ -81,6 +164,7 @@ impl From<SessionError> for AuthError {
     fn from(value: SessionError) -> Self {
         match value {
             SessionError::Invalid => AuthError::InvalidSession,
+            SessionError::NotFound => AuthError::SessionNotFound,
             SessionError::Unknown(err) => AuthError::Unknown(err),
         }
     }
@@ -106,9 +190,45 @@ impl IntoResponse for AuthError {
             AuthError::WrongPassword => {
                 ErrorResponse::of(StatusCode::LOCKED, "wrong password").into_response()
             }
+            AuthError::InvalidCredentials => {
+                ErrorResponse::of(StatusCode::UNAUTHORIZED, "invalid credentials").into_response()
+            }
+            AuthError::AccountSuspended => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "account suspended").into_response()
+            }
+            AuthError::TooManyAttempts(retry_after) => {
+                // round up so clients never retry a moment before the lockout ends
+                let seconds = retry_after.whole_seconds().max(0) + 1;
+                let mut response =
+                    ErrorResponse::of(StatusCode::TOO_MANY_REQUESTS, "too many login attempts")
+                        .into_response();
+                response.headers_mut().insert(RETRY_AFTER, seconds.into());
+                response
+            }
             AuthError::InvalidSession => {
                 ErrorResponse::of(StatusCode::FORBIDDEN, "invalid session").into_response()
             }
+            AuthError::SessionNotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "session not found").into_response()
+            }
+            AuthError::TwoFactor(err) => err.into_response(),
+            AuthError::InvalidToken => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, "invalid or expired token")
+                    .into_response()
+            }
+            AuthError::NotImpersonating => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, "not impersonating").into_response()
+            }
+            AuthError::CurrentPasswordRequired => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, "current password required")
+                    .into_response()
+            }
+            AuthError::SoleOrganizationOwner(organization_ids) => ErrorResponse::of_custom(
+                StatusCode::CONFLICT,
+                "soleOrganizationOwner".to_string(),
+                organization_ids,
+            )
+            .into_response(),
         }
     }
 }
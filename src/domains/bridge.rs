@@ -6,6 +6,8 @@ use validator::ValidationErrors;
 
 use crate::clients::bridge_service::BridgeServiceError;
 use crate::domains::error::ErrorResponse;
+use crate::domains::organization_quota::{OrganizationQuotaError, QuotaExceeded};
+use crate::domains::region::RegionUnavailable;
 use crate::utils::handle_sqlx_unique;
 
 #[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
@@ -32,10 +34,24 @@ pub enum BridgeError {
     AlreadyExists,
     #[error("validation errors: {0}")]
     Validation(#[from] ValidationErrors),
+    #[error("{0}")]
+    QuotaExceeded(#[from] QuotaExceeded),
+    #[error("{0}")]
+    RegionUnavailable(#[from] RegionUnavailable),
     #[error("unknown error: {0}")]
     Unknown(String),
 }
 
+impl From<OrganizationQuotaError> for BridgeError {
+    fn from(value: OrganizationQuotaError) -> Self {
+        match value {
+            OrganizationQuotaError::Exceeded(err) => BridgeError::QuotaExceeded(err),
+            OrganizationQuotaError::RegionUnavailable(err) => BridgeError::RegionUnavailable(err),
+            _ => BridgeError::Unknown(value.to_string()),
+        }
+    }
+}
+
 impl From<sqlx::Error> for BridgeError {
     fn from(value: sqlx::Error) -> Self {
         handle_sqlx_unique(
@@ -65,6 +81,8 @@ impl IntoResponse for BridgeError {
             BridgeError::Validation(err) => {
                 ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
             }
+            BridgeError::QuotaExceeded(err) => err.into_response(),
+            BridgeError::RegionUnavailable(err) => err.into_response(),
             BridgeError::Unknown(err) => {
                 error!("{}", err);
                 ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
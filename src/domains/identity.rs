@@ -0,0 +1,62 @@
+use crate::domains::error::ErrorResponse;
+use crate::utils::handle_sqlx_unique;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct Identity {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_login_at: Option<OffsetDateTime>,
+}
+
+pub type IdentityResult<R> = Result<R, IdentityError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IdentityError {
+    #[error("identity not found")]
+    NotFound,
+    #[error("identity already linked")]
+    AlreadyLinked,
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for IdentityError {
+    fn from(value: sqlx::Error) -> Self {
+        handle_sqlx_unique(
+            value,
+            "unique_identity_provider_subject",
+            |_| IdentityError::AlreadyLinked,
+            IdentityError::Unknown,
+        )
+    }
+}
+
+impl IntoResponse for IdentityError {
+    fn into_response(self) -> Response {
+        match self {
+            IdentityError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "identity not found").into_response()
+            }
+            IdentityError::AlreadyLinked => {
+                ErrorResponse::of(StatusCode::CONFLICT, "identity already linked").into_response()
+            }
+            IdentityError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
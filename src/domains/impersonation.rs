@@ -0,0 +1,54 @@
+use axum::http::Method;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct ImpersonationAuditEntry {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub impersonator_id: Uuid,
+    pub user_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub allowed: bool,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct Impersonator {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+}
+
+/// Impersonation is for looking, anything that changes state for the customer is refused.
+pub fn allowed_while_impersonating(method: &Method, path: &str) -> bool {
+    // leaving only ends the impersonation session, the customer's own sessions are kept
+    if (method == Method::DELETE && path == "/auth/impersonation")
+        || (method == Method::POST && path == "/auth/logout")
+    {
+        return true;
+    }
+
+    // GET handlers with side effects on the impersonated account
+    if path.starts_with("/auth/oidc/") && path.ends_with("/link") {
+        return false;
+    }
+
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+pub type ImpersonationResult<R> = Result<R, ImpersonationError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImpersonationError {
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for ImpersonationError {
+    fn from(value: sqlx::Error) -> Self {
+        ImpersonationError::Unknown(value.to_string())
+    }
+}
@@ -0,0 +1,32 @@
+use time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoginAttemptScope {
+    Account,
+    Ip,
+}
+
+impl LoginAttemptScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginAttemptScope::Account => "account",
+            LoginAttemptScope::Ip => "ip",
+        }
+    }
+}
+
+pub type LoginAttemptResult<R> = Result<R, LoginAttemptError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoginAttemptError {
+    #[error("locked for {0}")]
+    Locked(Duration),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for LoginAttemptError {
+    fn from(value: sqlx::Error) -> Self {
+        LoginAttemptError::Unknown(value.to_string())
+    }
+}
@@ -0,0 +1,182 @@
+use crate::clients::oidc::OidcClientError;
+use crate::domains::error::ErrorResponse;
+use crate::domains::identity::IdentityError;
+use crate::domains::organization_member::OrganizationMemberError;
+use crate::domains::session::SessionError;
+use crate::domains::two_factor::TwoFactorError;
+use crate::domains::user::UserError;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OidcIntent {
+    Login,
+    Link,
+    /// Confirms an account deletion for users who never had a usable password.
+    DeleteAccount,
+}
+
+impl OidcIntent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OidcIntent::Login => "login",
+            OidcIntent::Link => "link",
+            OidcIntent::DeleteAccount => "delete_account",
+        }
+    }
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct OidcLoginState {
+    pub state_hash: String,
+    pub provider: String,
+    pub code_verifier: String,
+    /// Set when a signed in user is linking a new identity or deleting their account.
+    pub user_id: Option<Uuid>,
+    pub intent: String,
+    pub created_at: OffsetDateTime,
+    pub expires_at: OffsetDateTime,
+}
+
+#[derive(Clone, Debug)]
+pub struct OidcAuthorization {
+    pub state: String,
+    pub url: String,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct OidcCallbackQuery {
+    pub state: String,
+    pub code: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct OidcDeleteAccountData {
+    pub csrf_token: String,
+}
+
+pub type OidcResult<R> = Result<R, OidcError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error("unknown provider")]
+    UnknownProvider,
+    #[error("invalid or expired login state")]
+    InvalidState,
+    #[error("provider denied the login: {0}")]
+    Denied(String),
+    #[error("provider did not share an email address")]
+    EmailRequired,
+    #[error("email not verified")]
+    EmailNotVerified,
+    #[error("account suspended")]
+    AccountSuspended,
+    #[error("sole owner of organizations: {0:?}")]
+    SoleOrganizationOwner(Vec<Uuid>),
+    #[error("invalid csrf token")]
+    InvalidCsrfToken,
+    #[error("provider did not confirm a fresh sign in")]
+    ReauthenticationRequired,
+    #[error("identity error: {0}")]
+    Identity(#[from] IdentityError),
+    #[error("provider error: {0}")]
+    Provider(String),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for OidcError {
+    fn from(value: sqlx::Error) -> Self {
+        OidcError::Unknown(value.to_string())
+    }
+}
+
+impl From<OidcClientError> for OidcError {
+    fn from(value: OidcClientError) -> Self {
+        match value {
+            OidcClientError::UnknownProvider => OidcError::UnknownProvider,
+            OidcClientError::InvalidResponse(err) => OidcError::Provider(err),
+            OidcClientError::Unknown(err) => OidcError::Unknown(err),
+        }
+    }
+}
+
+impl From<UserError> for OidcError {
+    fn from(value: UserError) -> Self {
+        OidcError::Unknown(value.to_string())
+    }
+}
+
+impl From<SessionError> for OidcError {
+    fn from(value: SessionError) -> Self {
+        OidcError::Unknown(value.to_string())
+    }
+}
+
+impl From<OrganizationMemberError> for OidcError {
+    fn from(value: OrganizationMemberError) -> Self {
+        OidcError::Unknown(value.to_string())
+    }
+}
+
+impl From<TwoFactorError> for OidcError {
+    fn from(value: TwoFactorError) -> Self {
+        OidcError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for OidcError {
+    fn into_response(self) -> Response {
+        match self {
+            OidcError::UnknownProvider => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "unknown provider").into_response()
+            }
+            OidcError::InvalidState => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, "invalid or expired login state")
+                    .into_response()
+            }
+            OidcError::Denied(_) => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, self.to_string()).into_response()
+            }
+            OidcError::EmailRequired => ErrorResponse::of(
+                StatusCode::BAD_REQUEST,
+                "provider did not share an email address",
+            )
+            .into_response(),
+            OidcError::EmailNotVerified => {
+                ErrorResponse::of(StatusCode::CONFLICT, "email not verified").into_response()
+            }
+            OidcError::AccountSuspended => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "account suspended").into_response()
+            }
+            OidcError::SoleOrganizationOwner(organization_ids) => ErrorResponse::of_custom(
+                StatusCode::CONFLICT,
+                "soleOrganizationOwner".to_string(),
+                organization_ids,
+            )
+            .into_response(),
+            OidcError::InvalidCsrfToken => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "invalid csrf token").into_response()
+            }
+            OidcError::ReauthenticationRequired => ErrorResponse::of(
+                StatusCode::FORBIDDEN,
+                "provider did not confirm a fresh sign in",
+            )
+            .into_response(),
+            OidcError::Identity(err) => err.into_response(),
+            OidcError::Provider(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::BAD_GATEWAY, "provider error").into_response()
+            }
+            OidcError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
@@ -1,18 +1,35 @@
 use crate::domains::error::ErrorResponse;
 use crate::domains::organization_member::OrganizationMemberError;
-use crate::domains::region::RegionError;
+use crate::domains::region::{RegionError, RegionUnavailable};
 use crate::utils::handle_sqlx_unique;
-use axum::http::StatusCode;
+use axum::http::{Method, StatusCode};
 use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
 use tracing::error;
 use uuid::Uuid;
-use validator::ValidationErrors;
+use validator::{ValidationError, ValidationErrors};
 
 #[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
 pub struct Organization {
     pub id: Uuid,
     pub slug: String,
     pub region_id: Uuid,
+    #[serde(skip_serializing)]
+    pub namespace: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub avatar_url: Option<String>,
+    pub contact_email: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub deletion_scheduled_at: Option<OffsetDateTime>,
+}
+
+pub fn allowed_while_pending_deletion(method: &Method, path: &str) -> bool {
+    if method == Method::POST && path.ends_with("/restore") {
+        return true;
+    }
+
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
 }
 
 #[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
@@ -21,6 +38,71 @@ pub struct CreateOrganizationData {
     pub slug: String,
     #[validate(length(min = 4, max = 32), regex = "crate::consts::SLUG_REGEX")]
     pub region_slug: String,
+    #[validate(length(min = 1, max = 64))]
+    pub display_name: Option<String>,
+}
+
+/// Absent fields are left untouched, an empty string clears an optional field.
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct UpdateOrganizationData {
+    #[validate(length(min = 1, max = 64))]
+    pub display_name: Option<String>,
+    #[validate(length(max = 512))]
+    pub description: Option<String>,
+    #[validate(custom(function = "validate_avatar_url"))]
+    pub avatar_url: Option<String>,
+    #[validate(custom(function = "validate_contact_email"))]
+    pub contact_email: Option<String>,
+}
+
+impl UpdateOrganizationData {
+    pub fn apply(self, organization: &mut Organization) {
+        if let Some(display_name) = self.display_name {
+            organization.display_name = display_name;
+        }
+        if let Some(description) = self.description {
+            organization.description = non_empty(description);
+        }
+        if let Some(avatar_url) = self.avatar_url {
+            organization.avatar_url = non_empty(avatar_url);
+        }
+        if let Some(contact_email) = self.contact_email {
+            organization.contact_email = non_empty(contact_email);
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct RenameOrganizationData {
+    #[validate(length(min = 4, max = 32), regex = "crate::consts::SLUG_REGEX")]
+    pub slug: String,
+}
+
+fn non_empty(value: String) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn validate_avatar_url(avatar_url: &str) -> Result<(), ValidationError> {
+    if avatar_url.is_empty() {
+        return Ok(());
+    }
+
+    match url::Url::parse(avatar_url) {
+        Ok(url) if url.scheme() == "https" => Ok(()),
+        _ => Err(ValidationError::new("invalidAvatarUrl")),
+    }
+}
+
+fn validate_contact_email(contact_email: &str) -> Result<(), ValidationError> {
+    if contact_email.is_empty() || validator::validate_email(contact_email) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalidEmail"))
+    }
 }
 
 pub type OrganizationResult<R> = Result<R, OrganizationError>;
@@ -35,6 +117,18 @@ pub enum OrganizationError {
     NotFound,
     #[error("region not found")]
     RegionNotFound,
+    #[error("region is disabled")]
+    RegionDisabled,
+    #[error("{0}")]
+    RegionUnavailable(#[from] RegionUnavailable),
+    #[error("email not verified")]
+    EmailNotVerified,
+    #[error("organization is scheduled for deletion")]
+    PendingDeletion,
+    #[error("organization is not scheduled for deletion")]
+    NotPendingDeletion,
+    #[error("api keys cannot delete organizations")]
+    Forbidden,
     #[error("unknown error: {0}")]
     Unknown(String),
 }
@@ -54,7 +148,10 @@ impl From<RegionError> for OrganizationError {
     fn from(value: RegionError) -> Self {
         match value {
             RegionError::NotFound => OrganizationError::RegionNotFound,
+            RegionError::Disabled => OrganizationError::RegionDisabled,
+            RegionError::Unavailable(err) => OrganizationError::RegionUnavailable(err),
             RegionError::Unknown(err) => OrganizationError::Unknown(err),
+            _ => OrganizationError::Unknown(value.to_string()),
         }
     }
 }
@@ -79,6 +176,14 @@ impl IntoResponse for OrganizationError {
                 ErrorResponse::of(StatusCode::PRECONDITION_FAILED, "region not found")
                     .into_response()
             }
+            OrganizationError::RegionDisabled => {
+                ErrorResponse::of(StatusCode::PRECONDITION_FAILED, "region disabled")
+                    .into_response()
+            }
+            OrganizationError::RegionUnavailable(err) => err.into_response(),
+            OrganizationError::EmailNotVerified => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "email not verified").into_response()
+            }
             OrganizationError::Unknown(err) => {
                 error!("{}", err);
                 ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
@@ -92,6 +197,21 @@ impl IntoResponse for OrganizationError {
                 ErrorResponse::of(StatusCode::CONFLICT, "organization already exists")
                     .into_response()
             }
+            OrganizationError::PendingDeletion => ErrorResponse::of(
+                StatusCode::CONFLICT,
+                "organization is scheduled for deletion",
+            )
+            .into_response(),
+            OrganizationError::NotPendingDeletion => ErrorResponse::of(
+                StatusCode::CONFLICT,
+                "organization is not scheduled for deletion",
+            )
+            .into_response(),
+            OrganizationError::Forbidden => ErrorResponse::of(
+                StatusCode::FORBIDDEN,
+                "api keys cannot delete organizations",
+            )
+            .into_response(),
         }
     }
 }
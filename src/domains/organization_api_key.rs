@@ -0,0 +1,115 @@
+use crate::domains::error::ErrorResponse;
+use crate::domains::organization_member::OrganizationMemberRole;
+use crate::domains::user::User;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+pub const ORGANIZATION_API_KEY_PREFIX: &str = "ork_oak_";
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct OrganizationApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub key_hint: String,
+    pub role: OrganizationMemberRole,
+    pub created_by: Option<Uuid>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl OrganizationApiKey {
+    // api keys act on behalf of the organization, not of a human, so they get a synthetic user
+    pub fn as_user(&self) -> User {
+        User {
+            id: self.id,
+            name: format!("api-key:{}", self.name),
+            email: String::new(),
+            password_hash: String::new(),
+            role: 0,
+            email_verified: true,
+            suspended_at: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CreatedOrganizationApiKey {
+    #[serde(flatten)]
+    pub api_key: OrganizationApiKey,
+    pub secret: String,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct CreateOrganizationApiKeyData {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    pub role: OrganizationMemberRole,
+    #[validate(range(min = 1, max = 365))]
+    pub expires_in_days: Option<i64>,
+}
+
+pub type OrganizationApiKeyResult<R> = Result<R, OrganizationApiKeyError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrganizationApiKeyError {
+    #[error("organization api key not found")]
+    NotFound,
+    #[error("invalid organization api key")]
+    Invalid,
+    #[error("api keys cannot manage api keys")]
+    Forbidden,
+    #[error("api key role exceeds the member role")]
+    RoleTooHigh,
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for OrganizationApiKeyError {
+    fn from(value: sqlx::Error) -> Self {
+        OrganizationApiKeyError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for OrganizationApiKeyError {
+    fn into_response(self) -> Response {
+        match self {
+            OrganizationApiKeyError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "organization api key not found")
+                    .into_response()
+            }
+            OrganizationApiKeyError::Invalid => {
+                ErrorResponse::of(StatusCode::UNAUTHORIZED, "invalid organization api key")
+                    .into_response()
+            }
+            OrganizationApiKeyError::Forbidden => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "api keys cannot manage api keys")
+                    .into_response()
+            }
+            OrganizationApiKeyError::RoleTooHigh => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "api key role exceeds your role")
+                    .into_response()
+            }
+            OrganizationApiKeyError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
+            OrganizationApiKeyError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::domains::error::ErrorResponse;
+use crate::domains::organization_member::OrganizationMemberError;
+use crate::utils::handle_sqlx_unique;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+pub enum OrganizationPermission {
+    #[serde(rename = "proxy.create")]
+    ProxyCreate,
+    #[serde(rename = "proxy.restart")]
+    ProxyRestart,
+    #[serde(rename = "proxy.delete")]
+    ProxyDelete,
+    #[serde(rename = "template.write")]
+    TemplateWrite,
+    #[serde(rename = "bridge.write")]
+    BridgeWrite,
+    #[serde(rename = "members.manage")]
+    MembersManage,
+    #[serde(rename = "api_keys.manage")]
+    ApiKeysManage,
+    #[serde(rename = "billing.read")]
+    BillingRead,
+    #[serde(rename = "organization.manage")]
+    OrganizationManage,
+}
+
+impl OrganizationPermission {
+    pub const ALL: [OrganizationPermission; 9] = [
+        OrganizationPermission::ProxyCreate,
+        OrganizationPermission::ProxyRestart,
+        OrganizationPermission::ProxyDelete,
+        OrganizationPermission::TemplateWrite,
+        OrganizationPermission::BridgeWrite,
+        OrganizationPermission::MembersManage,
+        OrganizationPermission::ApiKeysManage,
+        OrganizationPermission::BillingRead,
+        OrganizationPermission::OrganizationManage,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizationPermission::ProxyCreate => "proxy.create",
+            OrganizationPermission::ProxyRestart => "proxy.restart",
+            OrganizationPermission::ProxyDelete => "proxy.delete",
+            OrganizationPermission::TemplateWrite => "template.write",
+            OrganizationPermission::BridgeWrite => "bridge.write",
+            OrganizationPermission::MembersManage => "members.manage",
+            OrganizationPermission::ApiKeysManage => "api_keys.manage",
+            OrganizationPermission::BillingRead => "billing.read",
+            OrganizationPermission::OrganizationManage => "organization.manage",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<OrganizationPermission> {
+        Self::ALL
+            .into_iter()
+            .find(|permission| permission.as_str() == value)
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct OrganizationCustomRole {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    pub permissions: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl OrganizationCustomRole {
+    // unknown names are left over from removed permissions and grant nothing
+    pub fn permissions(&self) -> Vec<OrganizationPermission> {
+        self.permissions
+            .iter()
+            .filter_map(|permission| OrganizationPermission::parse(permission))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct OrganizationCustomRoleData {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    pub permissions: Vec<OrganizationPermission>,
+}
+
+pub type OrganizationCustomRoleResult<R> = Result<R, OrganizationCustomRoleError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrganizationCustomRoleError {
+    #[error("custom role not found")]
+    NotFound,
+    #[error("organization member not found")]
+    MemberNotFound,
+    #[error("custom role already exists")]
+    AlreadyExists,
+    #[error("cannot grant permissions you do not hold")]
+    PermissionNotHeld,
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for OrganizationCustomRoleError {
+    fn from(value: sqlx::Error) -> Self {
+        handle_sqlx_unique(
+            value,
+            "unique_organization_custom_role_name",
+            |_| OrganizationCustomRoleError::AlreadyExists,
+            OrganizationCustomRoleError::Unknown,
+        )
+    }
+}
+
+impl From<OrganizationMemberError> for OrganizationCustomRoleError {
+    fn from(value: OrganizationMemberError) -> Self {
+        match value {
+            OrganizationMemberError::NotFound => OrganizationCustomRoleError::MemberNotFound,
+            _ => OrganizationCustomRoleError::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for OrganizationCustomRoleError {
+    fn into_response(self) -> Response {
+        match self {
+            OrganizationCustomRoleError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "custom role not found").into_response()
+            }
+            OrganizationCustomRoleError::MemberNotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "organization member not found")
+                    .into_response()
+            }
+            OrganizationCustomRoleError::AlreadyExists => {
+                ErrorResponse::of(StatusCode::CONFLICT, "custom role already exists")
+                    .into_response()
+            }
+            OrganizationCustomRoleError::PermissionNotHeld => ErrorResponse::of(
+                StatusCode::FORBIDDEN,
+                "cannot grant permissions you do not hold",
+            )
+            .into_response(),
+            OrganizationCustomRoleError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
+            OrganizationCustomRoleError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
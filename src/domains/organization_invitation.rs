@@ -0,0 +1,146 @@
+use crate::domains::error::ErrorResponse;
+use crate::domains::organization_member::OrganizationMemberRole;
+use crate::utils::handle_sqlx_unique;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct OrganizationInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: Option<String>,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub role: OrganizationMemberRole,
+    pub invited_by: Option<Uuid>,
+    pub max_uses: Option<i32>,
+    pub uses: i32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+impl OrganizationInvitation {
+    pub fn is_usable(&self) -> bool {
+        self.expires_at > OffsetDateTime::now_utc()
+            && self.max_uses.map_or(true, |max_uses| self.uses < max_uses)
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CreatedOrganizationInvitation {
+    #[serde(flatten)]
+    pub invitation: OrganizationInvitation,
+    pub link: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct PendingInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub organization_slug: String,
+    pub role: OrganizationMemberRole,
+    pub invited_by_name: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct CreateOrganizationInvitationData {
+    #[validate(email(message = "invalidEmail"))]
+    pub email: Option<String>,
+    pub role: OrganizationMemberRole,
+    #[validate(range(min = 1, max = 30))]
+    pub expires_in_days: Option<i64>,
+    #[validate(range(min = 1, max = 1000))]
+    pub max_uses: Option<i32>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct AcceptInvitationData {
+    pub token: String,
+}
+
+pub type OrganizationInvitationResult<R> = Result<R, OrganizationInvitationError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrganizationInvitationError {
+    #[error("invitation not found")]
+    NotFound,
+    #[error("invitation is invalid or expired")]
+    Invalid,
+    #[error("invitation was sent to another email address")]
+    EmailMismatch,
+    #[error("email address is not verified")]
+    EmailNotVerified,
+    #[error("already a member of this organization")]
+    AlreadyMember,
+    #[error("invited role exceeds your role")]
+    RoleTooHigh,
+    #[error("api keys cannot manage invitations")]
+    Forbidden,
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for OrganizationInvitationError {
+    fn from(value: sqlx::Error) -> Self {
+        handle_sqlx_unique(
+            value,
+            "unique_organization_member",
+            |_| OrganizationInvitationError::AlreadyMember,
+            OrganizationInvitationError::Unknown,
+        )
+    }
+}
+
+impl IntoResponse for OrganizationInvitationError {
+    fn into_response(self) -> Response {
+        match self {
+            OrganizationInvitationError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "invitation not found").into_response()
+            }
+            OrganizationInvitationError::Invalid => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "invitation is invalid or expired")
+                    .into_response()
+            }
+            OrganizationInvitationError::EmailMismatch => ErrorResponse::of(
+                StatusCode::FORBIDDEN,
+                "invitation was sent to another email address",
+            )
+            .into_response(),
+            OrganizationInvitationError::EmailNotVerified => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "email address is not verified")
+                    .into_response()
+            }
+            OrganizationInvitationError::AlreadyMember => ErrorResponse::of(
+                StatusCode::CONFLICT,
+                "already a member of this organization",
+            )
+            .into_response(),
+            OrganizationInvitationError::RoleTooHigh => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "invited role exceeds your role")
+                    .into_response()
+            }
+            OrganizationInvitationError::Forbidden => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "api keys cannot manage invitations")
+                    .into_response()
+            }
+            OrganizationInvitationError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
+            OrganizationInvitationError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
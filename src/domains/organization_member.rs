@@ -1,15 +1,78 @@
 use crate::domains::error::ErrorResponse;
+use crate::domains::organization_custom_role::OrganizationPermission;
 use crate::utils::handle_sqlx_unique;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use tracing::error;
 use uuid::Uuid;
 
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Serialize,
+    serde::Deserialize,
+    sqlx::Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[repr(i16)]
+pub enum OrganizationMemberRole {
+    Viewer = 0,
+    Developer = 1,
+    Admin = 2,
+    Owner = 3,
+}
+
+impl OrganizationMemberRole {
+    pub fn permissions(&self) -> Vec<OrganizationPermission> {
+        match self {
+            OrganizationMemberRole::Viewer => vec![],
+            OrganizationMemberRole::Developer => vec![
+                OrganizationPermission::ProxyCreate,
+                OrganizationPermission::ProxyRestart,
+                OrganizationPermission::ProxyDelete,
+            ],
+            OrganizationMemberRole::Admin | OrganizationMemberRole::Owner => {
+                OrganizationPermission::ALL.to_vec()
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
 pub struct OrganizationMember {
     pub organization_id: Uuid,
     pub user_id: Uuid,
-    pub role: i16,
+    pub role: OrganizationMemberRole,
+    pub custom_role_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct OrganizationMemberView {
+    pub user_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub role: OrganizationMemberRole,
+    pub custom_role_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct UpdateOrganizationMemberRoleData {
+    pub role: OrganizationMemberRole,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct TransferOwnershipData {
+    pub user_id: Uuid,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct AssignCustomRoleData {
+    pub custom_role_id: Option<Uuid>,
 }
 
 pub type OrganizationMemberResult<R> = Result<R, OrganizationMemberError>;
@@ -20,6 +83,12 @@ pub enum OrganizationMemberError {
     NotFound,
     #[error("organization member already exists")]
     AlreadyExists,
+    #[error("an organization must keep at least one owner")]
+    LastOwner,
+    #[error("role exceeds your role")]
+    RoleTooHigh,
+    #[error("cannot manage this member")]
+    Forbidden,
     #[error("unknown error: {0}")]
     Unknown(String),
 }
@@ -51,6 +120,18 @@ impl IntoResponse for OrganizationMemberError {
                 ErrorResponse::of(StatusCode::CONFLICT, "organization member already exists")
                     .into_response()
             }
+            OrganizationMemberError::LastOwner => ErrorResponse::of(
+                StatusCode::CONFLICT,
+                "an organization must keep at least one owner",
+            )
+            .into_response(),
+            OrganizationMemberError::RoleTooHigh => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "role exceeds your role").into_response()
+            }
+            OrganizationMemberError::Forbidden => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "cannot manage this member")
+                    .into_response()
+            }
         }
     }
 }
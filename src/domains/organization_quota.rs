@@ -0,0 +1,218 @@
+use std::fmt::{Display, Formatter};
+
+use crate::consts::{
+    DEFAULT_CPU_MILLIS, DEFAULT_MAX_BRIDGES, DEFAULT_MAX_PROXIES, DEFAULT_MAX_PROXY_TEMPLATES,
+    DEFAULT_MEMORY_MIB, PROXY_CPU_MILLIS, PROXY_MEMORY_MIB,
+};
+use crate::domains::error::ErrorResponse;
+use crate::domains::region::RegionUnavailable;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct OrganizationQuota {
+    pub organization_id: Uuid,
+    pub max_proxies: i32,
+    pub max_proxy_templates: i32,
+    pub max_bridges: i32,
+    pub cpu_millis: i64,
+    pub memory_mib: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+impl OrganizationQuota {
+    pub fn default_for(organization_id: Uuid) -> Self {
+        Self {
+            organization_id,
+            max_proxies: DEFAULT_MAX_PROXIES,
+            max_proxy_templates: DEFAULT_MAX_PROXY_TEMPLATES,
+            max_bridges: DEFAULT_MAX_BRIDGES,
+            cpu_millis: DEFAULT_CPU_MILLIS,
+            memory_mib: DEFAULT_MEMORY_MIB,
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    /// Checks that one more proxy requesting `requested` fits next to the current `usage`.
+    pub fn check_proxy(
+        &self,
+        usage: &ProxyUsage,
+        requested: &ProxyResources,
+    ) -> Result<(), QuotaExceeded> {
+        let checks = [
+            (
+                QuotaResource::Proxies,
+                self.max_proxies as i64,
+                usage.proxies + 1,
+            ),
+            (
+                QuotaResource::Cpu,
+                self.cpu_millis,
+                usage.cpu_millis + requested.cpu_millis,
+            ),
+            (
+                QuotaResource::Memory,
+                self.memory_mib,
+                usage.memory_mib + requested.memory_mib,
+            ),
+        ];
+
+        match checks.into_iter().find(|(_, limit, needed)| needed > limit) {
+            Some((resource, limit, _)) => Err(QuotaExceeded { resource, limit }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a single proxy pod requests, from its template's tier or the namespace defaults.
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct ProxyResources {
+    pub cpu_millis: i64,
+    pub memory_mib: i64,
+}
+
+impl Default for ProxyResources {
+    fn default() -> Self {
+        Self {
+            cpu_millis: PROXY_CPU_MILLIS,
+            memory_mib: PROXY_MEMORY_MIB,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct ProxyUsage {
+    pub proxies: i64,
+    pub cpu_millis: i64,
+    pub memory_mib: i64,
+}
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct OrganizationUsage {
+    pub proxies: i64,
+    pub proxy_templates: i64,
+    pub bridges: i64,
+    pub cpu_millis: i64,
+    pub memory_mib: i64,
+}
+
+impl OrganizationUsage {
+    pub fn proxy_usage(&self) -> ProxyUsage {
+        ProxyUsage {
+            proxies: self.proxies,
+            cpu_millis: self.cpu_millis,
+            memory_mib: self.memory_mib,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct OrganizationQuotaView {
+    #[serde(flatten)]
+    pub quota: OrganizationQuota,
+    pub usage: OrganizationUsage,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct UpdateOrganizationQuotaData {
+    #[validate(range(min = 0, max = 10000))]
+    pub max_proxies: i32,
+    #[validate(range(min = 0, max = 10000))]
+    pub max_proxy_templates: i32,
+    #[validate(range(min = 0, max = 1000))]
+    pub max_bridges: i32,
+    #[validate(range(min = 0, max = 1000000))]
+    pub cpu_millis: i64,
+    #[validate(range(min = 0, max = 4194304))]
+    pub memory_mib: i64,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaResource {
+    Proxies,
+    ProxyTemplates,
+    Bridges,
+    Cpu,
+    Memory,
+}
+
+impl QuotaResource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaResource::Proxies => "proxies",
+            QuotaResource::ProxyTemplates => "proxy_templates",
+            QuotaResource::Bridges => "bridges",
+            QuotaResource::Cpu => "cpu",
+            QuotaResource::Memory => "memory",
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct QuotaExceeded {
+    pub resource: QuotaResource,
+    pub limit: i64,
+}
+
+impl Display for QuotaExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} quota of {} exceeded",
+            self.resource.as_str(),
+            self.limit
+        )
+    }
+}
+
+impl std::error::Error for QuotaExceeded {}
+
+impl IntoResponse for QuotaExceeded {
+    fn into_response(self) -> Response {
+        ErrorResponse::of_custom(StatusCode::FORBIDDEN, "quotaExceeded".to_string(), self)
+            .into_response()
+    }
+}
+
+pub type OrganizationQuotaResult<R> = Result<R, OrganizationQuotaError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrganizationQuotaError {
+    #[error("{0}")]
+    Exceeded(#[from] QuotaExceeded),
+    #[error("{0}")]
+    RegionUnavailable(#[from] RegionUnavailable),
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for OrganizationQuotaError {
+    fn from(value: sqlx::Error) -> Self {
+        OrganizationQuotaError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for OrganizationQuotaError {
+    fn into_response(self) -> Response {
+        match self {
+            OrganizationQuotaError::Exceeded(err) => err.into_response(),
+            OrganizationQuotaError::RegionUnavailable(err) => err.into_response(),
+            OrganizationQuotaError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
+            OrganizationQuotaError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
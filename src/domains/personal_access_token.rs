@@ -0,0 +1,177 @@
+use crate::domains::error::ErrorResponse;
+use axum::http::{Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+use validator::ValidationErrors;
+
+pub const PERSONAL_ACCESS_TOKEN_PREFIX: &str = "ork_pat_";
+
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+pub struct PersonalAccessToken {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub token_hint: String,
+    pub scopes: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_used_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub expires_at: Option<OffsetDateTime>,
+}
+
+impl PersonalAccessToken {
+    pub fn allows(&self, scope: TokenScope) -> bool {
+        self.scopes.iter().any(|granted| granted == scope.as_str())
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct CreatedPersonalAccessToken {
+    #[serde(flatten)]
+    pub token: PersonalAccessToken,
+    pub secret: String,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct CreatePersonalAccessTokenData {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub scopes: Vec<TokenScope>,
+    /// Required, personal access tokens always expire.
+    #[validate(range(min = 1, max = 365))]
+    pub expires_in_days: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TokenScope {
+    #[serde(rename = "user:read")]
+    UserRead,
+    #[serde(rename = "regions:read")]
+    RegionsRead,
+    #[serde(rename = "organizations:read")]
+    OrganizationsRead,
+    #[serde(rename = "organizations:write")]
+    OrganizationsWrite,
+    #[serde(rename = "members:read")]
+    MembersRead,
+    #[serde(rename = "members:write")]
+    MembersWrite,
+    #[serde(rename = "proxies:read")]
+    ProxiesRead,
+    #[serde(rename = "proxies:write")]
+    ProxiesWrite,
+    #[serde(rename = "templates:read")]
+    TemplatesRead,
+    #[serde(rename = "templates:write")]
+    TemplatesWrite,
+    #[serde(rename = "bridges:read")]
+    BridgesRead,
+    #[serde(rename = "bridges:write")]
+    BridgesWrite,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::UserRead => "user:read",
+            TokenScope::RegionsRead => "regions:read",
+            TokenScope::OrganizationsRead => "organizations:read",
+            TokenScope::OrganizationsWrite => "organizations:write",
+            TokenScope::MembersRead => "members:read",
+            TokenScope::MembersWrite => "members:write",
+            TokenScope::ProxiesRead => "proxies:read",
+            TokenScope::ProxiesWrite => "proxies:write",
+            TokenScope::TemplatesRead => "templates:read",
+            TokenScope::TemplatesWrite => "templates:write",
+            TokenScope::BridgesRead => "bridges:read",
+            TokenScope::BridgesWrite => "bridges:write",
+        }
+    }
+
+    // tokens may only call routes listed here, everything else (sessions, tokens, ...) needs a browser session
+    pub fn required_for(method: &Method, path: &str) -> Option<TokenScope> {
+        let read = method == Method::GET || method == Method::HEAD;
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        let (read_scope, write_scope) = match segments.as_slice() {
+            ["auth", "me"] => (TokenScope::UserRead, None),
+            ["regions", ..] => (TokenScope::RegionsRead, None),
+            ["organizations"] | ["organizations", _] => (
+                TokenScope::OrganizationsRead,
+                Some(TokenScope::OrganizationsWrite),
+            ),
+            ["organizations", _, resource, ..] => match *resource {
+                "members" | "roles" | "invites" => {
+                    (TokenScope::MembersRead, Some(TokenScope::MembersWrite))
+                }
+                "proxies" => (TokenScope::ProxiesRead, Some(TokenScope::ProxiesWrite)),
+                "proxy-templates" => (TokenScope::TemplatesRead, Some(TokenScope::TemplatesWrite)),
+                "bridges" => (TokenScope::BridgesRead, Some(TokenScope::BridgesWrite)),
+                "quota" => (TokenScope::OrganizationsRead, None),
+                "restore" | "slug" => (
+                    TokenScope::OrganizationsRead,
+                    Some(TokenScope::OrganizationsWrite),
+                ),
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        if read {
+            Some(read_scope)
+        } else {
+            write_scope
+        }
+    }
+}
+
+pub type PersonalAccessTokenResult<R> = Result<R, PersonalAccessTokenError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PersonalAccessTokenError {
+    #[error("personal access token not found")]
+    NotFound,
+    #[error("invalid personal access token")]
+    Invalid,
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for PersonalAccessTokenError {
+    fn from(value: sqlx::Error) -> Self {
+        PersonalAccessTokenError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for PersonalAccessTokenError {
+    fn into_response(self) -> Response {
+        match self {
+            PersonalAccessTokenError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "personal access token not found")
+                    .into_response()
+            }
+            PersonalAccessTokenError::Invalid => {
+                ErrorResponse::of(StatusCode::UNAUTHORIZED, "invalid personal access token")
+                    .into_response()
+            }
+            PersonalAccessTokenError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
+            PersonalAccessTokenError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use crate::domains::error::ErrorResponse;
+use crate::domains::organization_quota::{OrganizationQuotaError, QuotaExceeded};
 use crate::domains::proxy_template::ProxyTemplateError;
+use crate::domains::region::RegionUnavailable;
+use crate::domains::region_capacity::{RegionCapacityError, RegionFull};
+use crate::domains::tier::TierError;
 use crate::utils::handle_sqlx_unique;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -32,14 +36,32 @@ pub type ProxyResult<R> = Result<R, ProxyError>;
 pub enum ProxyError {
     #[error("validation errors: {0}")]
     ValidationErrors(#[from] ValidationErrors),
+    #[error("proxy not found")]
+    NotFound,
     #[error("proxy already exists")]
     AlreadyExists,
     #[error("proxy template not found")]
     TemplateNotFound,
+    #[error("{0}")]
+    QuotaExceeded(#[from] QuotaExceeded),
+    #[error("{0}")]
+    RegionUnavailable(#[from] RegionUnavailable),
+    #[error("{0}")]
+    RegionFull(#[from] RegionFull),
     #[error("unknown error: {0}")]
     Unknown(String),
 }
 
+impl From<OrganizationQuotaError> for ProxyError {
+    fn from(value: OrganizationQuotaError) -> Self {
+        match value {
+            OrganizationQuotaError::Exceeded(err) => ProxyError::QuotaExceeded(err),
+            OrganizationQuotaError::RegionUnavailable(err) => ProxyError::RegionUnavailable(err),
+            _ => ProxyError::Unknown(value.to_string()),
+        }
+    }
+}
+
 impl From<sqlx::Error> for ProxyError {
     fn from(value: sqlx::Error) -> Self {
         handle_sqlx_unique(
@@ -55,20 +77,50 @@ impl From<ProxyTemplateError> for ProxyError {
     fn from(value: ProxyTemplateError) -> Self {
         match value {
             ProxyTemplateError::NotFound => ProxyError::TemplateNotFound,
+            ProxyTemplateError::RegionUnavailable(err) => ProxyError::RegionUnavailable(err),
             ProxyTemplateError::Unknown(err) => ProxyError::Unknown(err),
-            _ => unreachable!(),
+            _ => ProxyError::Unknown(value.to_string()),
         }
     }
 }
 
+impl From<RegionCapacityError> for ProxyError {
+    fn from(value: RegionCapacityError) -> Self {
+        match value {
+            RegionCapacityError::Full(err) => ProxyError::RegionFull(err),
+            RegionCapacityError::Unavailable(err) => ProxyError::RegionUnavailable(err),
+            RegionCapacityError::Unknown(err) => ProxyError::Unknown(err),
+        }
+    }
+}
+
+impl From<kube::Error> for ProxyError {
+    fn from(value: kube::Error) -> Self {
+        ProxyError::Unknown(value.to_string())
+    }
+}
+
+// a template's tier is kept by a foreign key, not finding it is not the caller's fault
+impl From<TierError> for ProxyError {
+    fn from(value: TierError) -> Self {
+        ProxyError::Unknown(value.to_string())
+    }
+}
+
 impl IntoResponse for ProxyError {
     fn into_response(self) -> Response {
         match self {
+            ProxyError::QuotaExceeded(err) => err.into_response(),
+            ProxyError::RegionUnavailable(err) => err.into_response(),
+            ProxyError::RegionFull(err) => err.into_response(),
             ProxyError::Unknown(err) => {
                 error!("{}", err);
                 ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
                     .into_response()
             }
+            ProxyError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "proxy not found").into_response()
+            }
             ProxyError::TemplateNotFound => {
                 ErrorResponse::of(StatusCode::PRECONDITION_FAILED, "template not found")
                     .into_response()
@@ -1,6 +1,9 @@
 use crate::domains::bridge::BridgeError;
 use crate::domains::error::ErrorResponse;
 use crate::domains::organization::OrganizationError;
+use crate::domains::organization_quota::{OrganizationQuotaError, QuotaExceeded};
+use crate::domains::region::RegionUnavailable;
+use crate::domains::tier::TierError;
 use crate::utils::handle_sqlx_unique;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -16,6 +19,7 @@ pub struct ProxyTemplate {
     pub plugins_dir: String,
 
     pub bridge_id: Option<Uuid>,
+    pub tier_id: Option<Uuid>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
@@ -26,6 +30,8 @@ pub struct CreateProxyTemplateData {
     pub plugins_dir: String,
     #[validate(regex = "crate::consts::SLUG_REGEX")]
     pub bridge_slug: Option<String>,
+    #[validate(regex = "crate::consts::SLUG_REGEX")]
+    pub tier_slug: Option<String>,
 }
 
 pub type ProxyTemplateResult<R> = Result<R, ProxyTemplateError>;
@@ -36,14 +42,32 @@ pub enum ProxyTemplateError {
     AlreadyExists,
     #[error("bridge not found")]
     BridgeNotFound,
+    #[error("tier not found")]
+    TierNotFound,
     #[error("validation errors: {0}")]
     Validation(#[from] ValidationErrors),
     #[error("proxy template not found")]
     NotFound,
+    #[error("{0}")]
+    QuotaExceeded(#[from] QuotaExceeded),
+    #[error("{0}")]
+    RegionUnavailable(#[from] RegionUnavailable),
     #[error("unknown error: {0}")]
     Unknown(String),
 }
 
+impl From<OrganizationQuotaError> for ProxyTemplateError {
+    fn from(value: OrganizationQuotaError) -> Self {
+        match value {
+            OrganizationQuotaError::Exceeded(err) => ProxyTemplateError::QuotaExceeded(err),
+            OrganizationQuotaError::RegionUnavailable(err) => {
+                ProxyTemplateError::RegionUnavailable(err)
+            }
+            _ => ProxyTemplateError::Unknown(value.to_string()),
+        }
+    }
+}
+
 impl From<sqlx::Error> for ProxyTemplateError {
     fn from(value: sqlx::Error) -> Self {
         handle_sqlx_unique(
@@ -61,18 +85,34 @@ impl From<BridgeError> for ProxyTemplateError {
             BridgeError::NotFound => ProxyTemplateError::BridgeNotFound,
             BridgeError::AlreadyExists => unreachable!(),
             BridgeError::Validation(_) => unreachable!(),
+            BridgeError::QuotaExceeded(_) => unreachable!(),
+            BridgeError::RegionUnavailable(err) => ProxyTemplateError::RegionUnavailable(err),
             BridgeError::Unknown(err) => ProxyTemplateError::Unknown(err),
         }
     }
 }
+impl From<TierError> for ProxyTemplateError {
+    fn from(value: TierError) -> Self {
+        match value {
+            TierError::NotFound => ProxyTemplateError::TierNotFound,
+            _ => ProxyTemplateError::Unknown(value.to_string()),
+        }
+    }
+}
+
 impl IntoResponse for ProxyTemplateError {
     fn into_response(self) -> Response {
         match self {
+            ProxyTemplateError::QuotaExceeded(err) => err.into_response(),
+            ProxyTemplateError::RegionUnavailable(err) => err.into_response(),
             ProxyTemplateError::Unknown(err) => {
                 error!("{}", err);
                 ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
                     .into_response()
             }
+            ProxyTemplateError::TierNotFound => {
+                ErrorResponse::of(StatusCode::PRECONDITION_FAILED, "tier not found").into_response()
+            }
             ProxyTemplateError::BridgeNotFound => {
                 ErrorResponse::of(StatusCode::PRECONDITION_FAILED, "template not found")
                     .into_response()
@@ -1,16 +1,22 @@
 use crate::domains::error::ErrorResponse;
+use crate::envelope::EnvelopeError;
+use crate::utils::handle_sqlx_unique;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use kube::config::Kubeconfig;
 use log::error;
+use std::fmt::{Display, Formatter};
+use time::OffsetDateTime;
 use uuid::Uuid;
+use validator::ValidationErrors;
 
-#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
+#[derive(Clone, Debug, serde::Serialize)]
 pub struct Region {
     pub id: Uuid,
     pub slug: String,
     #[serde(skip_serializing)]
-    pub options: sqlx::types::Json<RegionOptions>,
+    pub options: RegionOptions,
+    pub disabled: bool,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -23,6 +29,152 @@ pub struct RegionOptions {
 pub struct BridgeConfig {
     #[serde(rename = "basePath")]
     pub base_path: String,
+    /// Cluster namespace the bridge service runs in, organization pods may only reach it there.
+    #[serde(default = "default_bridge_namespace")]
+    pub namespace: String,
+}
+
+fn default_bridge_namespace() -> String {
+    "ork-bridge".to_string()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionStatus {
+    /// Connected but not probed yet.
+    Unknown,
+    Healthy,
+    Degraded,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RegionHealth {
+    pub status: RegionStatus,
+    pub kube: bool,
+    pub bridge: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub checked_at: Option<OffsetDateTime>,
+}
+
+impl RegionHealth {
+    pub fn unknown() -> Self {
+        Self {
+            status: RegionStatus::Unknown,
+            kube: false,
+            bridge: false,
+            checked_at: None,
+        }
+    }
+
+    pub fn probed(kube: bool, bridge: bool) -> Self {
+        Self {
+            status: if kube && bridge {
+                RegionStatus::Healthy
+            } else {
+                RegionStatus::Degraded
+            },
+            kube,
+            bridge,
+            checked_at: Some(OffsetDateTime::now_utc()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RegionView {
+    #[serde(flatten)]
+    pub region: Region,
+    pub health: RegionHealth,
+}
+
+/// What admins get to see of a region, the kubeconfig is reduced to names and server addresses.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RegionDetails {
+    #[serde(flatten)]
+    pub region: Region,
+    pub options: RedactedRegionOptions,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RedactedRegionOptions {
+    pub bridge: BridgeConfig,
+    pub clusters: Vec<RedactedCluster>,
+    pub users: Vec<String>,
+    #[serde(rename = "currentContext")]
+    pub current_context: Option<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RedactedCluster {
+    pub name: String,
+    pub server: Option<String>,
+}
+
+impl From<Region> for RegionDetails {
+    fn from(region: Region) -> Self {
+        let kube = serde_json::to_value(&region.options.kube).unwrap_or_default();
+        let options = RedactedRegionOptions {
+            bridge: region.options.bridge.clone(),
+            clusters: kube["clusters"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| {
+                    Some(RedactedCluster {
+                        name: entry["name"].as_str()?.to_string(),
+                        server: entry["cluster"]["server"].as_str().map(str::to_string),
+                    })
+                })
+                .collect(),
+            users: kube["users"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry["name"].as_str().map(str::to_string))
+                .collect(),
+            current_context: kube["current-context"].as_str().map(str::to_string),
+        };
+
+        Self { region, options }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct CreateRegionData {
+    #[validate(length(min = 4, max = 32), regex = "crate::consts::SLUG_REGEX")]
+    pub slug: String,
+    pub options: RegionOptions,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct UpdateRegionData {
+    #[validate(length(min = 4, max = 32), regex = "crate::consts::SLUG_REGEX")]
+    pub slug: Option<String>,
+    pub options: Option<RegionOptions>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RegionUnavailable {
+    pub region_id: Uuid,
+}
+
+impl Display for RegionUnavailable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "region {} is unavailable", self.region_id)
+    }
+}
+
+impl std::error::Error for RegionUnavailable {}
+
+impl IntoResponse for RegionUnavailable {
+    fn into_response(self) -> Response {
+        ErrorResponse::of_custom(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "regionUnavailable".to_string(),
+            self,
+        )
+        .into_response()
+    }
 }
 
 pub type RegionResult<R> = Result<R, RegionError>;
@@ -30,14 +182,45 @@ pub type RegionResult<R> = Result<R, RegionError>;
 pub enum RegionError {
     #[error("region not found")]
     NotFound,
+    #[error("region already exists")]
+    AlreadyExists,
+    #[error("region still has organizations")]
+    InUse,
+    #[error("region is disabled")]
+    Disabled,
+    #[error("invalid region options: {0}")]
+    InvalidOptions(String),
+    #[error("{0}")]
+    Unavailable(#[from] RegionUnavailable),
+    #[error("validation errors: {0}")]
+    Validation(#[from] ValidationErrors),
     #[error("unknown error: {0}")]
     Unknown(String),
 }
 
+impl From<EnvelopeError> for RegionError {
+    fn from(value: EnvelopeError) -> Self {
+        error!("{:?}", value);
+        RegionError::Unknown(value.to_string())
+    }
+}
+
 impl From<sqlx::Error> for RegionError {
     fn from(value: sqlx::Error) -> Self {
         error!("{:?}", value);
-        RegionError::Unknown(value.to_string())
+        // organizations still referencing the region keep it from being deleted
+        if let sqlx::Error::Database(err) = &value {
+            if err.constraint() == Some("fk_region_id") {
+                return RegionError::InUse;
+            }
+        }
+
+        handle_sqlx_unique(
+            value,
+            "unique_region_slug",
+            |_| RegionError::AlreadyExists,
+            RegionError::Unknown,
+        )
     }
 }
 
@@ -47,6 +230,22 @@ impl IntoResponse for RegionError {
             RegionError::NotFound => {
                 ErrorResponse::of(StatusCode::NOT_FOUND, "regionNotFound").into_response()
             }
+            RegionError::AlreadyExists => {
+                ErrorResponse::of(StatusCode::CONFLICT, "regionAlreadyExists").into_response()
+            }
+            RegionError::InUse => {
+                ErrorResponse::of(StatusCode::CONFLICT, "regionInUse").into_response()
+            }
+            RegionError::Disabled => {
+                ErrorResponse::of(StatusCode::PRECONDITION_FAILED, "regionDisabled").into_response()
+            }
+            RegionError::InvalidOptions(_) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, "invalidRegionOptions").into_response()
+            }
+            RegionError::Unavailable(err) => err.into_response(),
+            RegionError::Validation(err) => {
+                ErrorResponse::of(StatusCode::BAD_REQUEST, err).into_response()
+            }
             RegionError::Unknown(_) => {
                 ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internalServerError")
                     .into_response()
@@ -0,0 +1,176 @@
+use std::fmt::{Display, Formatter};
+
+use crate::domains::error::ErrorResponse;
+use crate::domains::organization_quota::ProxyResources;
+use crate::domains::region::RegionUnavailable;
+use crate::domains::tier::{Tier, TierError};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use tracing::error;
+use uuid::Uuid;
+
+/// A ready, schedulable node along with what its pods already request.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct NodeCapacity {
+    pub name: String,
+    pub tier_tag: Option<String>,
+    pub allocatable_cpu_millis: i64,
+    pub allocatable_memory_mib: i64,
+    pub requested_cpu_millis: i64,
+    pub requested_memory_mib: i64,
+}
+
+impl NodeCapacity {
+    pub fn free_cpu_millis(&self) -> i64 {
+        (self.allocatable_cpu_millis - self.requested_cpu_millis).max(0)
+    }
+
+    pub fn free_memory_mib(&self) -> i64 {
+        (self.allocatable_memory_mib - self.requested_memory_mib).max(0)
+    }
+
+    /// Whether pods with the tier `tag` land here, an empty tag has no node selector.
+    pub fn serves(&self, tag: &str) -> bool {
+        tag.is_empty() || self.tier_tag.as_deref() == Some(tag)
+    }
+
+    /// How many more pods requesting `resources` fit on the node.
+    pub fn slots(&self, resources: &ProxyResources) -> i64 {
+        let cpu = self.free_cpu_millis() / resources.cpu_millis.max(1);
+        let memory = self.free_memory_mib() / resources.memory_mib.max(1);
+
+        cpu.min(memory)
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TierCapacity {
+    /// Absent for templates without a tier.
+    pub tier_slug: Option<String>,
+    pub nodes: i64,
+    pub free_cpu_millis: i64,
+    pub free_memory_mib: i64,
+    pub available_slots: i64,
+}
+
+impl TierCapacity {
+    fn of(
+        tier_slug: Option<String>,
+        tag: &str,
+        resources: &ProxyResources,
+        nodes: &[NodeCapacity],
+    ) -> Self {
+        let nodes: Vec<_> = nodes.iter().filter(|node| node.serves(tag)).collect();
+
+        Self {
+            tier_slug,
+            nodes: nodes.len() as i64,
+            free_cpu_millis: nodes.iter().map(|node| node.free_cpu_millis()).sum(),
+            free_memory_mib: nodes.iter().map(|node| node.free_memory_mib()).sum(),
+            available_slots: nodes.iter().map(|node| node.slots(resources)).sum(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RegionCapacity {
+    pub region_id: Uuid,
+    pub tiers: Vec<TierCapacity>,
+    pub nodes: Vec<NodeCapacity>,
+}
+
+impl RegionCapacity {
+    pub fn new(region_id: Uuid, nodes: Vec<NodeCapacity>, tiers: &[Tier]) -> Self {
+        let tiers = std::iter::once(TierCapacity::of(
+            None,
+            "",
+            &ProxyResources::default(),
+            &nodes,
+        ))
+        .chain(tiers.iter().map(|tier| {
+            TierCapacity::of(
+                Some(tier.slug.clone()),
+                &tier.tag,
+                &tier.resources(),
+                &nodes,
+            )
+        }))
+        .collect();
+
+        Self {
+            region_id,
+            tiers,
+            nodes,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RegionFull {
+    pub region_id: Uuid,
+    pub tier_slug: Option<String>,
+}
+
+impl Display for RegionFull {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.tier_slug {
+            Some(tier_slug) => write!(
+                f,
+                "region {} has no room for tier {}",
+                self.region_id, tier_slug
+            ),
+            None => write!(f, "region {} has no room", self.region_id),
+        }
+    }
+}
+
+impl std::error::Error for RegionFull {}
+
+impl IntoResponse for RegionFull {
+    fn into_response(self) -> Response {
+        ErrorResponse::of_custom(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "regionFull".to_string(),
+            self,
+        )
+        .into_response()
+    }
+}
+
+pub type RegionCapacityResult<R> = Result<R, RegionCapacityError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegionCapacityError {
+    #[error("{0}")]
+    Full(#[from] RegionFull),
+    #[error("{0}")]
+    Unavailable(#[from] RegionUnavailable),
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<TierError> for RegionCapacityError {
+    fn from(value: TierError) -> Self {
+        RegionCapacityError::Unknown(value.to_string())
+    }
+}
+
+impl From<kube::Error> for RegionCapacityError {
+    fn from(value: kube::Error) -> Self {
+        RegionCapacityError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for RegionCapacityError {
+    fn into_response(self) -> Response {
+        match self {
+            RegionCapacityError::Full(err) => err.into_response(),
+            RegionCapacityError::Unavailable(err) => err.into_response(),
+            RegionCapacityError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
@@ -1,14 +1,70 @@
+use crate::consts::SESSION_COOKIE_NAME;
 use crate::domains::user::{User, UserError};
+use crate::utils::hash_secret;
+use axum_extra::extract::cookie::Cookie;
 use sqlx::Error;
 use time::OffsetDateTime;
 use tracing::error;
 use uuid::Uuid;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
 pub struct Session {
+    /// The value of the session cookie, never handed out in responses.
+    #[serde(skip_serializing)]
     pub id: Uuid,
-    pub user: User,
+    /// Identifies the session when listing and revoking sessions.
+    #[serde(rename = "id")]
+    pub public_id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_seen_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
     pub expires_at: OffsetDateTime,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub two_factor_pending: bool,
+    #[serde(skip_serializing)]
+    pub two_factor_attempts: i16,
+    pub impersonator_id: Option<Uuid>,
+    #[serde(skip_serializing)]
+    pub impersonator_session_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct SessionUser {
+    #[sqlx(flatten)]
+    pub user: User,
+    pub impersonator_id: Option<Uuid>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SessionView {
+    #[serde(flatten)]
+    pub session: Session,
+    pub current: bool,
+}
+
+pub fn session_cookie(session_id: &Uuid, expires_at: OffsetDateTime) -> Cookie<'static> {
+    Cookie::build(SESSION_COOKIE_NAME, session_id.to_string())
+        .http_only(true)
+        .secure(true)
+        .expires(expires_at)
+        .path("/")
+        .finish()
+}
+
+/// Derived from the session id, which only ever travels in the cookie, so another site can't
+/// come up with it.
+pub fn csrf_token(session_id: &Uuid) -> String {
+    hash_secret(&format!("csrf:{}", session_id))
+}
+
+pub fn verify_csrf_token(session_id: &Uuid, csrf_token: &str) -> bool {
+    // comparing digests doesn't leak how much of a guessed token was right
+    hash_secret(csrf_token) == hash_secret(&self::csrf_token(session_id))
 }
 
 pub type SessionResult<R> = Result<R, SessionError>;
@@ -17,6 +73,8 @@ pub type SessionResult<R> = Result<R, SessionError>;
 pub enum SessionError {
     #[error("invalid session")]
     Invalid,
+    #[error("session not found")]
+    NotFound,
     #[error("unknown error: {0}")]
     Unknown(String),
 }
@@ -24,6 +82,6 @@ pub enum SessionError {
 impl From<sqlx::Error> for SessionError {
     fn from(value: Error) -> Self {
         error!("{:?}", value);
-        todo!()
+        SessionError::Unknown(value.to_string())
     }
 }
@@ -1,7 +1,75 @@
+use crate::domains::error::ErrorResponse;
+use crate::domains::organization_quota::ProxyResources;
+use crate::domains::region::RegionError;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use tracing::error;
 use uuid::Uuid;
 
 #[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
 pub struct Tier {
     pub id: Uuid,
     pub slug: String,
+    pub region_id: Uuid,
+    #[serde(skip_serializing)]
+    pub tag: String,
+    pub display_name: String,
+    pub cpu_millis: i64,
+    pub memory_mib: i64,
+    pub disk_mib: i64,
+    pub hourly_price_cents: i32,
+}
+
+impl Tier {
+    pub fn resources(&self) -> ProxyResources {
+        ProxyResources {
+            cpu_millis: self.cpu_millis,
+            memory_mib: self.memory_mib,
+        }
+    }
+}
+
+pub type TierResult<R> = Result<R, TierError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TierError {
+    #[error("tier not found")]
+    NotFound,
+    #[error("region not found")]
+    RegionNotFound,
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for TierError {
+    fn from(value: sqlx::Error) -> Self {
+        TierError::Unknown(value.to_string())
+    }
+}
+
+impl From<RegionError> for TierError {
+    fn from(value: RegionError) -> Self {
+        match value {
+            RegionError::NotFound => TierError::RegionNotFound,
+            _ => TierError::Unknown(value.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for TierError {
+    fn into_response(self) -> Response {
+        match self {
+            TierError::NotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "tier not found").into_response()
+            }
+            TierError::RegionNotFound => {
+                ErrorResponse::of(StatusCode::NOT_FOUND, "region not found").into_response()
+            }
+            TierError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
 }
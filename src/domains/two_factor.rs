@@ -0,0 +1,88 @@
+use crate::domains::error::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use time::OffsetDateTime;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct UserTotp {
+    pub user_id: Uuid,
+    pub secret: String,
+    pub enabled: bool,
+    pub last_counter: Option<i64>,
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TwoFactorEnrolment {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct TwoFactorChallenge {
+    pub two_factor_required: bool,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct TwoFactorCodeData {
+    pub code: String,
+}
+
+pub type TwoFactorResult<R> = Result<R, TwoFactorError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TwoFactorError {
+    #[error("two factor authentication is not enrolled")]
+    NotEnrolled,
+    #[error("two factor authentication is already enabled")]
+    AlreadyEnabled,
+    #[error("invalid two factor code")]
+    InvalidCode,
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for TwoFactorError {
+    fn from(value: sqlx::Error) -> Self {
+        TwoFactorError::Unknown(value.to_string())
+    }
+}
+
+impl IntoResponse for TwoFactorError {
+    fn into_response(self) -> Response {
+        match self {
+            TwoFactorError::NotEnrolled => ErrorResponse::of(
+                StatusCode::PRECONDITION_FAILED,
+                "two factor authentication is not enrolled",
+            )
+            .into_response(),
+            TwoFactorError::AlreadyEnabled => ErrorResponse::of(
+                StatusCode::CONFLICT,
+                "two factor authentication is already enabled",
+            )
+            .into_response(),
+            TwoFactorError::InvalidCode => {
+                ErrorResponse::of(StatusCode::UNAUTHORIZED, "invalid two factor code")
+                    .into_response()
+            }
+            TwoFactorError::Unknown(err) => {
+                error!("{}", err);
+                ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
+                    .into_response()
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
+use time::OffsetDateTime;
 use uuid::Uuid;
 
+pub const STAFF_ROLE: i16 = 1;
+pub const ADMIN_ROLE: i16 = 2;
+
 #[derive(Clone, Debug, serde::Serialize, sqlx::FromRow)]
 pub struct User {
     pub id: Uuid,
@@ -8,6 +12,9 @@ pub struct User {
     #[serde(skip_serializing)]
     pub password_hash: String,
     pub role: i16,
+    pub email_verified: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub suspended_at: Option<OffsetDateTime>,
 }
 
 pub type UserResult<R> = Result<R, UserError>;
@@ -0,0 +1,62 @@
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UserTokenKind {
+    EmailVerification,
+    PasswordReset,
+}
+
+impl UserTokenKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserTokenKind::EmailVerification => "email_verification",
+            UserTokenKind::PasswordReset => "password_reset",
+        }
+    }
+}
+
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct UserToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub token_hash: String,
+    pub created_at: OffsetDateTime,
+    pub expires_at: OffsetDateTime,
+    pub used_at: Option<OffsetDateTime>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct ConfirmEmailVerificationData {
+    pub token: String,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct RequestPasswordResetData {
+    #[validate(email(message = "invalidEmail"))]
+    pub email: String,
+}
+
+#[derive(Clone, Debug, serde::Deserialize, validator::Validate)]
+pub struct ConfirmPasswordResetData {
+    pub token: String,
+    #[validate(length(min = 8, message = "weakPassword"))]
+    pub password: String,
+}
+
+pub type UserTokenResult<R> = Result<R, UserTokenError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UserTokenError {
+    #[error("invalid or expired token")]
+    Invalid,
+    #[error("unknown error: {0}")]
+    Unknown(String),
+}
+
+impl From<sqlx::Error> for UserTokenError {
+    fn from(value: sqlx::Error) -> Self {
+        UserTokenError::Unknown(value.to_string())
+    }
+}
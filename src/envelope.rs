@@ -0,0 +1,301 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand::RngCore;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use sha2::{Digest, Sha256};
+
+const KEY_LENGTH: usize = 32;
+
+pub type EnvelopeResult<R> = Result<R, EnvelopeError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EnvelopeError {
+    #[error("ORK_MASTER_KEY is not set")]
+    MissingKey,
+    #[error("master keys must be {} base64 encoded bytes", KEY_LENGTH)]
+    InvalidKey,
+    #[error("no master key with id {0}")]
+    UnknownKey(String),
+    #[error("encryption failed")]
+    Encryption,
+    #[error("malformed sealed data")]
+    Malformed,
+    #[error("decryption failed")]
+    Decryption,
+}
+
+/// Data encrypted with its own random data key, the data key itself being encrypted with a
+/// master key. Rotating the master key only re-encrypts the data key.
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Sealed {
+    #[serde(rename = "keyId")]
+    pub key_id: String,
+    #[serde(rename = "wrappedKey")]
+    pub wrapped_key: String,
+    pub ciphertext: String,
+}
+
+#[derive(Clone)]
+struct MasterKey {
+    id: String,
+    bytes: [u8; KEY_LENGTH],
+}
+
+impl MasterKey {
+    fn decode(encoded: &str) -> EnvelopeResult<Self> {
+        let bytes: [u8; KEY_LENGTH] = STANDARD
+            .decode(encoded.trim())
+            .map_err(|_| EnvelopeError::InvalidKey)?
+            .try_into()
+            .map_err(|_| EnvelopeError::InvalidKey)?;
+        // identifies the key in sealed data without revealing anything about it
+        let id = hex::encode(&Sha256::digest(bytes)[..4]);
+
+        Ok(Self { id, bytes })
+    }
+}
+
+#[derive(Clone)]
+pub struct EnvelopeCipher {
+    current: MasterKey,
+    previous: Vec<MasterKey>,
+}
+
+impl EnvelopeCipher {
+    /// Reads the master key from `ORK_MASTER_KEY` and keys being rotated out from
+    /// `ORK_PREVIOUS_MASTER_KEYS` (comma separated), both base64 encoded.
+    pub fn from_env() -> EnvelopeResult<Self> {
+        let current = std::env::var("ORK_MASTER_KEY").map_err(|_| EnvelopeError::MissingKey)?;
+        let previous = std::env::var("ORK_PREVIOUS_MASTER_KEYS").unwrap_or_default();
+
+        Ok(Self {
+            current: MasterKey::decode(&current)?,
+            previous: previous
+                .split(',')
+                .filter(|key| !key.trim().is_empty())
+                .map(MasterKey::decode)
+                .collect::<EnvelopeResult<_>>()?,
+        })
+    }
+
+    /// The context is authenticated but not stored, opening requires the same one so sealed
+    /// data can't be moved to another record.
+    pub fn seal(&self, context: &[u8], plaintext: &[u8]) -> EnvelopeResult<Sealed> {
+        let mut data_key = [0u8; KEY_LENGTH];
+        rand::thread_rng().fill_bytes(&mut data_key);
+
+        Ok(Sealed {
+            key_id: self.current.id.clone(),
+            wrapped_key: STANDARD.encode(encrypt(
+                &self.current.bytes,
+                self.current.id.as_bytes(),
+                &data_key,
+            )?),
+            ciphertext: STANDARD.encode(encrypt(&data_key, context, plaintext)?),
+        })
+    }
+
+    pub fn open(&self, context: &[u8], sealed: &Sealed) -> EnvelopeResult<Vec<u8>> {
+        let data_key = self.unwrap_key(sealed)?;
+        let ciphertext = STANDARD
+            .decode(&sealed.ciphertext)
+            .map_err(|_| EnvelopeError::Malformed)?;
+
+        decrypt(&data_key, context, &ciphertext)
+    }
+
+    pub fn needs_rewrap(&self, sealed: &Sealed) -> bool {
+        sealed.key_id != self.current.id
+    }
+
+    /// Re-encrypts the data key with the current master key, the ciphertext is kept as is.
+    pub fn rewrap(&self, sealed: &Sealed) -> EnvelopeResult<Sealed> {
+        let data_key = self.unwrap_key(sealed)?;
+
+        Ok(Sealed {
+            key_id: self.current.id.clone(),
+            wrapped_key: STANDARD.encode(encrypt(
+                &self.current.bytes,
+                self.current.id.as_bytes(),
+                &data_key,
+            )?),
+            ciphertext: sealed.ciphertext.clone(),
+        })
+    }
+
+    fn unwrap_key(&self, sealed: &Sealed) -> EnvelopeResult<[u8; KEY_LENGTH]> {
+        let master_key = std::iter::once(&self.current)
+            .chain(&self.previous)
+            .find(|key| key.id == sealed.key_id)
+            .ok_or_else(|| EnvelopeError::UnknownKey(sealed.key_id.clone()))?;
+        let wrapped_key = STANDARD
+            .decode(&sealed.wrapped_key)
+            .map_err(|_| EnvelopeError::Malformed)?;
+
+        decrypt(&master_key.bytes, master_key.id.as_bytes(), &wrapped_key)?
+            .try_into()
+            .map_err(|_| EnvelopeError::Malformed)
+    }
+}
+
+fn cipher(key: &[u8; KEY_LENGTH]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).unwrap())
+}
+
+// the random nonce is prepended to the ciphertext
+fn encrypt(key: &[u8; KEY_LENGTH], aad: &[u8], plaintext: &[u8]) -> EnvelopeResult<Vec<u8>> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut in_out = plaintext.to_vec();
+    cipher(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(aad),
+            &mut in_out,
+        )
+        .map_err(|_| EnvelopeError::Encryption)?;
+
+    Ok([nonce.as_slice(), &in_out].concat())
+}
+
+fn decrypt(key: &[u8; KEY_LENGTH], aad: &[u8], data: &[u8]) -> EnvelopeResult<Vec<u8>> {
+    if data.len() < NONCE_LEN {
+        return Err(EnvelopeError::Malformed);
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| EnvelopeError::Malformed)?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = cipher(key)
+        .open_in_place(nonce, Aad::from(aad), &mut in_out)
+        .map_err(|_| EnvelopeError::Decryption)?;
+
+    Ok(plaintext.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTEXT: &[u8] = b"region";
+    const PLAINTEXT: &[u8] = b"apiVersion: v1";
+
+    fn key(byte: u8) -> MasterKey {
+        MasterKey::decode(&STANDARD.encode([byte; KEY_LENGTH])).unwrap()
+    }
+
+    fn cipher_with(current: u8, previous: &[u8]) -> EnvelopeCipher {
+        EnvelopeCipher {
+            current: key(current),
+            previous: previous.iter().map(|byte| key(*byte)).collect(),
+        }
+    }
+
+    // flips a bit of the decoded bytes, past the nonce
+    fn tamper(encoded: &str) -> String {
+        let mut bytes = STANDARD.decode(encoded).unwrap();
+        bytes[NONCE_LEN] ^= 1;
+        STANDARD.encode(bytes)
+    }
+
+    #[test]
+    fn rejects_keys_of_the_wrong_length() {
+        assert!(matches!(
+            MasterKey::decode(&STANDARD.encode([1u8; 16])),
+            Err(EnvelopeError::InvalidKey)
+        ));
+        assert!(matches!(
+            MasterKey::decode("not base64"),
+            Err(EnvelopeError::InvalidKey)
+        ));
+    }
+
+    #[test]
+    fn opens_what_it_seals() {
+        let cipher = cipher_with(1, &[]);
+        let sealed = cipher.seal(CONTEXT, PLAINTEXT).unwrap();
+
+        assert_eq!(sealed.key_id, key(1).id);
+        assert!(!cipher.needs_rewrap(&sealed));
+        assert_eq!(cipher.open(CONTEXT, &sealed).unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn rejects_another_context() {
+        let cipher = cipher_with(1, &[]);
+        let sealed = cipher.seal(CONTEXT, PLAINTEXT).unwrap();
+
+        assert!(matches!(
+            cipher.open(b"other region", &sealed),
+            Err(EnvelopeError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_ciphertext() {
+        let cipher = cipher_with(1, &[]);
+        let sealed = cipher.seal(CONTEXT, PLAINTEXT).unwrap();
+        let tampered = Sealed {
+            ciphertext: tamper(&sealed.ciphertext),
+            ..sealed.clone()
+        };
+        let truncated = Sealed {
+            ciphertext: STANDARD.encode([0u8; NONCE_LEN - 1]),
+            ..sealed
+        };
+
+        assert!(matches!(
+            cipher.open(CONTEXT, &tampered),
+            Err(EnvelopeError::Decryption)
+        ));
+        assert!(matches!(
+            cipher.open(CONTEXT, &truncated),
+            Err(EnvelopeError::Malformed)
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_wrapped_key() {
+        let cipher = cipher_with(1, &[]);
+        let sealed = cipher.seal(CONTEXT, PLAINTEXT).unwrap();
+        let tampered = Sealed {
+            wrapped_key: tamper(&sealed.wrapped_key),
+            ..sealed
+        };
+
+        assert!(matches!(
+            cipher.open(CONTEXT, &tampered),
+            Err(EnvelopeError::Decryption)
+        ));
+    }
+
+    #[test]
+    fn rewraps_with_the_current_key() {
+        let sealed = cipher_with(1, &[]).seal(CONTEXT, PLAINTEXT).unwrap();
+        let rotated = cipher_with(2, &[1]);
+
+        assert!(rotated.needs_rewrap(&sealed));
+        assert_eq!(rotated.open(CONTEXT, &sealed).unwrap(), PLAINTEXT);
+
+        let rewrapped = rotated.rewrap(&sealed).unwrap();
+        assert_eq!(rewrapped.key_id, key(2).id);
+        assert_eq!(rewrapped.ciphertext, sealed.ciphertext);
+        assert!(!rotated.needs_rewrap(&rewrapped));
+        // the previous key is no longer needed once rewrapped
+        assert_eq!(
+            cipher_with(2, &[]).open(CONTEXT, &rewrapped).unwrap(),
+            PLAINTEXT
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_key_ids() {
+        let sealed = cipher_with(1, &[]).seal(CONTEXT, PLAINTEXT).unwrap();
+
+        match cipher_with(2, &[3]).open(CONTEXT, &sealed) {
+            Err(EnvelopeError::UnknownKey(key_id)) => assert_eq!(key_id, key(1).id),
+            other => panic!("expected an unknown key, got {:?}", other),
+        }
+    }
+}
@@ -2,8 +2,16 @@ use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
 
 use crate::domains::error::ErrorResponse;
-use crate::domains::organization::{Organization, OrganizationError};
-use crate::domains::organization_member::{OrganizationMember, OrganizationMemberError};
+use crate::domains::organization::{
+    allowed_while_pending_deletion, Organization, OrganizationError,
+};
+use crate::domains::organization_api_key::{OrganizationApiKeyError, ORGANIZATION_API_KEY_PREFIX};
+use crate::domains::organization_custom_role::{
+    OrganizationCustomRoleError, OrganizationPermission,
+};
+use crate::domains::organization_member::{
+    OrganizationMember, OrganizationMemberError, OrganizationMemberRole,
+};
 use axum::extract::{FromRequestParts, Path};
 use axum::http::request::Parts;
 use axum::http::StatusCode;
@@ -13,23 +21,61 @@ use uuid::Uuid;
 
 use crate::domains::user::User;
 use crate::extractors::authenticated_user::{
-    AnyUserRole, AuthenticatedUser, AuthenticatedUserError, UserRole,
+    bearer_token, request_path, AnyUserRole, AuthenticatedUser, AuthenticatedUserError, UserRole,
 };
 use crate::managers::organization::OrganizationManager;
+use crate::managers::organization_api_key::OrganizationApiKeyManager;
+use crate::managers::organization_custom_role::OrganizationCustomRoleManager;
 use crate::managers::organization_member::OrganizationMemberManager;
 
 pub trait OrganizationRole {
-    fn check(level: i16) -> bool;
+    fn check(role: OrganizationMemberRole, permissions: &[OrganizationPermission]) -> bool;
 }
 
 pub struct AnyOrganizationRole;
 
 impl OrganizationRole for AnyOrganizationRole {
-    fn check(_level: i16) -> bool {
+    fn check(_role: OrganizationMemberRole, _permissions: &[OrganizationPermission]) -> bool {
         true
     }
 }
 
+pub struct OwnerOrgRole;
+
+impl OrganizationRole for OwnerOrgRole {
+    fn check(role: OrganizationMemberRole, _permissions: &[OrganizationPermission]) -> bool {
+        role >= OrganizationMemberRole::Owner
+    }
+}
+
+macro_rules! permission_roles {
+    ($($name:ident => $permission:ident),* $(,)?) => {
+        $(
+            pub struct $name;
+
+            impl OrganizationRole for $name {
+                fn check(
+                    _role: OrganizationMemberRole,
+                    permissions: &[OrganizationPermission],
+                ) -> bool {
+                    permissions.contains(&OrganizationPermission::$permission)
+                }
+            }
+        )*
+    };
+}
+
+permission_roles! {
+    CanCreateProxy => ProxyCreate,
+    CanRestartProxy => ProxyRestart,
+    CanDeleteProxy => ProxyDelete,
+    CanWriteTemplate => TemplateWrite,
+    CanWriteBridge => BridgeWrite,
+    CanManageMembers => MembersManage,
+    CanManageApiKeys => ApiKeysManage,
+    CanManageOrganization => OrganizationManage,
+}
+
 #[derive(Clone, Debug)]
 pub struct AuthenticatedOrgMember<
     UR: UserRole = AnyUserRole,
@@ -38,6 +84,8 @@ pub struct AuthenticatedOrgMember<
     User,
     Organization,
     OrganizationMember,
+    Option<Uuid>,
+    Vec<OrganizationPermission>,
     PhantomData<(UR, OR)>,
 );
 
@@ -50,9 +98,17 @@ where
         &self.1
     }
 
-    pub fn role(&self) -> i16 {
+    pub fn role(&self) -> OrganizationMemberRole {
         self.2.role
     }
+
+    pub fn api_key_id(&self) -> Option<&Uuid> {
+        self.3.as_ref()
+    }
+
+    pub fn permissions(&self) -> &[OrganizationPermission] {
+        &self.4
+    }
 }
 
 impl<UR, OR> Deref for AuthenticatedOrgMember<UR, OR>
@@ -97,7 +153,11 @@ where
     type Rejection = AuthenticatedOrgMemberError;
 
     async fn from_request_parts(mut parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let AuthenticatedUser(user, _) =
+        if let Some(secret) = bearer_token(parts, ORGANIZATION_API_KEY_PREFIX) {
+            return authenticate_api_key(parts, state, &secret).await;
+        }
+
+        let AuthenticatedUser(user, _, _) =
             AuthenticatedUser::<UR>::from_request_parts(parts, state).await?;
 
         let Path((organization_id,)): Path<(Uuid,)> =
@@ -112,19 +172,89 @@ where
             .find_by_user_id(&organization_id, &user.id)
             .await?;
 
-        if !OR::check(organization_member.role) {
+        let organization_custom_role_manager: &OrganizationCustomRoleManager =
+            parts.extensions.get().unwrap();
+        let permissions = organization_custom_role_manager
+            .permissions_for(&organization_member)
+            .await?;
+
+        if !OR::check(organization_member.role, &permissions) {
             return Err(AuthenticatedOrgMemberError::Forbidden);
         }
 
+        ensure_not_pending_deletion(parts, &organization)?;
+
         Ok(AuthenticatedOrgMember(
             user,
             organization,
             organization_member,
+            None,
+            permissions,
             PhantomData,
         ))
     }
 }
 
+async fn authenticate_api_key<S, UR, OR>(
+    parts: &mut Parts,
+    state: &S,
+    secret: &str,
+) -> Result<AuthenticatedOrgMember<UR, OR>, AuthenticatedOrgMemberError>
+where
+    S: Send + Sync,
+    UR: UserRole,
+    OR: OrganizationRole,
+{
+    let Path((organization_id,)): Path<(Uuid,)> =
+        Path::from_request_parts(parts, state).await.unwrap();
+
+    let organization_api_key_manager: &OrganizationApiKeyManager = parts.extensions.get().unwrap();
+    let api_key = organization_api_key_manager
+        .authenticate(&organization_id, secret)
+        .await?;
+
+    let organization_manager: &OrganizationManager = parts.extensions.get().unwrap();
+    let organization = organization_manager.find_by_id(&organization_id).await?;
+
+    let user = api_key.as_user();
+    let organization_member = OrganizationMember {
+        organization_id,
+        user_id: user.id,
+        role: api_key.role,
+        custom_role_id: None,
+    };
+    let permissions = organization_member.role.permissions();
+
+    if !UR::check(user.role) || !OR::check(organization_member.role, &permissions) {
+        return Err(AuthenticatedOrgMemberError::Forbidden);
+    }
+
+    ensure_not_pending_deletion(parts, &organization)?;
+
+    Ok(AuthenticatedOrgMember(
+        user,
+        organization,
+        organization_member,
+        Some(api_key.id),
+        permissions,
+        PhantomData,
+    ))
+}
+
+// an organization waiting for its teardown stays readable so it can still be restored
+fn ensure_not_pending_deletion(
+    parts: &Parts,
+    organization: &Organization,
+) -> Result<(), AuthenticatedOrgMemberError> {
+    if organization.deletion_scheduled_at.is_some()
+        && !allowed_while_pending_deletion(&parts.method, request_path(parts))
+    {
+        return Err(OrganizationError::PendingDeletion.into());
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AuthenticatedOrgMemberError {
     #[error("no permission")]
@@ -135,6 +265,10 @@ pub enum AuthenticatedOrgMemberError {
     OrganizationError(#[from] OrganizationError),
     #[error("organization member error: {0}")]
     OrganizationMemberError(#[from] OrganizationMemberError),
+    #[error("organization api key error: {0}")]
+    OrganizationApiKeyError(#[from] OrganizationApiKeyError),
+    #[error("organization custom role error: {0}")]
+    OrganizationCustomRoleError(#[from] OrganizationCustomRoleError),
 }
 
 impl IntoResponse for AuthenticatedOrgMemberError {
@@ -143,7 +277,9 @@ impl IntoResponse for AuthenticatedOrgMemberError {
             AuthenticatedOrgMemberError::Forbidden => StatusCode::FORBIDDEN.into_response(),
             AuthenticatedOrgMemberError::UserAuthenticatedError(err) => err.into_response(),
             AuthenticatedOrgMemberError::OrganizationError(err) => match err {
-                OrganizationError::NotFound => err.into_response(),
+                OrganizationError::NotFound | OrganizationError::PendingDeletion => {
+                    err.into_response()
+                }
                 OrganizationError::Unknown(_) => {
                     ErrorResponse::of(StatusCode::INTERNAL_SERVER_ERROR, "internal server error")
                         .into_response()
@@ -155,7 +291,10 @@ impl IntoResponse for AuthenticatedOrgMemberError {
                     ErrorResponse::of(StatusCode::NOT_FOUND, "organization not found")
                         .into_response()
                 }
+                _ => err.into_response(),
             },
+            AuthenticatedOrgMemberError::OrganizationApiKeyError(err) => err.into_response(),
+            AuthenticatedOrgMemberError::OrganizationCustomRoleError(err) => err.into_response(),
         }
     }
 }
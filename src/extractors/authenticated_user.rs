@@ -1,8 +1,17 @@
+use crate::consts::SESSION_COOKIE_NAME;
 use crate::domains::error::ErrorResponse;
-use crate::domains::session::{SessionError, SessionResult};
-use crate::domains::user::User;
+use crate::domains::impersonation::allowed_while_impersonating;
+use crate::domains::personal_access_token::{
+    PersonalAccessTokenError, TokenScope, PERSONAL_ACCESS_TOKEN_PREFIX,
+};
+use crate::domains::session::{SessionError, SessionResult, SessionUser};
+use crate::domains::user::{User, ADMIN_ROLE, STAFF_ROLE};
+use crate::managers::impersonation::ImpersonationManager;
+use crate::managers::personal_access_token::PersonalAccessTokenManager;
 use crate::managers::session::SessionManager;
-use axum::extract::FromRequestParts;
+use crate::middlewares::session_renewal::SessionRenewal;
+use axum::extract::{FromRequestParts, OriginalUri};
+use axum::http::header::AUTHORIZATION;
 use axum::http::request::Parts;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
@@ -25,12 +34,65 @@ impl UserRole for AnyUserRole {
     }
 }
 
+pub struct StaffRole;
+
+impl UserRole for StaffRole {
+    fn check(level: i16) -> bool {
+        level >= STAFF_ROLE
+    }
+}
+
+pub struct AdminRole;
+
+impl UserRole for AdminRole {
+    fn check(level: i16) -> bool {
+        level >= ADMIN_ROLE
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Credential {
+    Session(Uuid),
+    PersonalAccessToken(Uuid),
+    Impersonation {
+        session_id: Uuid,
+        impersonator_id: Uuid,
+    },
+}
+
 #[derive(Clone, Debug)]
 pub struct AuthenticatedUser<UR: UserRole = AnyUserRole>(
     pub(super) User,
+    pub(super) Credential,
     pub(super) PhantomData<UR>,
 );
 
+impl<UR> AuthenticatedUser<UR>
+where
+    UR: UserRole,
+{
+    pub fn credential(&self) -> &Credential {
+        &self.1
+    }
+
+    pub fn session_id(&self) -> Option<&Uuid> {
+        match &self.1 {
+            Credential::Session(session_id) => Some(session_id),
+            Credential::Impersonation { session_id, .. } => Some(session_id),
+            _ => None,
+        }
+    }
+
+    pub fn impersonator_id(&self) -> Option<&Uuid> {
+        match &self.1 {
+            Credential::Impersonation {
+                impersonator_id, ..
+            } => Some(impersonator_id),
+            _ => None,
+        }
+    }
+}
+
 impl<UR> Deref for AuthenticatedUser<UR>
 where
     UR: UserRole,
@@ -69,9 +131,13 @@ where
     type Rejection = AuthenticatedUserError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(secret) = bearer_token(parts, PERSONAL_ACCESS_TOKEN_PREFIX) {
+            return authenticate_personal_access_token(parts, &secret).await;
+        }
+
         let jar = CookieJar::from_request_parts(parts, state).await.unwrap();
 
-        let Some(session_cookie) = jar.get("ork_session_id") else {
+        let Some(session_cookie) = jar.get(SESSION_COOKIE_NAME) else {
             return Err(AuthenticatedUserError::Unauthenticated);
         };
 
@@ -79,21 +145,123 @@ where
             .map_err(|err| AuthenticatedUserError::Unknown(err.to_string()))?;
 
         let session_manager: &SessionManager = parts.extensions.get().unwrap();
-        let user = match session_manager.find_user_by_session_id(&session_id).await {
-            Ok(user) => user,
+        let SessionUser {
+            user,
+            impersonator_id,
+        } = match session_manager.find_user_by_session_id(&session_id).await {
+            Ok(session_user) => session_user,
             Err(SessionError::Invalid) => {
-                let jar = jar.remove(Cookie::named("ork_session_id"));
+                let jar = jar.remove(Cookie::named(SESSION_COOKIE_NAME));
                 return Err(AuthenticatedUserError::Invalid(jar));
             }
-            Err(SessionError::Unknown(err)) => return Err(AuthenticatedUserError::Unknown(err)),
+            Err(err) => return Err(AuthenticatedUserError::Unknown(err.to_string())),
         };
 
+        if user.suspended_at.is_some() {
+            return Err(AuthenticatedUserError::Suspended);
+        }
         if !UR::check(user.role) {
             return Err(AuthenticatedUserError::Forbidden);
         }
 
-        Ok(AuthenticatedUser(user, PhantomData))
+        let credential = match impersonator_id {
+            Some(impersonator_id) => {
+                audit_impersonation(parts, &session_id, &impersonator_id, &user.id).await?;
+                Credential::Impersonation {
+                    session_id,
+                    impersonator_id,
+                }
+            }
+            None => Credential::Session(session_id),
+        };
+
+        let renewed_until = session_manager
+            .touch(&session_id)
+            .await
+            .map_err(|err| AuthenticatedUserError::Unknown(err.to_string()))?;
+        if let (Some(expires_at), Some(renewal)) =
+            (renewed_until, parts.extensions.get::<SessionRenewal>())
+        {
+            renewal.set(session_id, expires_at);
+        }
+
+        Ok(AuthenticatedUser(user, credential, PhantomData))
+    }
+}
+
+pub(super) fn bearer_token(parts: &Parts, prefix: &str) -> Option<String> {
+    let value = parts.headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let token = value.strip_prefix("Bearer ")?.trim();
+
+    token.starts_with(prefix).then(|| token.to_string())
+}
+
+// nested routers only see the path below their mount point
+pub(crate) fn request_path(parts: &Parts) -> &str {
+    match parts.extensions.get::<OriginalUri>() {
+        Some(OriginalUri(uri)) => uri.path(),
+        None => parts.uri.path(),
+    }
+}
+
+async fn audit_impersonation(
+    parts: &Parts,
+    session_id: &Uuid,
+    impersonator_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<(), AuthenticatedUserError> {
+    let path = request_path(parts);
+    let allowed = allowed_while_impersonating(&parts.method, path);
+
+    let impersonation_manager: &ImpersonationManager = parts.extensions.get().unwrap();
+    impersonation_manager
+        .record(
+            session_id,
+            impersonator_id,
+            user_id,
+            &parts.method,
+            path,
+            allowed,
+        )
+        .await
+        .map_err(|err| AuthenticatedUserError::Unknown(err.to_string()))?;
+
+    if !allowed {
+        return Err(AuthenticatedUserError::ImpersonationReadOnly);
     }
+
+    Ok(())
+}
+
+async fn authenticate_personal_access_token<UR: UserRole>(
+    parts: &Parts,
+    secret: &str,
+) -> Result<AuthenticatedUser<UR>, AuthenticatedUserError> {
+    let path = request_path(parts);
+    let Some(required_scope) = TokenScope::required_for(&parts.method, path) else {
+        return Err(AuthenticatedUserError::Forbidden);
+    };
+
+    let personal_access_token_manager: &PersonalAccessTokenManager =
+        parts.extensions.get().unwrap();
+    let (user, token) = match personal_access_token_manager.authenticate(secret).await {
+        Ok(authenticated) => authenticated,
+        Err(PersonalAccessTokenError::Invalid) => return Err(AuthenticatedUserError::InvalidToken),
+        Err(err) => return Err(AuthenticatedUserError::Unknown(err.to_string())),
+    };
+
+    if user.suspended_at.is_some() {
+        return Err(AuthenticatedUserError::Suspended);
+    }
+    if !token.allows(required_scope) || !UR::check(user.role) {
+        return Err(AuthenticatedUserError::Forbidden);
+    }
+
+    Ok(AuthenticatedUser(
+        user,
+        Credential::PersonalAccessToken(token.id),
+        PhantomData,
+    ))
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -104,6 +272,12 @@ pub enum AuthenticatedUserError {
     Forbidden,
     #[error("invalid session")]
     Invalid(CookieJar),
+    #[error("invalid token")]
+    InvalidToken,
+    #[error("account suspended")]
+    Suspended,
+    #[error("not allowed while impersonating")]
+    ImpersonationReadOnly,
     #[error("unknown error: {0}")]
     Unknown(String),
 }
@@ -114,6 +288,14 @@ impl IntoResponse for AuthenticatedUserError {
             AuthenticatedUserError::Unauthenticated => StatusCode::UNAUTHORIZED.into_response(),
             AuthenticatedUserError::Forbidden => StatusCode::FORBIDDEN.into_response(),
             AuthenticatedUserError::Invalid(jar) => (StatusCode::UNAUTHORIZED, jar).into_response(),
+            AuthenticatedUserError::InvalidToken => StatusCode::UNAUTHORIZED.into_response(),
+            AuthenticatedUserError::Suspended => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "account suspended").into_response()
+            }
+            AuthenticatedUserError::ImpersonationReadOnly => {
+                ErrorResponse::of(StatusCode::FORBIDDEN, "not allowed while impersonating")
+                    .into_response()
+            }
             AuthenticatedUserError::Unknown(err) => {
                 error!("{:?}", err);
                 StatusCode::INTERNAL_SERVER_ERROR.into_response()
@@ -0,0 +1,80 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::header::USER_AGENT;
+use axum::http::request::Parts;
+use axum::http::HeaderMap;
+
+use crate::consts::TRUSTED_PROXIES;
+
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+#[derive(Clone, Debug, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let user_agent = parts
+            .headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        let ip_address = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| client_ip(address.ip(), &parts.headers).to_string());
+
+        Ok(ClientInfo {
+            user_agent,
+            ip_address,
+        })
+    }
+}
+
+/// Forwarded headers are only believed when the peer is one of `ORK_TRUSTED_PROXIES`, anyone
+/// else could send them to pose as another address.
+fn client_ip(peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+    if !TRUSTED_PROXIES.contains(&peer) {
+        return peer;
+    }
+
+    forwarded_for(headers)
+        .or_else(|| {
+            headers
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        })
+        .unwrap_or(peer)
+}
+
+// the client can put anything at the start of X-Forwarded-For, only the addresses appended by
+// our own proxies are reliable so the first untrusted one from the right is the client
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+
+    for hop in hops.into_iter().rev() {
+        let ip: IpAddr = hop.trim().parse().ok()?;
+        if !TRUSTED_PROXIES.contains(&ip) {
+            return Some(ip);
+        }
+    }
+
+    None
+}
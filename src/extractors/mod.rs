@@ -1,2 +1,3 @@
 pub mod authenticated_org_member;
 pub mod authenticated_user;
+pub mod client_info;
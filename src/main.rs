@@ -1,72 +1,225 @@
 mod clients;
 mod consts;
 mod domains;
+mod envelope;
 mod extractors;
 mod managers;
+mod middlewares;
 mod repositories;
 mod routes;
+mod totp;
 mod utils;
 
+use crate::clients::mailer::{LogMailer, Mailer, SmtpMailer};
+use crate::clients::oidc::OidcClient;
+use crate::consts::{REGION_PROBE_INTERVAL, SESSION_SWEEP_INTERVAL, TRUSTED_PROXIES};
+use crate::envelope::EnvelopeCipher;
 use crate::managers::bridge::BridgeManager;
+use crate::managers::identity::IdentityManager;
+use crate::managers::impersonation::ImpersonationManager;
+use crate::managers::login_attempt::LoginAttemptManager;
+use crate::managers::oidc::OidcManager;
 use crate::managers::organization::OrganizationManager;
+use crate::managers::organization_api_key::OrganizationApiKeyManager;
+use crate::managers::organization_custom_role::OrganizationCustomRoleManager;
+use crate::managers::organization_invitation::OrganizationInvitationManager;
 use crate::managers::organization_member::OrganizationMemberManager;
+use crate::managers::organization_quota::OrganizationQuotaManager;
+use crate::managers::personal_access_token::PersonalAccessTokenManager;
 use crate::managers::proxy::ProxyManager;
 use crate::managers::proxy_template::ProxyTemplateManager;
 use crate::managers::region::RegionManager;
+use crate::managers::region_capacity::RegionCapacityManager;
 use crate::managers::region_connection::RegionConnectionManager;
 use crate::managers::session::SessionManager;
+use crate::managers::tier::TierManager;
+use crate::managers::two_factor::TwoFactorManager;
 use crate::managers::user::UserManager;
+use crate::managers::user_token::UserTokenManager;
 use crate::repositories::bridge::BridgeRepository;
+use crate::repositories::identity::IdentityRepository;
+use crate::repositories::impersonation::ImpersonationRepository;
+use crate::repositories::login_attempt::LoginAttemptRepository;
+use crate::repositories::oidc_login_state::OidcLoginStateRepository;
 use crate::repositories::organization::OrganizationRepository;
+use crate::repositories::organization_api_key::OrganizationApiKeyRepository;
+use crate::repositories::organization_custom_role::OrganizationCustomRoleRepository;
+use crate::repositories::organization_invitation::OrganizationInvitationRepository;
 use crate::repositories::organization_member::OrganizationMemberRepository;
+use crate::repositories::organization_quota::OrganizationQuotaRepository;
+use crate::repositories::personal_access_token::PersonalAccessTokenRepository;
 use crate::repositories::proxy::ProxyRepository;
 use crate::repositories::proxy_template::ProxyTemplateRepository;
 use crate::repositories::regions::RegionRepository;
 use crate::repositories::session::SessionRepository;
+use crate::repositories::tier::TierRepository;
+use crate::repositories::two_factor::TwoFactorRepository;
 use crate::repositories::user::UserRepository;
+use crate::repositories::user_token::UserTokenRepository;
 use axum::http::{HeaderName, HeaderValue};
 use axum::Extension;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
 use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
 use tower_http::trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer};
-use tracing::info;
+use tracing::{error, info};
 
 #[tokio::main]
 async fn main() {
     init_logging();
 
     let address: SocketAddr = "127.0.0.1:8080".parse().unwrap();
+    // fail on a bad ORK_TRUSTED_PROXIES now rather than on the first request
+    lazy_static::initialize(&TRUSTED_PROXIES);
 
     let pg_pool = create_pg_pool().await;
 
     let bridge_repository = BridgeRepository::new(pg_pool.clone());
+    let identity_repository = IdentityRepository::new(pg_pool.clone());
+    let impersonation_repository = ImpersonationRepository::new(pg_pool.clone());
+    let login_attempt_repository = LoginAttemptRepository::new(pg_pool.clone());
+    let oidc_login_state_repository = OidcLoginStateRepository::new(pg_pool.clone());
     let organization_repository = OrganizationRepository::new(pg_pool.clone());
+    let organization_api_key_repository = OrganizationApiKeyRepository::new(pg_pool.clone());
+    let organization_custom_role_repository =
+        OrganizationCustomRoleRepository::new(pg_pool.clone());
+    let organization_invitation_repository = OrganizationInvitationRepository::new(pg_pool.clone());
     let organization_member_repository = OrganizationMemberRepository::new(pg_pool.clone());
+    let organization_quota_repository = OrganizationQuotaRepository::new(pg_pool.clone());
+    let personal_access_token_repository = PersonalAccessTokenRepository::new(pg_pool.clone());
     let proxy_repository = ProxyRepository::new(pg_pool.clone());
     let proxy_template_repository = ProxyTemplateRepository::new(pg_pool.clone());
-    let region_repository = RegionRepository::new(pg_pool.clone());
+    let region_repository = RegionRepository::new(pg_pool.clone(), create_envelope_cipher());
     let user_repository = UserRepository::new(pg_pool.clone());
+    let user_token_repository = UserTokenRepository::new(pg_pool.clone());
     let session_repository = SessionRepository::new(pg_pool.clone());
+    let tier_repository = TierRepository::new(pg_pool.clone());
+    let two_factor_repository = TwoFactorRepository::new(pg_pool.clone());
 
-    let region_manager = RegionManager::new(region_repository.clone());
-    let region_connection_manager = RegionConnectionManager::new(region_manager.clone()).await;
-    let bridge_manager = BridgeManager::new(bridge_repository.clone());
+    let mailer = create_mailer();
+
+    let region_connection_manager = RegionConnectionManager::new();
+    let region_manager =
+        RegionManager::new(region_repository.clone(), region_connection_manager.clone());
+    match region_manager.reseal_all().await {
+        Ok(0) => {}
+        Ok(resealed) => info!("resealed the options of {} regions", resealed),
+        Err(err) => error!("failed to reseal regions: {}", err),
+    }
+    if let Err(err) = region_manager.connect_all().await {
+        error!("failed to connect regions: {}", err);
+    }
+    let organization_quota_manager = OrganizationQuotaManager::new(
+        organization_quota_repository.clone(),
+        region_connection_manager.clone(),
+    );
+    let bridge_manager = BridgeManager::new(
+        bridge_repository.clone(),
+        organization_quota_manager.clone(),
+    );
+    let identity_manager = IdentityManager::new(identity_repository.clone());
+    let impersonation_manager = ImpersonationManager::new(impersonation_repository.clone());
+    let login_attempt_manager = LoginAttemptManager::new(login_attempt_repository.clone());
+    let oidc_manager = OidcManager::new(
+        OidcClient::from_env().await,
+        oidc_login_state_repository.clone(),
+    );
+    let proxy_manager = ProxyManager::new(
+        region_connection_manager.clone(),
+        proxy_repository.clone(),
+        organization_quota_manager.clone(),
+    );
     let organization_manager = OrganizationManager::new(
         region_connection_manager.clone(),
         organization_repository.clone(),
+        proxy_manager.clone(),
+        bridge_manager.clone(),
+        organization_quota_manager.clone(),
+    );
+    let organization_api_key_manager =
+        OrganizationApiKeyManager::new(organization_api_key_repository.clone());
+    let organization_custom_role_manager =
+        OrganizationCustomRoleManager::new(organization_custom_role_repository.clone());
+    let organization_invitation_manager = OrganizationInvitationManager::new(
+        organization_invitation_repository.clone(),
+        mailer.clone(),
     );
     let organization_member_manager =
         OrganizationMemberManager::new(organization_member_repository.clone());
-    let proxy_manager =
-        ProxyManager::new(region_connection_manager.clone(), proxy_repository.clone());
-    let proxy_template_manager = ProxyTemplateManager::new(proxy_template_repository.clone());
+    let proxy_template_manager = ProxyTemplateManager::new(
+        proxy_template_repository.clone(),
+        organization_quota_manager.clone(),
+    );
     let user_manager = UserManager::new(user_repository.clone());
+    let user_token_manager = UserTokenManager::new(user_token_repository.clone(), mailer.clone());
     let session_manager = SessionManager::new(session_repository.clone());
+    let tier_manager = TierManager::new(tier_repository.clone());
+    let region_capacity_manager =
+        RegionCapacityManager::new(region_connection_manager.clone(), tier_manager.clone());
+    let two_factor_manager = TwoFactorManager::new(two_factor_repository.clone());
+    let personal_access_token_manager = PersonalAccessTokenManager::new(
+        personal_access_token_repository.clone(),
+        user_manager.clone(),
+    );
+
+    session_manager.spawn_expired_sweeper(SESSION_SWEEP_INTERVAL);
+    login_attempt_manager.spawn_stale_sweeper(SESSION_SWEEP_INTERVAL);
+    oidc_manager.spawn_expired_sweeper(SESSION_SWEEP_INTERVAL);
+    organization_manager.spawn_deletion_sweeper(SESSION_SWEEP_INTERVAL);
+    organization_manager.spawn_namespace_reconciler(SESSION_SWEEP_INTERVAL);
+    region_manager.spawn_health_monitor(REGION_PROBE_INTERVAL);
 
     let router = axum::Router::new()
         .nest(
             "/auth",
-            routes::auth::router(user_manager.clone(), session_manager.clone()),
+            routes::auth::router(
+                user_manager.clone(),
+                session_manager.clone(),
+                two_factor_manager.clone(),
+                user_token_manager.clone(),
+                organization_member_manager.clone(),
+                organization_invitation_manager.clone(),
+                login_attempt_manager.clone(),
+            )
+            .nest(
+                "/tokens",
+                routes::personal_access_token::router(personal_access_token_manager.clone()),
+            )
+            .nest(
+                "/2fa",
+                routes::two_factor::router(two_factor_manager.clone()),
+            )
+            .nest(
+                "/verify-email",
+                routes::email_verification::router(
+                    user_manager.clone(),
+                    user_token_manager.clone(),
+                ),
+            )
+            .nest(
+                "/password-reset",
+                routes::password_reset::router(
+                    user_manager.clone(),
+                    session_manager.clone(),
+                    user_token_manager.clone(),
+                ),
+            )
+            .nest(
+                "/oidc",
+                routes::oidc::router(
+                    oidc_manager.clone(),
+                    identity_manager.clone(),
+                    user_manager.clone(),
+                    session_manager.clone(),
+                    two_factor_manager.clone(),
+                    organization_member_manager.clone(),
+                ),
+            )
+            .nest(
+                "/identities",
+                routes::identity::router(identity_manager.clone()),
+            ),
         )
         .nest(
             "/organizations",
@@ -77,7 +230,26 @@ async fn main() {
             )
             .nest(
                 "/:org_id/members",
-                routes::organization_member::router(organization_member_manager.clone()),
+                routes::organization_member::router(
+                    organization_member_manager.clone(),
+                    organization_custom_role_manager.clone(),
+                ),
+            )
+            .nest(
+                "/:org_id/invites",
+                routes::organization_invitation::router(organization_invitation_manager.clone()),
+            )
+            .nest(
+                "/:org_id/quota",
+                routes::organization_quota::router(organization_quota_manager.clone()),
+            )
+            .nest(
+                "/:org_id/roles",
+                routes::organization_custom_role::router(organization_custom_role_manager.clone()),
+            )
+            .nest(
+                "/:org_id/api-keys",
+                routes::organization_api_key::router(organization_api_key_manager.clone()),
             )
             .nest(
                 "/:org_id/proxies",
@@ -85,6 +257,8 @@ async fn main() {
                     proxy_manager.clone(),
                     proxy_template_manager.clone(),
                     region_connection_manager.clone(),
+                    tier_manager.clone(),
+                    region_capacity_manager.clone(),
                 ),
             )
             .nest(
@@ -92,6 +266,7 @@ async fn main() {
                 routes::proxy_template::router(
                     bridge_manager.clone(),
                     proxy_template_manager.clone(),
+                    tier_manager.clone(),
                 ),
             )
             .nest(
@@ -99,7 +274,31 @@ async fn main() {
                 routes::bridge::router(bridge_manager.clone(), region_connection_manager.clone()),
             ),
         )
-        .nest("/regions", routes::region::router(region_manager.clone()))
+        .nest(
+            "/invitations",
+            routes::invitation::router(organization_invitation_manager.clone()),
+        )
+        .nest(
+            "/regions",
+            routes::region::router(region_manager.clone(), tier_manager.clone()),
+        )
+        .nest(
+            "/admin",
+            routes::admin::router(
+                user_manager.clone(),
+                session_manager.clone(),
+                organization_manager.clone(),
+                organization_member_manager.clone(),
+                proxy_manager.clone(),
+                impersonation_manager.clone(),
+                organization_quota_manager.clone(),
+                region_manager.clone(),
+                region_capacity_manager.clone(),
+            ),
+        )
+        .layer(axum::middleware::from_fn(
+            middlewares::session_renewal::reissue_session_cookie,
+        ))
         .layer(
             TraceLayer::new_for_http()
                 .make_span_with(DefaultMakeSpan::new().level(tracing::Level::INFO))
@@ -107,9 +306,10 @@ async fn main() {
         )
         .layer(
             CorsLayer::new()
-                .allow_headers(AllowHeaders::list(vec![HeaderName::from_static(
-                    "content-type",
-                )]))
+                .allow_headers(AllowHeaders::list(vec![
+                    HeaderName::from_static("content-type"),
+                    HeaderName::from_static("authorization"),
+                ]))
                 .allow_credentials(true)
                 // .allow_methods(Any)
                 .allow_origin(AllowOrigin::exact(HeaderValue::from_static(
@@ -117,13 +317,17 @@ async fn main() {
                 ))),
         )
         .layer(Extension(session_manager.clone()))
+        .layer(Extension(impersonation_manager.clone()))
+        .layer(Extension(personal_access_token_manager.clone()))
         .layer(Extension(organization_manager.clone()))
-        .layer(Extension(organization_member_manager.clone()));
+        .layer(Extension(organization_member_manager.clone()))
+        .layer(Extension(organization_custom_role_manager.clone()))
+        .layer(Extension(organization_api_key_manager.clone()));
 
     info!("binding on {}", &address);
 
     axum::Server::bind(&address)
-        .serve(router.into_make_service())
+        .serve(router.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .unwrap();
 }
@@ -143,3 +347,20 @@ async fn create_pg_pool() -> sqlx::PgPool {
 
     pg_pool
 }
+
+fn create_envelope_cipher() -> EnvelopeCipher {
+    EnvelopeCipher::from_env().unwrap()
+}
+
+fn create_mailer() -> Arc<dyn Mailer> {
+    match std::env::var("ORK_SMTP_URL") {
+        Ok(url) => {
+            let from = std::env::var("ORK_MAIL_FROM")
+                .unwrap_or_else(|_| "ork <no-reply@ork.gg>".to_string());
+            Arc::new(SmtpMailer::new(&url, &from).unwrap())
+        }
+        Err(_) => Arc::new(LogMailer::new(
+            std::env::var("ORK_MAIL_DIR").ok().map(PathBuf::from),
+        )),
+    }
+}
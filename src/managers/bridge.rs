@@ -1,5 +1,7 @@
 use crate::domains::bridge::{Bridge, BridgeResult};
 use crate::domains::organization::Organization;
+use crate::domains::organization_quota::QuotaResource;
+use crate::managers::organization_quota::OrganizationQuotaManager;
 use crate::managers::region_connection::RegionConnectionManager;
 use crate::repositories::bridge::BridgeRepository;
 use ork_bridge_service::domains::namespace::CreateNamespaceData;
@@ -8,11 +10,29 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct BridgeManager {
     bridge_repository: BridgeRepository,
+    organization_quota_manager: OrganizationQuotaManager,
 }
 
 impl BridgeManager {
-    pub fn new(bridge_repository: BridgeRepository) -> Self {
-        Self { bridge_repository }
+    pub fn new(
+        bridge_repository: BridgeRepository,
+        organization_quota_manager: OrganizationQuotaManager,
+    ) -> Self {
+        Self {
+            bridge_repository,
+            organization_quota_manager,
+        }
+    }
+
+    pub async fn ensure_quota(&self, organization: &Organization) -> BridgeResult<()> {
+        Ok(self
+            .organization_quota_manager
+            .ensure_available(&organization.id, QuotaResource::Bridges)
+            .await?)
+    }
+
+    pub async fn list(&self, organization_id: &Uuid) -> BridgeResult<Vec<Bridge>> {
+        self.bridge_repository.list(organization_id).await
     }
 
     pub async fn find_by_slug(
@@ -30,8 +50,13 @@ impl BridgeManager {
         organization: &Organization,
         bridge: &mut Bridge,
     ) -> BridgeResult<()> {
+        let quota = self
+            .organization_quota_manager
+            .find(&organization.id)
+            .await?;
+
         self.bridge_repository
-            .insert(&organization.id, &bridge)
+            .insert(&organization.id, &bridge, quota.max_bridges as i64)
             .await?;
 
         Ok(())
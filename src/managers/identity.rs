@@ -0,0 +1,48 @@
+use crate::domains::identity::{Identity, IdentityResult};
+use crate::repositories::identity::IdentityRepository;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct IdentityManager {
+    identity_repository: IdentityRepository,
+}
+
+impl IdentityManager {
+    pub fn new(identity_repository: IdentityRepository) -> Self {
+        Self {
+            identity_repository,
+        }
+    }
+
+    pub async fn list_by_user_id(&self, user_id: &Uuid) -> IdentityResult<Vec<Identity>> {
+        self.identity_repository.list_by_user_id(user_id).await
+    }
+
+    pub async fn find_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> IdentityResult<Option<Identity>> {
+        self.identity_repository
+            .find_by_subject(provider, subject)
+            .await
+    }
+
+    pub async fn insert(&self, identity: &Identity) -> IdentityResult<()> {
+        self.identity_repository.insert(identity).await
+    }
+
+    pub async fn touch(&self, identity_id: &Uuid, email: &Option<String>) -> IdentityResult<()> {
+        self.identity_repository.touch(identity_id, email).await
+    }
+
+    pub async fn delete_by_user_id(
+        &self,
+        user_id: &Uuid,
+        identity_id: &Uuid,
+    ) -> IdentityResult<()> {
+        self.identity_repository
+            .delete_by_user_id(user_id, identity_id)
+            .await
+    }
+}
@@ -0,0 +1,56 @@
+use axum::http::Method;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::domains::impersonation::{ImpersonationAuditEntry, ImpersonationResult};
+use crate::repositories::impersonation::ImpersonationRepository;
+
+#[derive(Clone)]
+pub struct ImpersonationManager {
+    impersonation_repository: ImpersonationRepository,
+}
+
+impl ImpersonationManager {
+    pub fn new(impersonation_repository: ImpersonationRepository) -> Self {
+        Self {
+            impersonation_repository,
+        }
+    }
+
+    pub async fn list_audit_entries(
+        &self,
+        user_id: Option<&Uuid>,
+        impersonator_id: Option<&Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> ImpersonationResult<Vec<ImpersonationAuditEntry>> {
+        self.impersonation_repository
+            .list_audit_entries(user_id, impersonator_id, limit, offset)
+            .await
+    }
+
+    pub async fn record(
+        &self,
+        session_id: &Uuid,
+        impersonator_id: &Uuid,
+        user_id: &Uuid,
+        method: &Method,
+        path: &str,
+        allowed: bool,
+    ) -> ImpersonationResult<()> {
+        let entry = ImpersonationAuditEntry {
+            id: Uuid::new_v4(),
+            session_id: *session_id,
+            impersonator_id: *impersonator_id,
+            user_id: *user_id,
+            method: method.to_string(),
+            path: path.to_string(),
+            allowed,
+            created_at: OffsetDateTime::now_utc(),
+        };
+
+        self.impersonation_repository
+            .insert_audit_entry(&entry)
+            .await
+    }
+}
@@ -0,0 +1,139 @@
+use std::net::IpAddr;
+
+use time::{Duration, OffsetDateTime};
+use tracing::{error, info};
+
+use crate::consts::{
+    LOGIN_ACCOUNT_MAX_FAILURES, LOGIN_FAILURE_WINDOW, LOGIN_IP_MAX_FAILURES, LOGIN_LOCKOUT_BASE,
+    LOGIN_LOCKOUT_MAX,
+};
+use crate::domains::login_attempt::{LoginAttemptError, LoginAttemptResult, LoginAttemptScope};
+use crate::repositories::login_attempt::LoginAttemptRepository;
+
+#[derive(Clone)]
+pub struct LoginAttemptManager {
+    login_attempt_repository: LoginAttemptRepository,
+}
+
+impl LoginAttemptManager {
+    pub fn new(login_attempt_repository: LoginAttemptRepository) -> Self {
+        Self {
+            login_attempt_repository,
+        }
+    }
+
+    /// Fails with `Locked` while either the account or the client address is locked out.
+    pub async fn ensure_unlocked(
+        &self,
+        account: &str,
+        ip_address: Option<&str>,
+    ) -> LoginAttemptResult<()> {
+        let ip_address = throttled_ip_address(ip_address);
+        let mut locked_until = self
+            .login_attempt_repository
+            .find_locked_until(LoginAttemptScope::Account, account)
+            .await?;
+
+        if let Some(ip_address) = ip_address {
+            let ip_locked_until = self
+                .login_attempt_repository
+                .find_locked_until(LoginAttemptScope::Ip, ip_address)
+                .await?;
+            locked_until = locked_until.max(ip_locked_until);
+        }
+
+        match locked_until {
+            Some(locked_until) => Err(LoginAttemptError::Locked(
+                locked_until - OffsetDateTime::now_utc(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn record_failure(
+        &self,
+        account: &str,
+        ip_address: Option<&str>,
+    ) -> LoginAttemptResult<()> {
+        let ip_address = throttled_ip_address(ip_address);
+        self.record_scoped_failure(
+            LoginAttemptScope::Account,
+            account,
+            LOGIN_ACCOUNT_MAX_FAILURES,
+        )
+        .await?;
+
+        if let Some(ip_address) = ip_address {
+            self.record_scoped_failure(LoginAttemptScope::Ip, ip_address, LOGIN_IP_MAX_FAILURES)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Only the account counter is reset, a client guessing across many accounts stays throttled.
+    pub async fn record_success(&self, account: &str) -> LoginAttemptResult<()> {
+        self.login_attempt_repository
+            .delete(LoginAttemptScope::Account, account)
+            .await
+    }
+
+    pub fn spawn_stale_sweeper(&self, period: std::time::Duration) {
+        let login_attempt_repository = self.login_attempt_repository.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+
+                let window_start = OffsetDateTime::now_utc() - LOGIN_FAILURE_WINDOW;
+                match login_attempt_repository.delete_stale(&window_start).await {
+                    Ok(0) => {}
+                    Ok(count) => info!("purged {} stale login attempts", count),
+                    Err(err) => error!("failed to purge stale login attempts: {}", err),
+                }
+            }
+        });
+    }
+
+    async fn record_scoped_failure(
+        &self,
+        scope: LoginAttemptScope,
+        key: &str,
+        max_failures: i32,
+    ) -> LoginAttemptResult<()> {
+        let now = OffsetDateTime::now_utc();
+        let failures = self
+            .login_attempt_repository
+            .increment_failures(scope, key, &(now - LOGIN_FAILURE_WINDOW))
+            .await?;
+
+        if failures < max_failures {
+            return Ok(());
+        }
+
+        let locked_until = now + lockout_duration(failures - max_failures);
+
+        self.login_attempt_repository
+            .lock(scope, key, &locked_until)
+            .await
+    }
+}
+
+// a loopback address is the reverse proxy itself when its forwarded headers aren't trusted,
+// throttling it would lock every client out at once
+fn throttled_ip_address(ip_address: Option<&str>) -> Option<&str> {
+    ip_address.filter(|ip_address| {
+        !ip_address
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+    })
+}
+
+// doubles with every failure past the threshold
+fn lockout_duration(excess_failures: i32) -> Duration {
+    let exponent = excess_failures.clamp(0, 16) as u32;
+
+    (LOGIN_LOCKOUT_BASE * 2_i32.pow(exponent)).min(LOGIN_LOCKOUT_MAX)
+}
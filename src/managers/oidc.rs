@@ -0,0 +1,124 @@
+use time::OffsetDateTime;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::clients::oidc::{ExternalIdentity, OidcClient};
+use crate::consts::{API_URL, OIDC_STATE_TTL};
+use crate::domains::oidc::{OidcAuthorization, OidcIntent, OidcLoginState, OidcResult};
+use crate::repositories::oidc_login_state::OidcLoginStateRepository;
+use crate::utils::{generate_secret, hash_secret};
+
+#[derive(Clone)]
+pub struct OidcManager {
+    oidc_client: OidcClient,
+    oidc_login_state_repository: OidcLoginStateRepository,
+}
+
+impl OidcManager {
+    pub fn new(
+        oidc_client: OidcClient,
+        oidc_login_state_repository: OidcLoginStateRepository,
+    ) -> Self {
+        Self {
+            oidc_client,
+            oidc_login_state_repository,
+        }
+    }
+
+    pub fn provider_names(&self) -> Vec<String> {
+        self.oidc_client.provider_names()
+    }
+
+    pub async fn authorize(
+        &self,
+        provider: &str,
+        intent: OidcIntent,
+        user_id: Option<Uuid>,
+    ) -> OidcResult<OidcAuthorization> {
+        let state = generate_secret("", 32);
+        let code_verifier = generate_secret("", 64);
+
+        let url = self.oidc_client.authorization_url(
+            provider,
+            &state,
+            &code_verifier,
+            &redirect_uri(provider),
+            // an existing session at the provider must not be enough to delete an account, the
+            // callback still checks the auth_time since not every provider honours this
+            intent == OidcIntent::DeleteAccount,
+        )?;
+
+        let now = OffsetDateTime::now_utc();
+        let login_state = OidcLoginState {
+            state_hash: hash_secret(&state),
+            provider: provider.to_string(),
+            code_verifier,
+            user_id,
+            intent: intent.as_str().to_string(),
+            created_at: now,
+            expires_at: now + OIDC_STATE_TTL,
+        };
+
+        self.oidc_login_state_repository
+            .insert(&login_state)
+            .await?;
+
+        Ok(OidcAuthorization { state, url })
+    }
+
+    /// Consumes the login state and trades the code for the provider's view of the user.
+    pub async fn complete(
+        &self,
+        provider: &str,
+        state: &str,
+        code: &str,
+    ) -> OidcResult<(OidcLoginState, ExternalIdentity)> {
+        let login_state = self
+            .oidc_login_state_repository
+            .consume(provider, &hash_secret(state))
+            .await?;
+
+        let tokens = self
+            .oidc_client
+            .exchange_code(
+                provider,
+                code,
+                &login_state.code_verifier,
+                &redirect_uri(provider),
+            )
+            .await?;
+        let identity = self
+            .oidc_client
+            .fetch_identity(provider, &tokens.access_token)
+            .await?;
+
+        Ok((
+            login_state,
+            ExternalIdentity {
+                auth_time: tokens.auth_time,
+                ..identity
+            },
+        ))
+    }
+
+    pub fn spawn_expired_sweeper(&self, period: std::time::Duration) {
+        let oidc_login_state_repository = self.oidc_login_state_repository.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+
+                match oidc_login_state_repository.delete_expired().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("purged {} expired oidc login states", count),
+                    Err(err) => error!("failed to purge expired oidc login states: {}", err),
+                }
+            }
+        });
+    }
+}
+
+fn redirect_uri(provider: &str) -> String {
+    format!("{}/auth/oidc/{}/callback", *API_URL, provider)
+}
@@ -1,23 +1,39 @@
+use time::OffsetDateTime;
+use tracing::{error, info};
 use uuid::Uuid;
 
-use crate::domains::organization::{Organization, OrganizationResult};
+use crate::consts::ORGANIZATION_DELETION_GRACE;
+use crate::domains::organization::{Organization, OrganizationError, OrganizationResult};
+use crate::managers::bridge::BridgeManager;
+use crate::managers::organization_quota::OrganizationQuotaManager;
+use crate::managers::proxy::ProxyManager;
 use crate::managers::region_connection::RegionConnectionManager;
 use crate::repositories::organization::OrganizationRepository;
+use crate::utils::like_pattern;
 
 #[derive(Clone)]
 pub struct OrganizationManager {
     region_connection_manager: RegionConnectionManager,
     organization_repository: OrganizationRepository,
+    proxy_manager: ProxyManager,
+    bridge_manager: BridgeManager,
+    organization_quota_manager: OrganizationQuotaManager,
 }
 
 impl OrganizationManager {
     pub fn new(
         region_connection_manager: RegionConnectionManager,
         organization_repository: OrganizationRepository,
+        proxy_manager: ProxyManager,
+        bridge_manager: BridgeManager,
+        organization_quota_manager: OrganizationQuotaManager,
     ) -> Self {
         Self {
             region_connection_manager,
             organization_repository,
+            proxy_manager,
+            bridge_manager,
+            organization_quota_manager,
         }
     }
 
@@ -30,6 +46,19 @@ impl OrganizationManager {
             .await
     }
 
+    pub async fn search(
+        &self,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> OrganizationResult<Vec<Organization>> {
+        let pattern = query.map(like_pattern);
+
+        self.organization_repository
+            .search(pattern.as_ref(), limit, offset)
+            .await
+    }
+
     pub async fn find_by_id(&self, organization_id: &Uuid) -> OrganizationResult<Organization> {
         self.organization_repository
             .find_by_id(organization_id)
@@ -37,15 +66,235 @@ impl OrganizationManager {
     }
 
     pub async fn create(&self, organization: &Organization) -> OrganizationResult<()> {
+        // checked before anything is written so a degraded region doesn't leave half an org
+        let kube_client = self
+            .region_connection_manager
+            .find_kube_wrapped_client_by_id(&organization.region_id)
+            .await?;
+
         self.organization_repository.insert(organization).await?;
 
+        // nothing else references the row yet, so it can go if the namespace can't be created
+        if let Err(err) = kube_client
+            .create_organization_namespace(organization)
+            .await
+        {
+            if let Err(cleanup_err) = self.organization_repository.delete(&organization.id).await {
+                error!(
+                    "failed to delete organization {}: {}",
+                    organization.slug, cleanup_err
+                );
+            }
+            return Err(OrganizationError::Unknown(err.to_string()));
+        }
+
+        // the namespace exists from here on, the reconciler re-applies whatever fails below
+        // so the org keeps its row and gets its owner instead of being left half created
+        if let Err(err) = self.apply_network_policies(organization).await {
+            error!(
+                "failed to apply network policies of organization {}: {}",
+                organization.slug, err
+            );
+        }
+
+        if let Err(err) = self.organization_quota_manager.apply(organization).await {
+            error!(
+                "failed to apply quota of organization {}: {}",
+                organization.slug, err
+            );
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_settings(
+        &self,
+        organization: &Organization,
+    ) -> OrganizationResult<Organization> {
+        self.organization_repository
+            .update_settings(organization)
+            .await
+    }
+
+    pub async fn rename_slug(
+        &self,
+        organization_id: &Uuid,
+        slug: &str,
+    ) -> OrganizationResult<Organization> {
+        // the namespace is keyed by id, renaming the slug leaves cluster resources untouched
+        self.organization_repository
+            .rename_slug(organization_id, slug)
+            .await
+    }
+
+    pub async fn schedule_deletion(
+        &self,
+        organization_id: &Uuid,
+        requested_by: &Uuid,
+    ) -> OrganizationResult<Organization> {
+        self.organization_repository
+            .schedule_deletion(
+                organization_id,
+                &(OffsetDateTime::now_utc() + ORGANIZATION_DELETION_GRACE),
+                requested_by,
+            )
+            .await
+    }
+
+    pub async fn cancel_deletion(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationResult<Organization> {
+        self.organization_repository
+            .cancel_deletion(organization_id)
+            .await
+    }
+
+    async fn apply_network_policies(&self, organization: &Organization) -> OrganizationResult<()> {
         self.region_connection_manager
             .find_kube_wrapped_client_by_id(&organization.region_id)
+            .await?
+            .apply_organization_network_policies(organization)
             .await
-            .unwrap()
-            .create_organization_namespace(&organization)
-            .await;
+            .map_err(|err| OrganizationError::Unknown(err.to_string()))
+    }
 
-        Ok(())
+    /// Re-applies the network policies and quota of every organization, restoring any that were
+    /// edited or removed in the cluster, rolling out changes to existing namespaces and catching
+    /// up on quota updates that were saved but failed to reach the cluster.
+    pub fn spawn_namespace_reconciler(&self, period: std::time::Duration) {
+        let organization_manager = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+
+                let organizations = match organization_manager
+                    .organization_repository
+                    .list_active()
+                    .await
+                {
+                    Ok(organizations) => organizations,
+                    Err(err) => {
+                        error!("failed to list organizations to reconcile: {}", err);
+                        continue;
+                    }
+                };
+
+                for organization in organizations {
+                    if let Err(err) = organization_manager
+                        .apply_network_policies(&organization)
+                        .await
+                    {
+                        error!(
+                            "failed to apply network policies of organization {}: {}",
+                            organization.slug, err
+                        );
+                    }
+
+                    if let Err(err) = organization_manager
+                        .organization_quota_manager
+                        .apply(&organization)
+                        .await
+                    {
+                        error!(
+                            "failed to apply quota of organization {}: {}",
+                            organization.slug, err
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    pub fn spawn_deletion_sweeper(&self, period: std::time::Duration) {
+        let organization_manager = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+
+                let organizations = match organization_manager
+                    .organization_repository
+                    .list_due_for_deletion()
+                    .await
+                {
+                    Ok(organizations) => organizations,
+                    Err(err) => {
+                        error!("failed to list organizations due for deletion: {}", err);
+                        continue;
+                    }
+                };
+
+                for organization in organizations {
+                    match organization_manager.teardown(&organization).await {
+                        Ok(true) => info!("deleted organization {}", organization.slug),
+                        Ok(false) => {}
+                        Err(err) => error!(
+                            "failed to tear down organization {}: {}",
+                            organization.slug, err
+                        ),
+                    }
+                }
+            }
+        });
+    }
+
+    /// Removes the remote resources of an organization and then its rows. Every step tolerates
+    /// resources that are already gone, so a failed teardown is simply retried on the next sweep.
+    /// Returns false while the namespace is still terminating.
+    async fn teardown(&self, organization: &Organization) -> OrganizationResult<bool> {
+        let bs_client = self
+            .region_connection_manager
+            .find_bridge_service_client_by_id(&organization.region_id)
+            .await?;
+        let kube_client = self
+            .region_connection_manager
+            .find_kube_wrapped_client_by_id(&organization.region_id)
+            .await?;
+
+        let proxies = self
+            .proxy_manager
+            .list(&organization.id)
+            .await
+            .map_err(|err| OrganizationError::Unknown(err.to_string()))?;
+        for bs_proxy_id in proxies
+            .iter()
+            .filter_map(|proxy| proxy.bs_proxy_id.as_ref())
+        {
+            bs_client
+                .delete_proxy(bs_proxy_id)
+                .await
+                .map_err(|err| OrganizationError::Unknown(err.to_string()))?;
+        }
+
+        let bridges = self
+            .bridge_manager
+            .list(&organization.id)
+            .await
+            .map_err(|err| OrganizationError::Unknown(err.to_string()))?;
+        for bridge in &bridges {
+            bs_client
+                .delete_namespace(&bridge.bs_namespace_id)
+                .await
+                .map_err(|err| OrganizationError::Unknown(err.to_string()))?;
+        }
+
+        // pods and services live in the namespace and go away with it
+        let namespace_gone = kube_client
+            .delete_organization_namespace(organization)
+            .await
+            .map_err(|err| OrganizationError::Unknown(err.to_string()))?;
+        if !namespace_gone {
+            return Ok(false);
+        }
+
+        self.organization_repository
+            .delete(&organization.id)
+            .await?;
+
+        Ok(true)
     }
 }
@@ -0,0 +1,63 @@
+use crate::domains::organization_api_key::{
+    OrganizationApiKey, OrganizationApiKeyError, OrganizationApiKeyResult,
+};
+use crate::repositories::organization_api_key::OrganizationApiKeyRepository;
+use crate::utils::hash_secret;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrganizationApiKeyManager {
+    organization_api_key_repository: OrganizationApiKeyRepository,
+}
+
+impl OrganizationApiKeyManager {
+    pub fn new(organization_api_key_repository: OrganizationApiKeyRepository) -> Self {
+        Self {
+            organization_api_key_repository,
+        }
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationApiKeyResult<Vec<OrganizationApiKey>> {
+        self.organization_api_key_repository
+            .list(organization_id)
+            .await
+    }
+
+    pub async fn authenticate(
+        &self,
+        organization_id: &Uuid,
+        secret: &str,
+    ) -> OrganizationApiKeyResult<OrganizationApiKey> {
+        let api_key = self
+            .organization_api_key_repository
+            .find_by_key_hash(&hash_secret(secret))
+            .await?;
+
+        if &api_key.organization_id != organization_id {
+            return Err(OrganizationApiKeyError::Invalid);
+        }
+
+        self.organization_api_key_repository
+            .touch(&api_key.id)
+            .await?;
+
+        Ok(api_key)
+    }
+
+    pub async fn insert(&self, api_key: &OrganizationApiKey) -> OrganizationApiKeyResult<()> {
+        self.organization_api_key_repository.insert(api_key).await
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        api_key_id: &Uuid,
+    ) -> OrganizationApiKeyResult<()> {
+        self.organization_api_key_repository
+            .delete(organization_id, api_key_id)
+            .await
+    }
+}
@@ -0,0 +1,87 @@
+use crate::domains::organization_custom_role::{
+    OrganizationCustomRole, OrganizationCustomRoleResult, OrganizationPermission,
+};
+use crate::domains::organization_member::OrganizationMember;
+use crate::repositories::organization_custom_role::OrganizationCustomRoleRepository;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrganizationCustomRoleManager {
+    organization_custom_role_repository: OrganizationCustomRoleRepository,
+}
+
+impl OrganizationCustomRoleManager {
+    pub fn new(organization_custom_role_repository: OrganizationCustomRoleRepository) -> Self {
+        Self {
+            organization_custom_role_repository,
+        }
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationCustomRoleResult<Vec<OrganizationCustomRole>> {
+        self.organization_custom_role_repository
+            .list(organization_id)
+            .await
+    }
+
+    pub async fn find_by_id(
+        &self,
+        organization_id: &Uuid,
+        custom_role_id: &Uuid,
+    ) -> OrganizationCustomRoleResult<OrganizationCustomRole> {
+        self.organization_custom_role_repository
+            .find_by_id(organization_id, custom_role_id)
+            .await
+    }
+
+    /// The member's built-in role grants plus whatever its custom role adds on top.
+    pub async fn permissions_for(
+        &self,
+        organization_member: &OrganizationMember,
+    ) -> OrganizationCustomRoleResult<Vec<OrganizationPermission>> {
+        let mut permissions = organization_member.role.permissions();
+
+        if let Some(custom_role_id) = &organization_member.custom_role_id {
+            let custom_role = self
+                .find_by_id(&organization_member.organization_id, custom_role_id)
+                .await?;
+            for permission in custom_role.permissions() {
+                if !permissions.contains(&permission) {
+                    permissions.push(permission);
+                }
+            }
+        }
+
+        Ok(permissions)
+    }
+
+    pub async fn insert(
+        &self,
+        custom_role: &OrganizationCustomRole,
+    ) -> OrganizationCustomRoleResult<()> {
+        self.organization_custom_role_repository
+            .insert(custom_role)
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        custom_role: &OrganizationCustomRole,
+    ) -> OrganizationCustomRoleResult<()> {
+        self.organization_custom_role_repository
+            .update(custom_role)
+            .await
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        custom_role_id: &Uuid,
+    ) -> OrganizationCustomRoleResult<()> {
+        self.organization_custom_role_repository
+            .delete(organization_id, custom_role_id)
+            .await
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::clients::mailer::{Mail, Mailer};
+use crate::consts::{ORGANIZATION_INVITATION_TTL, WEBSITE_URL};
+use crate::domains::organization::Organization;
+use crate::domains::organization_invitation::{
+    CreateOrganizationInvitationData, CreatedOrganizationInvitation, OrganizationInvitation,
+    OrganizationInvitationError, OrganizationInvitationResult, PendingInvitation,
+};
+use crate::domains::user::User;
+use crate::repositories::organization_invitation::OrganizationInvitationRepository;
+use crate::utils::{generate_secret, hash_secret};
+
+#[derive(Clone)]
+pub struct OrganizationInvitationManager {
+    organization_invitation_repository: OrganizationInvitationRepository,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl OrganizationInvitationManager {
+    pub fn new(
+        organization_invitation_repository: OrganizationInvitationRepository,
+        mailer: Arc<dyn Mailer>,
+    ) -> Self {
+        Self {
+            organization_invitation_repository,
+            mailer,
+        }
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationInvitationResult<Vec<OrganizationInvitation>> {
+        self.organization_invitation_repository
+            .list(organization_id)
+            .await
+    }
+
+    pub async fn list_pending(
+        &self,
+        user: &User,
+    ) -> OrganizationInvitationResult<Vec<PendingInvitation>> {
+        // an unverified address could belong to anyone, don't reveal who invited it
+        if !user.email_verified {
+            return Ok(vec![]);
+        }
+
+        self.organization_invitation_repository
+            .list_pending_by_email(&user.email, &user.id)
+            .await
+    }
+
+    pub async fn create(
+        &self,
+        organization: &Organization,
+        inviter: &User,
+        data: CreateOrganizationInvitationData,
+    ) -> OrganizationInvitationResult<CreatedOrganizationInvitation> {
+        let secret = generate_secret("", 48);
+        let now = OffsetDateTime::now_utc();
+        let email = data.email.map(|email| email.to_lowercase());
+
+        let invitation = OrganizationInvitation {
+            id: Uuid::new_v4(),
+            organization_id: organization.id,
+            // an email invitation is meant for a single person
+            max_uses: if email.is_some() {
+                Some(1)
+            } else {
+                data.max_uses
+            },
+            email,
+            token_hash: hash_secret(&secret),
+            role: data.role,
+            invited_by: Some(inviter.id),
+            uses: 0,
+            created_at: now,
+            expires_at: now
+                + data
+                    .expires_in_days
+                    .map(Duration::days)
+                    .unwrap_or(ORGANIZATION_INVITATION_TTL),
+        };
+
+        self.organization_invitation_repository
+            .insert(&invitation)
+            .await?;
+
+        let link = format!("{}/invitations/accept?token={}", *WEBSITE_URL, secret);
+
+        if let Some(email) = &invitation.email {
+            self.mailer
+                .send(&Mail::organization_invitation(
+                    email,
+                    &organization.slug,
+                    &inviter.name,
+                    &link,
+                ))
+                .await
+                .map_err(|err| OrganizationInvitationError::Unknown(err.to_string()))?;
+        }
+
+        Ok(CreatedOrganizationInvitation { invitation, link })
+    }
+
+    pub async fn accept_token(
+        &self,
+        user: &User,
+        token: &str,
+    ) -> OrganizationInvitationResult<OrganizationInvitation> {
+        let invitation = self
+            .organization_invitation_repository
+            .find_by_token_hash(&hash_secret(token))
+            .await?;
+
+        self.accept(user, invitation).await
+    }
+
+    pub async fn accept_by_id(
+        &self,
+        user: &User,
+        invitation_id: &Uuid,
+    ) -> OrganizationInvitationResult<OrganizationInvitation> {
+        let invitation = self
+            .organization_invitation_repository
+            .find_by_id(invitation_id)
+            .await?;
+
+        // link invitations can only be redeemed by whoever holds the token
+        if invitation.email.is_none() {
+            return Err(OrganizationInvitationError::NotFound);
+        }
+
+        self.accept(user, invitation).await
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        invitation_id: &Uuid,
+    ) -> OrganizationInvitationResult<()> {
+        self.organization_invitation_repository
+            .delete(organization_id, invitation_id)
+            .await
+    }
+
+    async fn accept(
+        &self,
+        user: &User,
+        invitation: OrganizationInvitation,
+    ) -> OrganizationInvitationResult<OrganizationInvitation> {
+        if !invitation.is_usable() {
+            return Err(OrganizationInvitationError::Invalid);
+        }
+
+        if let Some(email) = &invitation.email {
+            if !email.eq_ignore_ascii_case(&user.email) {
+                return Err(OrganizationInvitationError::EmailMismatch);
+            }
+            if !user.email_verified {
+                return Err(OrganizationInvitationError::EmailNotVerified);
+            }
+        }
+
+        self.organization_invitation_repository
+            .accept(&invitation.id, &user.id)
+            .await
+    }
+}
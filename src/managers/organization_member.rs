@@ -1,4 +1,6 @@
-use crate::domains::organization_member::{OrganizationMember, OrganizationMemberResult};
+use crate::domains::organization_member::{
+    OrganizationMember, OrganizationMemberResult, OrganizationMemberRole, OrganizationMemberView,
+};
 use crate::repositories::organization_member::OrganizationMemberRepository;
 use std::cmp::min;
 use uuid::Uuid;
@@ -19,7 +21,7 @@ impl OrganizationMemberManager {
         &self,
         organization_id: &Uuid,
         user_id: &Uuid,
-        min_role: i16,
+        min_role: OrganizationMemberRole,
     ) -> OrganizationMemberResult<OrganizationMember> {
         self.organization_member_repository
             .find_with_role(organization_id, user_id, min_role)
@@ -39,12 +41,32 @@ impl OrganizationMemberManager {
     pub async fn list(
         &self,
         organization_id: &Uuid,
-    ) -> OrganizationMemberResult<Vec<OrganizationMember>> {
+    ) -> OrganizationMemberResult<Vec<OrganizationMemberView>> {
         self.organization_member_repository
             .list(organization_id)
             .await
     }
 
+    pub async fn list_solely_owned_organization_ids(
+        &self,
+        user_id: &Uuid,
+    ) -> OrganizationMemberResult<Vec<Uuid>> {
+        self.organization_member_repository
+            .list_solely_owned_organization_ids(user_id, OrganizationMemberRole::Owner)
+            .await
+    }
+
+    pub async fn set_custom_role(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+        custom_role_id: &Option<Uuid>,
+    ) -> OrganizationMemberResult<()> {
+        self.organization_member_repository
+            .set_custom_role(organization_id, user_id, custom_role_id)
+            .await
+    }
+
     pub async fn insert(
         &self,
         organization_id: &Uuid,
@@ -54,4 +76,36 @@ impl OrganizationMemberManager {
             .insert(organization_id, organization_member)
             .await
     }
+
+    pub async fn update_role(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+        role: OrganizationMemberRole,
+    ) -> OrganizationMemberResult<()> {
+        self.organization_member_repository
+            .update_role(organization_id, user_id, role)
+            .await
+    }
+
+    pub async fn transfer_ownership(
+        &self,
+        organization_id: &Uuid,
+        from_user_id: &Uuid,
+        to_user_id: &Uuid,
+    ) -> OrganizationMemberResult<()> {
+        self.organization_member_repository
+            .transfer_ownership(organization_id, from_user_id, to_user_id)
+            .await
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+    ) -> OrganizationMemberResult<()> {
+        self.organization_member_repository
+            .delete(organization_id, user_id)
+            .await
+    }
 }
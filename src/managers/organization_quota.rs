@@ -0,0 +1,130 @@
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::domains::organization::Organization;
+use crate::domains::organization_quota::{
+    OrganizationQuota, OrganizationQuotaError, OrganizationQuotaResult, OrganizationQuotaView,
+    ProxyResources, QuotaExceeded, QuotaResource, UpdateOrganizationQuotaData,
+};
+use crate::managers::region_connection::RegionConnectionManager;
+use crate::repositories::organization_quota::OrganizationQuotaRepository;
+
+#[derive(Clone)]
+pub struct OrganizationQuotaManager {
+    organization_quota_repository: OrganizationQuotaRepository,
+    region_connection_manager: RegionConnectionManager,
+}
+
+impl OrganizationQuotaManager {
+    pub fn new(
+        organization_quota_repository: OrganizationQuotaRepository,
+        region_connection_manager: RegionConnectionManager,
+    ) -> Self {
+        Self {
+            organization_quota_repository,
+            region_connection_manager,
+        }
+    }
+
+    pub async fn find(&self, organization_id: &Uuid) -> OrganizationQuotaResult<OrganizationQuota> {
+        Ok(self
+            .organization_quota_repository
+            .find(organization_id)
+            .await?
+            .unwrap_or_else(|| OrganizationQuota::default_for(*organization_id)))
+    }
+
+    pub async fn view(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationQuotaResult<OrganizationQuotaView> {
+        Ok(OrganizationQuotaView {
+            quota: self.find(organization_id).await?,
+            usage: self
+                .organization_quota_repository
+                .usage(organization_id)
+                .await?,
+        })
+    }
+
+    /// Fails early when one more `resource` would exceed the quota, before anything is created
+    /// remotely. The insert re-checks under a lock, this only avoids orphaned remote resources.
+    pub async fn ensure_available(
+        &self,
+        organization_id: &Uuid,
+        resource: QuotaResource,
+    ) -> OrganizationQuotaResult<()> {
+        let quota = self.find(organization_id).await?;
+        let usage = self
+            .organization_quota_repository
+            .usage(organization_id)
+            .await?;
+
+        let (resource, limit, used) = match resource {
+            QuotaResource::ProxyTemplates => (
+                resource,
+                quota.max_proxy_templates as i64,
+                usage.proxy_templates,
+            ),
+            QuotaResource::Bridges => (resource, quota.max_bridges as i64, usage.bridges),
+            _ => unreachable!("proxies are checked with ensure_proxy_available"),
+        };
+
+        if used >= limit {
+            return Err(QuotaExceeded { resource, limit }.into());
+        }
+
+        Ok(())
+    }
+
+    /// The proxy counterpart of [`ensure_available`](Self::ensure_available), proxies count
+    /// towards the cpu and memory quotas with what their tier requests.
+    pub async fn ensure_proxy_available(
+        &self,
+        organization_id: &Uuid,
+        requested: &ProxyResources,
+    ) -> OrganizationQuotaResult<()> {
+        let quota = self.find(organization_id).await?;
+        let usage = self
+            .organization_quota_repository
+            .usage(organization_id)
+            .await?;
+
+        Ok(quota.check_proxy(&usage.proxy_usage(), requested)?)
+    }
+
+    pub async fn update(
+        &self,
+        organization: &Organization,
+        data: UpdateOrganizationQuotaData,
+    ) -> OrganizationQuotaResult<OrganizationQuota> {
+        let quota = OrganizationQuota {
+            organization_id: organization.id,
+            max_proxies: data.max_proxies,
+            max_proxy_templates: data.max_proxy_templates,
+            max_bridges: data.max_bridges,
+            cpu_millis: data.cpu_millis,
+            memory_mib: data.memory_mib,
+            updated_at: OffsetDateTime::now_utc(),
+        };
+
+        // the database is the source of truth, if the cluster can't be reached now the namespace
+        // reconciler applies the saved quota later
+        self.organization_quota_repository.upsert(&quota).await?;
+        self.apply(organization).await?;
+
+        Ok(quota)
+    }
+
+    /// Mirrors the quota into the organization namespace as a ResourceQuota and LimitRange.
+    pub async fn apply(&self, organization: &Organization) -> OrganizationQuotaResult<()> {
+        let quota = self.find(&organization.id).await?;
+
+        self.region_connection_manager
+            .find_kube_wrapped_client_by_id(&organization.region_id)
+            .await?
+            .apply_organization_quota(organization, &quota)
+            .await
+            .map_err(|err| OrganizationQuotaError::Unknown(err.to_string()))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::domains::personal_access_token::{
+    PersonalAccessToken, PersonalAccessTokenError, PersonalAccessTokenResult,
+};
+use crate::domains::user::{User, UserError};
+use crate::managers::user::UserManager;
+use crate::repositories::personal_access_token::PersonalAccessTokenRepository;
+use crate::utils::hash_secret;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct PersonalAccessTokenManager {
+    personal_access_token_repository: PersonalAccessTokenRepository,
+    user_manager: UserManager,
+}
+
+impl PersonalAccessTokenManager {
+    pub fn new(
+        personal_access_token_repository: PersonalAccessTokenRepository,
+        user_manager: UserManager,
+    ) -> Self {
+        Self {
+            personal_access_token_repository,
+            user_manager,
+        }
+    }
+
+    pub async fn list_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> PersonalAccessTokenResult<Vec<PersonalAccessToken>> {
+        self.personal_access_token_repository
+            .list_by_user_id(user_id)
+            .await
+    }
+
+    pub async fn authenticate(
+        &self,
+        secret: &str,
+    ) -> PersonalAccessTokenResult<(User, PersonalAccessToken)> {
+        let token = self
+            .personal_access_token_repository
+            .find_by_token_hash(&hash_secret(secret))
+            .await?;
+
+        let user = self
+            .user_manager
+            .find_by_id(&token.user_id)
+            .await
+            .map_err(|err| match err {
+                UserError::NotFound => PersonalAccessTokenError::Invalid,
+                err => PersonalAccessTokenError::Unknown(err.to_string()),
+            })?;
+
+        self.personal_access_token_repository
+            .touch(&token.id)
+            .await?;
+
+        Ok((user, token))
+    }
+
+    pub async fn insert(&self, token: &PersonalAccessToken) -> PersonalAccessTokenResult<()> {
+        self.personal_access_token_repository.insert(token).await
+    }
+
+    pub async fn delete_by_user_id(
+        &self,
+        user_id: &Uuid,
+        token_id: &Uuid,
+    ) -> PersonalAccessTokenResult<()> {
+        self.personal_access_token_repository
+            .delete_by_user_id(user_id, token_id)
+            .await
+    }
+}
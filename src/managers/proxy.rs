@@ -1,38 +1,72 @@
 use uuid::Uuid;
 
 use crate::domains::organization::Organization;
+use crate::domains::organization_quota::ProxyResources;
 use crate::domains::proxy::{Proxy, ProxyResult};
-use crate::domains::proxy_template::ProxyTemplate;
+use crate::managers::organization_quota::OrganizationQuotaManager;
 use crate::managers::region_connection::RegionConnectionManager;
 use crate::repositories::proxy::ProxyRepository;
 
 #[derive(Clone)]
 pub struct ProxyManager {
     proxy_repository: ProxyRepository,
+    organization_quota_manager: OrganizationQuotaManager,
 }
 
 impl ProxyManager {
     pub fn new(
         region_connection_manager: RegionConnectionManager,
         proxy_repository: ProxyRepository,
+        organization_quota_manager: OrganizationQuotaManager,
     ) -> Self {
-        Self { proxy_repository }
+        Self {
+            proxy_repository,
+            organization_quota_manager,
+        }
     }
 
     pub async fn list(&self, organization_id: &Uuid) -> ProxyResult<Vec<Proxy>> {
         self.proxy_repository.list(organization_id).await
     }
 
+    pub async fn find_by_id(&self, organization_id: &Uuid, proxy_id: &Uuid) -> ProxyResult<Proxy> {
+        self.proxy_repository
+            .find_by_id(organization_id, proxy_id)
+            .await
+    }
+
+    pub async fn ensure_quota(
+        &self,
+        organization: &Organization,
+        requested: &ProxyResources,
+    ) -> ProxyResult<()> {
+        Ok(self
+            .organization_quota_manager
+            .ensure_proxy_available(&organization.id, requested)
+            .await?)
+    }
+
     pub async fn create(
         &self,
         organization: &Organization,
-        template: &ProxyTemplate,
         proxy: &Proxy,
+        requested: &ProxyResources,
     ) -> ProxyResult<()> {
+        let quota = self
+            .organization_quota_manager
+            .find(&organization.id)
+            .await?;
+
         self.proxy_repository
-            .insert(&organization.id, proxy)
+            .insert(&organization.id, proxy, &quota, requested)
             .await?;
 
         Ok(())
     }
+
+    pub async fn delete(&self, organization_id: &Uuid, proxy_id: &Uuid) -> ProxyResult<()> {
+        self.proxy_repository
+            .delete(organization_id, proxy_id)
+            .await
+    }
 }
@@ -1,4 +1,5 @@
 use crate::domains::proxy_template::{ProxyTemplate, ProxyTemplateResult};
+use crate::managers::organization_quota::OrganizationQuotaManager;
 use crate::repositories::proxy_template::ProxyTemplateRepository;
 use sqlx::{query, query_as};
 use uuid::Uuid;
@@ -6,12 +7,17 @@ use uuid::Uuid;
 #[derive(Clone)]
 pub struct ProxyTemplateManager {
     proxy_template_repository: ProxyTemplateRepository,
+    organization_quota_manager: OrganizationQuotaManager,
 }
 
 impl ProxyTemplateManager {
-    pub fn new(proxy_template_repository: ProxyTemplateRepository) -> Self {
+    pub fn new(
+        proxy_template_repository: ProxyTemplateRepository,
+        organization_quota_manager: OrganizationQuotaManager,
+    ) -> Self {
         Self {
             proxy_template_repository,
+            organization_quota_manager,
         }
     }
 
@@ -19,6 +25,16 @@ impl ProxyTemplateManager {
         self.proxy_template_repository.list(organization_id).await
     }
 
+    pub async fn find_by_id(
+        &self,
+        organization_id: &Uuid,
+        template_id: &Uuid,
+    ) -> ProxyTemplateResult<ProxyTemplate> {
+        self.proxy_template_repository
+            .find_by_id(organization_id, template_id)
+            .await
+    }
+
     pub async fn find_by_slug(
         &self,
         organization_id: &Uuid,
@@ -34,8 +50,17 @@ impl ProxyTemplateManager {
         organization_id: &Uuid,
         proxy_template: &ProxyTemplate,
     ) -> ProxyTemplateResult<()> {
+        let quota = self
+            .organization_quota_manager
+            .find(organization_id)
+            .await?;
+
         self.proxy_template_repository
-            .insert(organization_id, proxy_template)
+            .insert(
+                organization_id,
+                proxy_template,
+                quota.max_proxy_templates as i64,
+            )
             .await
     }
 }
@@ -1,21 +1,189 @@
-use crate::domains::region::{Region, RegionResult};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::domains::region::{
+    CreateRegionData, Region, RegionError, RegionResult, RegionStatus, RegionView, UpdateRegionData,
+};
+use crate::managers::region_connection::RegionConnectionManager;
 use crate::repositories::regions::RegionRepository;
 
 #[derive(Clone)]
 pub struct RegionManager {
     region_repository: RegionRepository,
+    region_connection_manager: RegionConnectionManager,
 }
 
 impl RegionManager {
-    pub fn new(region_repository: RegionRepository) -> Self {
-        Self { region_repository }
+    pub fn new(
+        region_repository: RegionRepository,
+        region_connection_manager: RegionConnectionManager,
+    ) -> Self {
+        Self {
+            region_repository,
+            region_connection_manager,
+        }
+    }
+
+    pub async fn reseal_all(&self) -> RegionResult<usize> {
+        self.region_repository.reseal_all().await
+    }
+
+    /// A region that can't be connected is marked unreachable instead of failing the others,
+    /// the health monitor keeps retrying it.
+    pub async fn connect_all(&self) -> RegionResult<()> {
+        for region in self.region_repository.list().await? {
+            self.connect_or_mark(&region).await;
+        }
+
+        Ok(())
+    }
+
+    async fn connect_or_mark(&self, region: &Region) -> bool {
+        match self.region_connection_manager.connect(region).await {
+            Ok(()) => true,
+            Err(err) => {
+                error!("failed to connect region {}: {}", region.slug, err);
+                self.region_connection_manager
+                    .mark_unreachable(&region.id)
+                    .await;
+                false
+            }
+        }
+    }
+
+    pub fn spawn_health_monitor(&self, period: std::time::Duration) {
+        let region_manager = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+
+                let regions = match region_manager.region_repository.list().await {
+                    Ok(regions) => regions,
+                    Err(err) => {
+                        error!("failed to list regions for health probes: {}", err);
+                        continue;
+                    }
+                };
+
+                for region in regions {
+                    let connection_manager = &region_manager.region_connection_manager;
+                    if !connection_manager.is_connected(&region.id).await
+                        && !region_manager.connect_or_mark(&region).await
+                    {
+                        continue;
+                    }
+
+                    let previous = connection_manager.health(&region.id).await.status;
+                    let health = connection_manager.probe(&region.id).await;
+                    match (previous, health.status) {
+                        (RegionStatus::Degraded, RegionStatus::Degraded) => {}
+                        (_, RegionStatus::Degraded) => warn!(
+                            "region {} is degraded (kube: {}, bridge: {})",
+                            region.slug, health.kube, health.bridge
+                        ),
+                        (RegionStatus::Degraded, _) => {
+                            info!("region {} recovered", region.slug)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        });
     }
 
     pub async fn list(&self) -> RegionResult<Vec<Region>> {
         self.region_repository.list().await
     }
 
+    pub async fn list_enabled(&self) -> RegionResult<Vec<RegionView>> {
+        let mut views = vec![];
+        for region in self.region_repository.list_enabled().await? {
+            views.push(RegionView {
+                health: self.region_connection_manager.health(&region.id).await,
+                region,
+            });
+        }
+
+        Ok(views)
+    }
+
+    pub async fn find_by_id(&self, region_id: &Uuid) -> RegionResult<Region> {
+        self.region_repository.find_by_id(region_id).await
+    }
+
     pub async fn find_by_slug(&self, slug: &String) -> RegionResult<Region> {
         self.region_repository.find_by_slug(slug).await
     }
+
+    /// Like [`find_by_slug`](Self::find_by_slug) but rejects regions closed to new organizations.
+    pub async fn find_enabled_by_slug(&self, slug: &String) -> RegionResult<Region> {
+        let region = self.region_repository.find_by_slug(slug).await?;
+        if region.disabled {
+            return Err(RegionError::Disabled);
+        }
+
+        Ok(region)
+    }
+
+    pub async fn create(&self, data: CreateRegionData) -> RegionResult<Region> {
+        let region = Region {
+            id: Uuid::new_v4(),
+            slug: data.slug,
+            options: data.options,
+            disabled: false,
+        };
+
+        self.region_repository.insert(&region).await?;
+
+        if let Err(err) = self.region_connection_manager.connect(&region).await {
+            self.region_repository.delete(&region.id).await?;
+            return Err(err);
+        }
+
+        Ok(region)
+    }
+
+    pub async fn update(&self, region_id: &Uuid, data: UpdateRegionData) -> RegionResult<Region> {
+        let previous = self.region_repository.find_by_id(region_id).await?;
+
+        let mut region = previous.clone();
+        if let Some(slug) = data.slug {
+            region.slug = slug;
+        }
+        let reconnect = data.options.is_some();
+        if let Some(options) = data.options {
+            region.options = options;
+        }
+
+        self.region_repository.update(&region).await?;
+
+        if reconnect {
+            if let Err(err) = self.region_connection_manager.connect(&region).await {
+                self.region_repository.update(&previous).await?;
+                return Err(err);
+            }
+        }
+
+        Ok(region)
+    }
+
+    pub async fn set_disabled(&self, region_id: &Uuid, disabled: bool) -> RegionResult<Region> {
+        let mut region = self.region_repository.find_by_id(region_id).await?;
+        region.disabled = disabled;
+
+        self.region_repository.update(&region).await?;
+
+        Ok(region)
+    }
+
+    pub async fn delete(&self, region_id: &Uuid) -> RegionResult<()> {
+        self.region_repository.find_by_id(region_id).await?;
+        self.region_repository.delete(region_id).await?;
+
+        self.region_connection_manager.disconnect(region_id).await;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,76 @@
+use uuid::Uuid;
+
+use crate::domains::organization_quota::ProxyResources;
+use crate::domains::region_capacity::{RegionCapacity, RegionCapacityResult, RegionFull};
+use crate::domains::tier::Tier;
+use crate::managers::region_connection::RegionConnectionManager;
+use crate::managers::tier::TierManager;
+
+#[derive(Clone)]
+pub struct RegionCapacityManager {
+    region_connection_manager: RegionConnectionManager,
+    tier_manager: TierManager,
+}
+
+impl RegionCapacityManager {
+    pub fn new(
+        region_connection_manager: RegionConnectionManager,
+        tier_manager: TierManager,
+    ) -> Self {
+        Self {
+            region_connection_manager,
+            tier_manager,
+        }
+    }
+
+    pub async fn capacity(&self, region_id: &Uuid) -> RegionCapacityResult<RegionCapacity> {
+        let nodes = self
+            .region_connection_manager
+            .find_kube_wrapped_client_by_id(region_id)
+            .await?
+            .node_capacities()
+            .await?;
+        let tiers = self.tier_manager.list_by_region(region_id).await?;
+
+        Ok(RegionCapacity::new(*region_id, nodes, &tiers))
+    }
+
+    /// Rejects a proxy no node could schedule instead of leaving a Pending pod. Capacities come
+    /// from the last region probe, so proxies created since then aren't accounted for and
+    /// concurrent creations may still race for the last slot.
+    pub async fn ensure_schedulable(
+        &self,
+        region_id: &Uuid,
+        tier: Option<&Tier>,
+        resources: &ProxyResources,
+    ) -> RegionCapacityResult<()> {
+        let nodes = match self
+            .region_connection_manager
+            .node_capacities(region_id)
+            .await
+        {
+            Some(nodes) => nodes,
+            None => {
+                self.region_connection_manager
+                    .find_kube_wrapped_client_by_id(region_id)
+                    .await?
+                    .node_capacities()
+                    .await?
+            }
+        };
+
+        let tag = tier.map(|tier| tier.tag.as_str()).unwrap_or_default();
+        if nodes
+            .iter()
+            .any(|node| node.serves(tag) && node.slots(resources) > 0)
+        {
+            return Ok(());
+        }
+
+        Err(RegionFull {
+            region_id: *region_id,
+            tier_slug: tier.map(|tier| tier.slug.clone()),
+        }
+        .into())
+    }
+}
@@ -3,59 +3,183 @@ use std::sync::Arc;
 
 use kube::config::KubeConfigOptions;
 use tokio::sync::RwLock;
+use tracing::warn;
 use uuid::Uuid;
 
 use crate::clients::bridge_service::BridgeServiceClient;
 use crate::clients::kube::KubeWrappedClient;
-use crate::managers::region::RegionManager;
+use crate::consts::REGION_PROBE_TIMEOUT;
+use crate::domains::region::{Region, RegionError, RegionHealth, RegionResult, RegionUnavailable};
+use crate::domains::region_capacity::NodeCapacity;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct RegionConnectionManager {
     kube: Arc<RwLock<HashMap<Uuid, KubeWrappedClient>>>,
     bridge: Arc<RwLock<HashMap<Uuid, BridgeServiceClient>>>,
+    health: Arc<RwLock<HashMap<Uuid, RegionHealth>>>,
+    nodes: Arc<RwLock<HashMap<Uuid, Vec<NodeCapacity>>>>,
 }
 
 impl RegionConnectionManager {
-    pub async fn new(region_manager: RegionManager) -> Self {
-        let kube: Arc<RwLock<HashMap<Uuid, KubeWrappedClient>>> = Default::default();
-        let bridge: Arc<RwLock<HashMap<Uuid, BridgeServiceClient>>> = Default::default();
-
-        for region in region_manager.list().await.unwrap() {
-            let client: kube::Client = kube::Config::from_custom_kubeconfig(
-                region.options.kube.clone(),
-                &KubeConfigOptions::default(),
-            )
-            .await
-            .unwrap()
-            .try_into()
-            .unwrap();
-
-            let mut kube = kube.write().await;
-            kube.insert(region.id, KubeWrappedClient::new(client));
-
-            let mut bridge = bridge.write().await;
-            bridge.insert(
-                region.id,
-                BridgeServiceClient::new(region.options.bridge.base_path.clone()),
-            );
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the clients of a region and adds them, replacing any previous ones. Nothing is
+    /// touched when the options can't be turned into clients.
+    pub async fn connect(&self, region: &Region) -> RegionResult<()> {
+        let client: kube::Client = kube::Config::from_custom_kubeconfig(
+            region.options.kube.clone(),
+            &KubeConfigOptions::default(),
+        )
+        .await
+        .map_err(|err| RegionError::InvalidOptions(err.to_string()))?
+        .try_into()
+        .map_err(|err: kube::Error| RegionError::InvalidOptions(err.to_string()))?;
+
+        let mut kube = self.kube.write().await;
+        let mut bridge = self.bridge.write().await;
+        let mut health = self.health.write().await;
+        let mut nodes = self.nodes.write().await;
+        kube.insert(
+            region.id,
+            KubeWrappedClient::new(client, region.options.bridge.namespace.clone()),
+        );
+        bridge.insert(
+            region.id,
+            BridgeServiceClient::new(region.options.bridge.base_path.clone()),
+        );
+        health.insert(region.id, RegionHealth::unknown());
+        nodes.remove(&region.id);
+
+        Ok(())
+    }
+
+    pub async fn disconnect(&self, region_id: &Uuid) {
+        let mut kube = self.kube.write().await;
+        let mut bridge = self.bridge.write().await;
+        let mut health = self.health.write().await;
+        let mut nodes = self.nodes.write().await;
+        kube.remove(region_id);
+        bridge.remove(region_id);
+        health.remove(region_id);
+        nodes.remove(region_id);
+    }
+
+    pub async fn is_connected(&self, region_id: &Uuid) -> bool {
+        let kube = self.kube.read().await;
+        kube.contains_key(region_id)
+    }
+
+    /// Regions that couldn't be connected are degraded until a later attempt succeeds.
+    pub async fn mark_unreachable(&self, region_id: &Uuid) {
+        let mut health = self.health.write().await;
+        health.insert(*region_id, RegionHealth::probed(false, false));
+    }
+
+    pub async fn health(&self, region_id: &Uuid) -> RegionHealth {
+        let health = self.health.read().await;
+        health
+            .get(region_id)
+            .cloned()
+            .unwrap_or_else(RegionHealth::unknown)
+    }
+
+    /// Calls the cluster API server and the bridge service of a region and records the outcome.
+    pub async fn probe(&self, region_id: &Uuid) -> RegionHealth {
+        let kube_client = self.kube.read().await.get(region_id).cloned();
+        let bridge_client = self.bridge.read().await.get(region_id).cloned();
+
+        let kube = match &kube_client {
+            Some(client) => matches!(
+                tokio::time::timeout(REGION_PROBE_TIMEOUT, client.ping()).await,
+                Ok(Ok(_))
+            ),
+            None => false,
+        };
+        let bridge = match bridge_client {
+            Some(client) => matches!(
+                tokio::time::timeout(REGION_PROBE_TIMEOUT, client.ping()).await,
+                Ok(Ok(_))
+            ),
+            None => false,
+        };
+
+        if let (true, Some(client)) = (kube, &kube_client) {
+            self.refresh_node_capacities(region_id, client).await;
         }
 
-        Self { kube, bridge }
+        let region_health = RegionHealth::probed(kube, bridge);
+        let mut health = self.health.write().await;
+        health.insert(*region_id, region_health.clone());
+
+        region_health
+    }
+
+    /// Node capacities as of the last probe, absent until the cluster was reached once.
+    pub async fn node_capacities(&self, region_id: &Uuid) -> Option<Vec<NodeCapacity>> {
+        let nodes = self.nodes.read().await;
+        nodes.get(region_id).cloned()
+    }
+
+    // listing every pod is too heavy for each proxy creation, so it is done along with probes
+    // and the last known capacities are kept when it fails
+    async fn refresh_node_capacities(&self, region_id: &Uuid, client: &KubeWrappedClient) {
+        match tokio::time::timeout(REGION_PROBE_TIMEOUT, client.node_capacities()).await {
+            Ok(Ok(capacities)) => {
+                let mut nodes = self.nodes.write().await;
+                nodes.insert(*region_id, capacities);
+            }
+            Ok(Err(err)) => warn!(
+                "failed to list node capacities of region {}: {}",
+                region_id, err
+            ),
+            Err(_) => warn!("timed out listing node capacities of region {}", region_id),
+        }
     }
 
     pub async fn find_kube_wrapped_client_by_id(
         &self,
         region_id: &Uuid,
-    ) -> Option<KubeWrappedClient> {
+    ) -> Result<KubeWrappedClient, RegionUnavailable> {
+        self.ensure_available(region_id, |health| health.kube)
+            .await?;
+
         let kube = self.kube.read().await;
-        kube.get(region_id).cloned()
+        kube.get(region_id).cloned().ok_or(RegionUnavailable {
+            region_id: *region_id,
+        })
     }
 
     pub async fn find_bridge_service_client_by_id(
         &self,
         region_id: &Uuid,
-    ) -> Option<BridgeServiceClient> {
+    ) -> Result<BridgeServiceClient, RegionUnavailable> {
+        self.ensure_available(region_id, |health| health.bridge)
+            .await?;
+
         let bridge = self.bridge.read().await;
-        bridge.get(region_id).cloned()
+        bridge.get(region_id).cloned().ok_or(RegionUnavailable {
+            region_id: *region_id,
+        })
+    }
+
+    /// Only the component the caller needs has to be up, a bridge outage doesn't block
+    /// operations that only talk to the cluster and vice versa. Regions that weren't probed yet
+    /// are assumed to be available.
+    async fn ensure_available(
+        &self,
+        region_id: &Uuid,
+        reachable: impl Fn(&RegionHealth) -> bool,
+    ) -> Result<(), RegionUnavailable> {
+        let health = self.health.read().await;
+        match health.get(region_id) {
+            Some(health) if health.checked_at.is_some() && !reachable(health) => {
+                Err(RegionUnavailable {
+                    region_id: *region_id,
+                })
+            }
+            _ => Ok(()),
+        }
     }
 }
@@ -1,6 +1,8 @@
-use crate::domains::session::{Session, SessionResult};
-use crate::domains::user::User;
+use crate::consts::{SESSION_RENEWAL_THRESHOLD, SESSION_TTL};
+use crate::domains::session::{Session, SessionResult, SessionUser};
 use crate::repositories::session::SessionRepository;
+use time::OffsetDateTime;
+use tracing::{error, info};
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -13,13 +15,80 @@ impl SessionManager {
         Self { session_repository }
     }
 
-    pub async fn find_user_by_session_id(&self, session_id: &Uuid) -> SessionResult<User> {
+    pub async fn list_by_user_id(&self, user_id: &Uuid) -> SessionResult<Vec<Session>> {
+        self.session_repository.list_by_user_id(user_id).await
+    }
+
+    pub async fn find_user_by_session_id(&self, session_id: &Uuid) -> SessionResult<SessionUser> {
         self.session_repository
             .find_user_by_session_id(session_id)
             .await
     }
 
+    pub async fn find_by_id(&self, session_id: &Uuid) -> SessionResult<Session> {
+        self.session_repository.find_by_id(session_id).await
+    }
+
+    pub async fn find_two_factor_pending(&self, session_id: &Uuid) -> SessionResult<Session> {
+        self.session_repository
+            .find_two_factor_pending(session_id)
+            .await
+    }
+
     pub async fn insert(&self, session: &Session) -> SessionResult<()> {
         self.session_repository.insert(session).await
     }
+
+    pub async fn touch(&self, session_id: &Uuid) -> SessionResult<Option<OffsetDateTime>> {
+        let now = OffsetDateTime::now_utc();
+        let expires_at = now + SESSION_TTL;
+
+        let renewed = self
+            .session_repository
+            .touch(session_id, &(now + SESSION_RENEWAL_THRESHOLD), &expires_at)
+            .await?;
+
+        Ok(renewed.then_some(expires_at))
+    }
+
+    pub async fn increment_two_factor_attempts(&self, session_id: &Uuid) -> SessionResult<i16> {
+        self.session_repository
+            .increment_two_factor_attempts(session_id)
+            .await
+    }
+
+    pub async fn delete(&self, session_id: &Uuid) -> SessionResult<()> {
+        self.session_repository.delete(session_id).await
+    }
+
+    pub async fn delete_by_public_id(
+        &self,
+        user_id: &Uuid,
+        public_id: &Uuid,
+    ) -> SessionResult<Uuid> {
+        self.session_repository
+            .delete_by_public_id(user_id, public_id)
+            .await
+    }
+
+    pub async fn delete_all_by_user_id(&self, user_id: &Uuid) -> SessionResult<u64> {
+        self.session_repository.delete_all_by_user_id(user_id).await
+    }
+
+    pub fn spawn_expired_sweeper(&self, period: std::time::Duration) {
+        let session_repository = self.session_repository.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+
+                match session_repository.delete_expired().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("purged {} expired sessions", count),
+                    Err(err) => error!("failed to purge expired sessions: {}", err),
+                }
+            }
+        });
+    }
 }
@@ -0,0 +1,28 @@
+use uuid::Uuid;
+
+use crate::domains::tier::{Tier, TierResult};
+use crate::repositories::tier::TierRepository;
+
+#[derive(Clone)]
+pub struct TierManager {
+    tier_repository: TierRepository,
+}
+
+impl TierManager {
+    pub fn new(tier_repository: TierRepository) -> Self {
+        Self { tier_repository }
+    }
+
+    pub async fn list_by_region(&self, region_id: &Uuid) -> TierResult<Vec<Tier>> {
+        self.tier_repository.list_by_region(region_id).await
+    }
+
+    pub async fn find_by_id(&self, tier_id: &Uuid) -> TierResult<Tier> {
+        self.tier_repository.find_by_id(tier_id).await
+    }
+
+    /// Tiers belong to a region, a template may only use one from its organization's region.
+    pub async fn find_by_slug(&self, region_id: &Uuid, slug: &String) -> TierResult<Tier> {
+        self.tier_repository.find_by_slug(region_id, slug).await
+    }
+}
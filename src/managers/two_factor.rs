@@ -0,0 +1,128 @@
+use crate::domains::two_factor::{
+    RecoveryCodes, TwoFactorEnrolment, TwoFactorError, TwoFactorResult, TwoFactorStatus,
+};
+use crate::domains::user::User;
+use crate::repositories::two_factor::TwoFactorRepository;
+use crate::totp;
+use crate::utils::{generate_secret, hash_secret};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Clone)]
+pub struct TwoFactorManager {
+    two_factor_repository: TwoFactorRepository,
+}
+
+impl TwoFactorManager {
+    pub fn new(two_factor_repository: TwoFactorRepository) -> Self {
+        Self {
+            two_factor_repository,
+        }
+    }
+
+    pub async fn status(&self, user_id: &Uuid) -> TwoFactorResult<TwoFactorStatus> {
+        let enabled = self.is_enabled(user_id).await?;
+        let recovery_codes_remaining = self
+            .two_factor_repository
+            .count_recovery_codes(user_id)
+            .await?;
+
+        Ok(TwoFactorStatus {
+            enabled,
+            recovery_codes_remaining,
+        })
+    }
+
+    pub async fn is_enabled(&self, user_id: &Uuid) -> TwoFactorResult<bool> {
+        match self.two_factor_repository.find_by_user_id(user_id).await {
+            Ok(user_totp) => Ok(user_totp.enabled),
+            Err(TwoFactorError::NotEnrolled) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn enrol(&self, user: &User) -> TwoFactorResult<TwoFactorEnrolment> {
+        let secret = totp::generate_secret();
+
+        self.two_factor_repository
+            .upsert_pending(&user.id, &secret)
+            .await?;
+
+        Ok(TwoFactorEnrolment {
+            otpauth_uri: totp::provisioning_uri(&secret, &user.email),
+            secret,
+        })
+    }
+
+    pub async fn confirm(&self, user_id: &Uuid, code: &str) -> TwoFactorResult<RecoveryCodes> {
+        let user_totp = self.two_factor_repository.find_by_user_id(user_id).await?;
+        if user_totp.enabled {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        let counter = totp::verify(&user_totp.secret, code, OffsetDateTime::now_utc())
+            .ok_or(TwoFactorError::InvalidCode)?;
+
+        let recovery_codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let code = generate_secret("", 10).to_lowercase();
+                format!("{}-{}", &code[..5], &code[5..])
+            })
+            .collect();
+        let recovery_code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| hash_recovery_code(code))
+            .collect();
+
+        self.two_factor_repository
+            .enable(user_id, counter as i64, &recovery_code_hashes)
+            .await?;
+
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    pub async fn verify(&self, user_id: &Uuid, code: &str) -> TwoFactorResult<()> {
+        let user_totp = self.two_factor_repository.find_by_user_id(user_id).await?;
+        if !user_totp.enabled {
+            return Err(TwoFactorError::NotEnrolled);
+        }
+
+        if let Some(counter) = totp::verify(&user_totp.secret, code, OffsetDateTime::now_utc()) {
+            // a code can only be used once, even though it stays valid for the whole time step
+            return match self
+                .two_factor_repository
+                .record_counter(user_id, counter as i64)
+                .await?
+            {
+                true => Ok(()),
+                false => Err(TwoFactorError::InvalidCode),
+            };
+        }
+
+        if self
+            .two_factor_repository
+            .consume_recovery_code(user_id, &hash_recovery_code(code))
+            .await?
+        {
+            return Ok(());
+        }
+
+        Err(TwoFactorError::InvalidCode)
+    }
+
+    pub async fn disable(&self, user_id: &Uuid, code: &str) -> TwoFactorResult<()> {
+        self.verify(user_id, code).await?;
+        self.two_factor_repository.delete(user_id).await
+    }
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|char| char.is_ascii_alphanumeric())
+        .collect();
+
+    hash_secret(&normalized.to_lowercase())
+}
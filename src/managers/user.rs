@@ -1,5 +1,8 @@
 use crate::domains::user::{User, UserResult};
 use crate::repositories::user::UserRepository;
+use crate::utils::like_pattern;
+use time::OffsetDateTime;
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct UserManager {
@@ -11,10 +14,63 @@ impl UserManager {
         Self { user_repository }
     }
 
+    pub async fn find_by_id(&self, user_id: &Uuid) -> UserResult<User> {
+        self.user_repository.find_by_id(user_id).await
+    }
+
     pub async fn find_by_email(&self, email: &String) -> UserResult<User> {
         self.user_repository.find_by_email(email).await
     }
 
+    pub async fn search(
+        &self,
+        query: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> UserResult<Vec<User>> {
+        let pattern = query.map(like_pattern);
+
+        self.user_repository
+            .search(pattern.as_ref(), limit, offset)
+            .await
+    }
+
+    pub async fn suspend(&self, user_id: &Uuid) -> UserResult<()> {
+        self.user_repository
+            .set_suspended_at(user_id, &Some(OffsetDateTime::now_utc()))
+            .await
+    }
+
+    pub async fn unsuspend(&self, user_id: &Uuid) -> UserResult<()> {
+        self.user_repository.set_suspended_at(user_id, &None).await
+    }
+
+    pub async fn set_role(&self, user_id: &Uuid, role: i16) -> UserResult<()> {
+        self.user_repository.set_role(user_id, role).await
+    }
+
+    pub async fn update(&self, user: &User) -> UserResult<()> {
+        self.user_repository.update(user).await
+    }
+
+    pub async fn delete(&self, user_id: &Uuid) -> UserResult<()> {
+        self.user_repository.delete(user_id).await
+    }
+
+    pub async fn set_email_verified(&self, user_id: &Uuid) -> UserResult<()> {
+        self.user_repository.set_email_verified(user_id).await
+    }
+
+    pub async fn update_password_hash(
+        &self,
+        user_id: &Uuid,
+        password_hash: &String,
+    ) -> UserResult<()> {
+        self.user_repository
+            .update_password_hash(user_id, password_hash)
+            .await
+    }
+
     pub async fn insert(&self, user: &User) -> UserResult<()> {
         self.user_repository.insert(user).await
     }
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::clients::mailer::{Mail, Mailer};
+use crate::consts::WEBSITE_URL;
+use crate::domains::user::User;
+use crate::domains::user_token::{UserToken, UserTokenError, UserTokenKind, UserTokenResult};
+use crate::repositories::user_token::UserTokenRepository;
+use crate::utils::{generate_secret, hash_secret};
+
+#[derive(Clone)]
+pub struct UserTokenManager {
+    user_token_repository: UserTokenRepository,
+    mailer: Arc<dyn Mailer>,
+}
+
+impl UserTokenManager {
+    pub fn new(user_token_repository: UserTokenRepository, mailer: Arc<dyn Mailer>) -> Self {
+        Self {
+            user_token_repository,
+            mailer,
+        }
+    }
+
+    pub async fn send_email_verification(&self, user: &User) -> UserTokenResult<()> {
+        let secret = self
+            .issue(user, UserTokenKind::EmailVerification, Duration::days(2))
+            .await?;
+        let link = format!("{}/verify-email?token={}", *WEBSITE_URL, secret);
+
+        self.send(Mail::email_verification(&user.email, &link))
+            .await
+    }
+
+    pub async fn send_password_reset(&self, user: &User) -> UserTokenResult<()> {
+        let secret = self
+            .issue(user, UserTokenKind::PasswordReset, Duration::hours(1))
+            .await?;
+        let link = format!("{}/password-reset?token={}", *WEBSITE_URL, secret);
+
+        self.send(Mail::password_reset(&user.email, &link)).await
+    }
+
+    pub async fn consume(&self, kind: UserTokenKind, secret: &str) -> UserTokenResult<UserToken> {
+        self.user_token_repository
+            .consume(kind, &hash_secret(secret))
+            .await
+    }
+
+    async fn issue(
+        &self,
+        user: &User,
+        kind: UserTokenKind,
+        ttl: Duration,
+    ) -> UserTokenResult<String> {
+        // only the latest link of a kind stays valid
+        self.user_token_repository
+            .delete_unused(&user.id, kind)
+            .await?;
+
+        let secret = generate_secret("", 48);
+        let now = OffsetDateTime::now_utc();
+
+        self.user_token_repository
+            .insert(&UserToken {
+                id: Uuid::new_v4(),
+                user_id: user.id,
+                kind: kind.as_str().to_string(),
+                token_hash: hash_secret(&secret),
+                created_at: now,
+                expires_at: now + ttl,
+                used_at: None,
+            })
+            .await?;
+
+        Ok(secret)
+    }
+
+    async fn send(&self, mail: Mail) -> UserTokenResult<()> {
+        self.mailer
+            .send(&mail)
+            .await
+            .map_err(|err| UserTokenError::Unknown(err.to_string()))
+    }
+}
@@ -0,0 +1,50 @@
+use std::sync::{Arc, Mutex};
+
+use axum::http::header::SET_COOKIE;
+use axum::http::Request;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum_extra::extract::CookieJar;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::consts::SESSION_COOKIE_NAME;
+use crate::domains::session::session_cookie;
+
+#[derive(Clone, Default)]
+pub struct SessionRenewal(Arc<Mutex<Option<(Uuid, OffsetDateTime)>>>);
+
+impl SessionRenewal {
+    pub fn set(&self, session_id: Uuid, expires_at: OffsetDateTime) {
+        *self.0.lock().unwrap() = Some((session_id, expires_at));
+    }
+
+    fn take(&self) -> Option<(Uuid, OffsetDateTime)> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+pub async fn reissue_session_cookie<B>(mut request: Request<B>, next: Next<B>) -> Response {
+    let renewal = SessionRenewal::default();
+    request.extensions_mut().insert(renewal.clone());
+
+    let response = next.run(request).await;
+
+    let Some((session_id, expires_at)) = renewal.take() else {
+        return response;
+    };
+
+    // the handler already decided what to do with the session cookie (e.g. logout)
+    let cookie_prefix = format!("{}=", SESSION_COOKIE_NAME);
+    let sets_session_cookie = response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .any(|value| value.as_bytes().starts_with(cookie_prefix.as_bytes()));
+    if sets_session_cookie {
+        return response;
+    }
+
+    let jar = CookieJar::new().add(session_cookie(&session_id, expires_at));
+    (jar, response).into_response()
+}
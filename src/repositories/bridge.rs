@@ -1,6 +1,8 @@
 use uuid::Uuid;
 
 use crate::domains::bridge::{Bridge, BridgeError, BridgeResult};
+use crate::domains::organization_quota::{QuotaExceeded, QuotaResource};
+use crate::repositories::organization_quota::lock_and_count;
 
 #[derive(Clone)]
 pub struct BridgeRepository {
@@ -12,6 +14,15 @@ impl BridgeRepository {
         Self { pg_pool }
     }
 
+    pub async fn list(&self, organization_id: &Uuid) -> BridgeResult<Vec<Bridge>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM bridges WHERE organization_id = $1;")
+                .bind(organization_id)
+                .fetch_all(&self.pg_pool)
+                .await?,
+        )
+    }
+
     pub async fn find_by_slug(
         &self,
         organization_id: &Uuid,
@@ -25,7 +36,22 @@ impl BridgeRepository {
             .ok_or(BridgeError::NotFound)
     }
 
-    pub async fn insert(&self, organization_id: &Uuid, bridge: &Bridge) -> BridgeResult<()> {
+    pub async fn insert(
+        &self,
+        organization_id: &Uuid,
+        bridge: &Bridge,
+        limit: i64,
+    ) -> BridgeResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        if lock_and_count(&mut transaction, organization_id, "bridges").await? >= limit {
+            return Err(QuotaExceeded {
+                resource: QuotaResource::Bridges,
+                limit,
+            }
+            .into());
+        }
+
         sqlx::query(
             "INSERT INTO bridges(id, slug, bs_namespace_id, organization_id) VALUES ($1, $2, $3, $4);",
         )
@@ -33,9 +59,11 @@ impl BridgeRepository {
         .bind(&bridge.slug)
         .bind(&bridge.bs_namespace_id)
         .bind(&organization_id)
-        .execute(&self.pg_pool)
+        .execute(&mut *transaction)
         .await?;
 
+        transaction.commit().await?;
+
         Ok(())
     }
 }
@@ -0,0 +1,84 @@
+use crate::domains::identity::{Identity, IdentityError, IdentityResult};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct IdentityRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl IdentityRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list_by_user_id(&self, user_id: &Uuid) -> IdentityResult<Vec<Identity>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM identities WHERE user_id = $1 ORDER BY created_at;")
+                .bind(user_id)
+                .fetch_all(&self.pg_pool)
+                .await?,
+        )
+    }
+
+    pub async fn find_by_subject(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> IdentityResult<Option<Identity>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM identities WHERE provider = $1 AND subject = $2;")
+                .bind(provider)
+                .bind(subject)
+                .fetch_optional(&self.pg_pool)
+                .await?,
+        )
+    }
+
+    pub async fn insert(&self, identity: &Identity) -> IdentityResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO identities(id, user_id, provider, subject, email, created_at, last_login_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+        )
+        .bind(&identity.id)
+        .bind(&identity.user_id)
+        .bind(&identity.provider)
+        .bind(&identity.subject)
+        .bind(&identity.email)
+        .bind(&identity.created_at)
+        .bind(&identity.last_login_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn touch(&self, identity_id: &Uuid, email: &Option<String>) -> IdentityResult<()> {
+        sqlx::query("UPDATE identities SET last_login_at = NOW(), email = $2 WHERE id = $1;")
+            .bind(identity_id)
+            .bind(email)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_by_user_id(
+        &self,
+        user_id: &Uuid,
+        identity_id: &Uuid,
+    ) -> IdentityResult<()> {
+        let result = sqlx::query("DELETE FROM identities WHERE id = $1 AND user_id = $2;")
+            .bind(identity_id)
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(IdentityError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use crate::domains::impersonation::{ImpersonationAuditEntry, ImpersonationResult};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ImpersonationRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl ImpersonationRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list_audit_entries(
+        &self,
+        user_id: Option<&Uuid>,
+        impersonator_id: Option<&Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> ImpersonationResult<Vec<ImpersonationAuditEntry>> {
+        Ok(sqlx::query_as(
+            r#"
+        SELECT * 
+        FROM impersonation_audit_log 
+        WHERE ($1::UUID IS NULL OR user_id = $1) 
+            AND ($2::UUID IS NULL OR impersonator_id = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4;
+        "#,
+        )
+        .bind(user_id)
+        .bind(impersonator_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn insert_audit_entry(
+        &self,
+        entry: &ImpersonationAuditEntry,
+    ) -> ImpersonationResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO impersonation_audit_log(id, session_id, impersonator_id, user_id, method, path, allowed, created_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8);
+        "#,
+        )
+        .bind(&entry.id)
+        .bind(&entry.session_id)
+        .bind(&entry.impersonator_id)
+        .bind(&entry.user_id)
+        .bind(&entry.method)
+        .bind(&entry.path)
+        .bind(&entry.allowed)
+        .bind(&entry.created_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use crate::domains::login_attempt::{LoginAttemptResult, LoginAttemptScope};
+use time::OffsetDateTime;
+
+#[derive(Clone)]
+pub struct LoginAttemptRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl LoginAttemptRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn find_locked_until(
+        &self,
+        scope: LoginAttemptScope,
+        key: &str,
+    ) -> LoginAttemptResult<Option<OffsetDateTime>> {
+        Ok(sqlx::query_scalar(
+            "SELECT locked_until FROM login_attempts WHERE scope = $1 AND key = $2 AND locked_until > NOW();",
+        )
+        .bind(scope.as_str())
+        .bind(key)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .flatten())
+    }
+
+    pub async fn increment_failures(
+        &self,
+        scope: LoginAttemptScope,
+        key: &str,
+        window_start: &OffsetDateTime,
+    ) -> LoginAttemptResult<i32> {
+        Ok(sqlx::query_scalar(
+            r#"
+        INSERT INTO login_attempts(scope, key, failures, last_failure_at) 
+        VALUES ($1, $2, 1, NOW())
+        ON CONFLICT (scope, key) DO UPDATE 
+        SET failures = CASE 
+                WHEN login_attempts.last_failure_at < $3 THEN 1 
+                ELSE login_attempts.failures + 1 
+            END,
+            last_failure_at = NOW()
+        RETURNING failures;
+        "#,
+        )
+        .bind(scope.as_str())
+        .bind(key)
+        .bind(window_start)
+        .fetch_one(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn lock(
+        &self,
+        scope: LoginAttemptScope,
+        key: &str,
+        locked_until: &OffsetDateTime,
+    ) -> LoginAttemptResult<()> {
+        sqlx::query("UPDATE login_attempts SET locked_until = $3 WHERE scope = $1 AND key = $2;")
+            .bind(scope.as_str())
+            .bind(key)
+            .bind(locked_until)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, scope: LoginAttemptScope, key: &str) -> LoginAttemptResult<()> {
+        sqlx::query("DELETE FROM login_attempts WHERE scope = $1 AND key = $2;")
+            .bind(scope.as_str())
+            .bind(key)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_stale(&self, window_start: &OffsetDateTime) -> LoginAttemptResult<u64> {
+        Ok(sqlx::query(
+            "DELETE FROM login_attempts WHERE last_failure_at < $1 AND (locked_until IS NULL OR locked_until < NOW());",
+        )
+        .bind(window_start)
+        .execute(&self.pg_pool)
+        .await?
+        .rows_affected())
+    }
+}
@@ -1,8 +1,20 @@
 pub mod bridge;
+pub mod identity;
+pub mod impersonation;
+pub mod login_attempt;
+pub mod oidc_login_state;
 pub mod organization;
+pub mod organization_api_key;
+pub mod organization_custom_role;
+pub mod organization_invitation;
 pub mod organization_member;
+pub mod organization_quota;
+pub mod personal_access_token;
 pub mod proxy;
 pub mod proxy_template;
 pub mod regions;
 pub mod session;
+pub mod tier;
+pub mod two_factor;
 pub mod user;
+pub mod user_token;
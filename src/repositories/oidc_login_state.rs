@@ -0,0 +1,58 @@
+use crate::domains::oidc::{OidcError, OidcLoginState, OidcResult};
+
+#[derive(Clone)]
+pub struct OidcLoginStateRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl OidcLoginStateRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn insert(&self, login_state: &OidcLoginState) -> OidcResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO oidc_login_states(state_hash, provider, code_verifier, user_id, intent, created_at, expires_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7);
+        "#,
+        )
+        .bind(&login_state.state_hash)
+        .bind(&login_state.provider)
+        .bind(&login_state.code_verifier)
+        .bind(&login_state.user_id)
+        .bind(&login_state.intent)
+        .bind(&login_state.created_at)
+        .bind(&login_state.expires_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn consume(&self, provider: &str, state_hash: &String) -> OidcResult<OidcLoginState> {
+        sqlx::query_as(
+            r#"
+        DELETE FROM oidc_login_states 
+        WHERE state_hash = $1 
+            AND provider = $2 
+            AND expires_at > NOW()
+        RETURNING *;
+        "#,
+        )
+        .bind(state_hash)
+        .bind(provider)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(OidcError::InvalidState)
+    }
+
+    pub async fn delete_expired(&self) -> OidcResult<u64> {
+        Ok(
+            sqlx::query("DELETE FROM oidc_login_states WHERE expires_at < NOW();")
+                .execute(&self.pg_pool)
+                .await?
+                .rows_affected(),
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use crate::domains::organization::{Organization, OrganizationError, OrganizationResult};
 use sqlx::{query, query_as, Column, Row, ValueRef};
+use time::OffsetDateTime;
 use tracing::info;
 use uuid::Uuid;
 
@@ -32,6 +33,32 @@ impl OrganizationRepository {
         Ok(organizations)
     }
 
+    pub async fn search(
+        &self,
+        pattern: Option<&String>,
+        limit: i64,
+        offset: i64,
+    ) -> OrganizationResult<Vec<Organization>> {
+        let organizations = query_as(
+            r#"
+        SELECT * 
+        FROM organizations 
+        WHERE $1::VARCHAR IS NULL 
+            OR slug ILIKE $1
+            OR display_name ILIKE $1
+        ORDER BY slug
+        LIMIT $2 OFFSET $3;
+        "#,
+        )
+        .bind(pattern)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(organizations)
+    }
+
     pub async fn find_by_id(&self, organization_id: &Uuid) -> OrganizationResult<Organization> {
         query_as("SELECT * FROM organizations WHERE id = $1;")
             .bind(&organization_id)
@@ -41,12 +68,126 @@ impl OrganizationRepository {
     }
 
     pub async fn insert(&self, organization: &Organization) -> OrganizationResult<()> {
-        query("INSERT INTO organizations(id, slug, region_id) VALUES ($1, $2, $3);")
-            .bind(&organization.id)
-            .bind(&organization.slug)
-            .bind(&organization.region_id)
+        query(
+            r#"
+        INSERT INTO organizations(id, slug, region_id, namespace, display_name, description, avatar_url, contact_email) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8);
+        "#,
+        )
+        .bind(&organization.id)
+        .bind(&organization.slug)
+        .bind(&organization.region_id)
+        .bind(&organization.namespace)
+        .bind(&organization.display_name)
+        .bind(&organization.description)
+        .bind(&organization.avatar_url)
+        .bind(&organization.contact_email)
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    // only the settings columns, a concurrent slug rename must not be reverted
+    pub async fn update_settings(
+        &self,
+        organization: &Organization,
+    ) -> OrganizationResult<Organization> {
+        query_as(
+            r#"
+        UPDATE organizations 
+        SET display_name = $2, description = $3, avatar_url = $4, contact_email = $5 
+        WHERE id = $1
+        RETURNING *;
+        "#,
+        )
+        .bind(&organization.id)
+        .bind(&organization.display_name)
+        .bind(&organization.description)
+        .bind(&organization.avatar_url)
+        .bind(&organization.contact_email)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(OrganizationError::NotFound)
+    }
+
+    pub async fn rename_slug(
+        &self,
+        organization_id: &Uuid,
+        slug: &str,
+    ) -> OrganizationResult<Organization> {
+        query_as("UPDATE organizations SET slug = $2 WHERE id = $1 RETURNING *;")
+            .bind(organization_id)
+            .bind(slug)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(OrganizationError::NotFound)
+    }
+
+    pub async fn schedule_deletion(
+        &self,
+        organization_id: &Uuid,
+        scheduled_at: &OffsetDateTime,
+        requested_by: &Uuid,
+    ) -> OrganizationResult<Organization> {
+        query_as(
+            r#"
+        UPDATE organizations 
+        SET deletion_scheduled_at = $2, deletion_requested_by = $3 
+        WHERE id = $1 
+            AND deletion_scheduled_at IS NULL
+        RETURNING *;
+        "#,
+        )
+        .bind(organization_id)
+        .bind(scheduled_at)
+        .bind(requested_by)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(OrganizationError::PendingDeletion)
+    }
+
+    pub async fn cancel_deletion(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationResult<Organization> {
+        // past the grace period the teardown may already be running
+        query_as(
+            r#"
+        UPDATE organizations 
+        SET deletion_scheduled_at = NULL, deletion_requested_by = NULL 
+        WHERE id = $1 
+            AND deletion_scheduled_at > NOW()
+        RETURNING *;
+        "#,
+        )
+        .bind(organization_id)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(OrganizationError::NotPendingDeletion)
+    }
+
+    pub async fn list_active(&self) -> OrganizationResult<Vec<Organization>> {
+        Ok(
+            query_as("SELECT * FROM organizations WHERE deletion_scheduled_at IS NULL;")
+                .fetch_all(&self.pg_pool)
+                .await?,
+        )
+    }
+
+    pub async fn list_due_for_deletion(&self) -> OrganizationResult<Vec<Organization>> {
+        Ok(
+            query_as("SELECT * FROM organizations WHERE deletion_scheduled_at <= NOW();")
+                .fetch_all(&self.pg_pool)
+                .await?,
+        )
+    }
+
+    pub async fn delete(&self, organization_id: &Uuid) -> OrganizationResult<()> {
+        query("DELETE FROM organizations WHERE id = $1;")
+            .bind(organization_id)
             .execute(&self.pg_pool)
             .await?;
+
         Ok(())
     }
 }
@@ -0,0 +1,97 @@
+use crate::domains::organization_api_key::{
+    OrganizationApiKey, OrganizationApiKeyError, OrganizationApiKeyResult,
+};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrganizationApiKeyRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl OrganizationApiKeyRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationApiKeyResult<Vec<OrganizationApiKey>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM organization_api_keys WHERE organization_id = $1 ORDER BY created_at DESC;",
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn find_by_key_hash(
+        &self,
+        key_hash: &String,
+    ) -> OrganizationApiKeyResult<OrganizationApiKey> {
+        sqlx::query_as(
+            r#"
+        SELECT * 
+        FROM organization_api_keys 
+        WHERE key_hash = $1 
+            AND (expires_at IS NULL OR expires_at > NOW())
+            LIMIT 1;
+        "#,
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(OrganizationApiKeyError::Invalid)
+    }
+
+    pub async fn insert(&self, api_key: &OrganizationApiKey) -> OrganizationApiKeyResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO organization_api_keys(id, organization_id, name, key_hash, key_hint, role, created_by, created_at, expires_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9);
+        "#,
+        )
+        .bind(&api_key.id)
+        .bind(&api_key.organization_id)
+        .bind(&api_key.name)
+        .bind(&api_key.key_hash)
+        .bind(&api_key.key_hint)
+        .bind(&api_key.role)
+        .bind(&api_key.created_by)
+        .bind(&api_key.created_at)
+        .bind(&api_key.expires_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn touch(&self, api_key_id: &Uuid) -> OrganizationApiKeyResult<()> {
+        sqlx::query("UPDATE organization_api_keys SET last_used_at = NOW() WHERE id = $1;")
+            .bind(api_key_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        api_key_id: &Uuid,
+    ) -> OrganizationApiKeyResult<()> {
+        let result = sqlx::query(
+            "DELETE FROM organization_api_keys WHERE id = $1 AND organization_id = $2;",
+        )
+        .bind(api_key_id)
+        .bind(organization_id)
+        .execute(&self.pg_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationApiKeyError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use crate::domains::organization_custom_role::{
+    OrganizationCustomRole, OrganizationCustomRoleError, OrganizationCustomRoleResult,
+};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrganizationCustomRoleRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl OrganizationCustomRoleRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationCustomRoleResult<Vec<OrganizationCustomRole>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM organization_custom_roles WHERE organization_id = $1 ORDER BY name;",
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        organization_id: &Uuid,
+        custom_role_id: &Uuid,
+    ) -> OrganizationCustomRoleResult<OrganizationCustomRole> {
+        sqlx::query_as(
+            "SELECT * FROM organization_custom_roles WHERE id = $1 AND organization_id = $2;",
+        )
+        .bind(custom_role_id)
+        .bind(organization_id)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(OrganizationCustomRoleError::NotFound)
+    }
+
+    pub async fn insert(
+        &self,
+        custom_role: &OrganizationCustomRole,
+    ) -> OrganizationCustomRoleResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO organization_custom_roles(id, organization_id, name, permissions, created_at) 
+        VALUES ($1, $2, $3, $4, $5);
+        "#,
+        )
+        .bind(&custom_role.id)
+        .bind(&custom_role.organization_id)
+        .bind(&custom_role.name)
+        .bind(&custom_role.permissions)
+        .bind(&custom_role.created_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update(
+        &self,
+        custom_role: &OrganizationCustomRole,
+    ) -> OrganizationCustomRoleResult<()> {
+        let result = sqlx::query(
+            "UPDATE organization_custom_roles SET name = $3, permissions = $4 WHERE id = $1 AND organization_id = $2;",
+        )
+        .bind(&custom_role.id)
+        .bind(&custom_role.organization_id)
+        .bind(&custom_role.name)
+        .bind(&custom_role.permissions)
+        .execute(&self.pg_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationCustomRoleError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        custom_role_id: &Uuid,
+    ) -> OrganizationCustomRoleResult<()> {
+        let result = sqlx::query(
+            "DELETE FROM organization_custom_roles WHERE id = $1 AND organization_id = $2;",
+        )
+        .bind(custom_role_id)
+        .bind(organization_id)
+        .execute(&self.pg_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationCustomRoleError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use crate::domains::organization_invitation::{
+    OrganizationInvitation, OrganizationInvitationError, OrganizationInvitationResult,
+    PendingInvitation,
+};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrganizationInvitationRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl OrganizationInvitationRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationInvitationResult<Vec<OrganizationInvitation>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM organization_invitations WHERE organization_id = $1 ORDER BY created_at DESC;",
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn find_by_id(
+        &self,
+        invitation_id: &Uuid,
+    ) -> OrganizationInvitationResult<OrganizationInvitation> {
+        sqlx::query_as("SELECT * FROM organization_invitations WHERE id = $1;")
+            .bind(invitation_id)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(OrganizationInvitationError::NotFound)
+    }
+
+    pub async fn find_by_token_hash(
+        &self,
+        token_hash: &String,
+    ) -> OrganizationInvitationResult<OrganizationInvitation> {
+        sqlx::query_as("SELECT * FROM organization_invitations WHERE token_hash = $1;")
+            .bind(token_hash)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(OrganizationInvitationError::Invalid)
+    }
+
+    pub async fn list_pending_by_email(
+        &self,
+        email: &String,
+        user_id: &Uuid,
+    ) -> OrganizationInvitationResult<Vec<PendingInvitation>> {
+        Ok(sqlx::query_as(
+            r#"
+        SELECT i.id, 
+               i.organization_id, 
+               o.slug AS organization_slug, 
+               i.role, 
+               u.name AS invited_by_name, 
+               i.expires_at
+        FROM organization_invitations i
+            INNER JOIN organizations o ON o.id = i.organization_id
+            LEFT JOIN users u ON u.id = i.invited_by
+        WHERE LOWER(i.email) = LOWER($1)
+            AND i.expires_at > NOW()
+            AND (i.max_uses IS NULL OR i.uses < i.max_uses)
+            AND NOT EXISTS (
+                SELECT 1
+                FROM organization_members m
+                WHERE m.organization_id = i.organization_id
+                    AND m.user_id = $2
+            )
+        ORDER BY i.created_at DESC;
+        "#,
+        )
+        .bind(email)
+        .bind(user_id)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn insert(
+        &self,
+        invitation: &OrganizationInvitation,
+    ) -> OrganizationInvitationResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO organization_invitations(id, organization_id, email, token_hash, role, invited_by, max_uses, uses, created_at, expires_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
+        "#,
+        )
+        .bind(&invitation.id)
+        .bind(&invitation.organization_id)
+        .bind(&invitation.email)
+        .bind(&invitation.token_hash)
+        .bind(&invitation.role)
+        .bind(&invitation.invited_by)
+        .bind(&invitation.max_uses)
+        .bind(&invitation.uses)
+        .bind(&invitation.created_at)
+        .bind(&invitation.expires_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Claims one use of the invitation and adds the member in the same transaction, so an
+    /// invitation can never be used more often than allowed.
+    pub async fn accept(
+        &self,
+        invitation_id: &Uuid,
+        user_id: &Uuid,
+    ) -> OrganizationInvitationResult<OrganizationInvitation> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let invitation: OrganizationInvitation = sqlx::query_as(
+            r#"
+        UPDATE organization_invitations 
+        SET uses = uses + 1 
+        WHERE id = $1 
+            AND expires_at > NOW() 
+            AND (max_uses IS NULL OR uses < max_uses)
+        RETURNING *;
+        "#,
+        )
+        .bind(invitation_id)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or(OrganizationInvitationError::Invalid)?;
+
+        sqlx::query(
+            "INSERT INTO organization_members(user_id, organization_id, role) VALUES ($1, $2, $3);",
+        )
+        .bind(user_id)
+        .bind(&invitation.organization_id)
+        .bind(&invitation.role)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(invitation)
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        invitation_id: &Uuid,
+    ) -> OrganizationInvitationResult<()> {
+        let result = sqlx::query(
+            "DELETE FROM organization_invitations WHERE id = $1 AND organization_id = $2;",
+        )
+        .bind(invitation_id)
+        .bind(organization_id)
+        .execute(&self.pg_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationInvitationError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::domains::organization_member::{
-    OrganizationMember, OrganizationMemberError, OrganizationMemberResult,
+    OrganizationMember, OrganizationMemberError, OrganizationMemberResult, OrganizationMemberRole,
+    OrganizationMemberView,
 };
 use std::cmp::min;
 use uuid::Uuid;
@@ -17,12 +18,14 @@ impl OrganizationMemberRepository {
     pub async fn list(
         &self,
         organization_id: &Uuid,
-    ) -> OrganizationMemberResult<Vec<OrganizationMember>> {
+    ) -> OrganizationMemberResult<Vec<OrganizationMemberView>> {
         let members = sqlx::query_as(
             r#"
-        SELECT * 
-        FROM organization_members
-        WHERE organization_id = $1;
+        SELECT m.user_id, u.name, u.email, m.role, m.custom_role_id
+        FROM organization_members m
+            INNER JOIN users u ON u.id = m.user_id
+        WHERE m.organization_id = $1
+        ORDER BY m.role DESC, u.name;
         "#,
         )
         .bind(organization_id)
@@ -51,7 +54,7 @@ impl OrganizationMemberRepository {
         &self,
         organization_id: &Uuid,
         user_id: &Uuid,
-        min_role: i16,
+        min_role: OrganizationMemberRole,
     ) -> OrganizationMemberResult<OrganizationMember> {
         sqlx::query_as("SELECT * FROM organization_members WHERE organization_id = $1 AND user_id = $2 AND role >= $3;")
             .bind(&organization_id)
@@ -61,6 +64,54 @@ impl OrganizationMemberRepository {
             .ok_or(OrganizationMemberError::NotFound)
     }
 
+    pub async fn list_solely_owned_organization_ids(
+        &self,
+        user_id: &Uuid,
+        owner_role: OrganizationMemberRole,
+    ) -> OrganizationMemberResult<Vec<Uuid>> {
+        Ok(sqlx::query_scalar(
+            r#"
+        SELECT owned.organization_id
+        FROM organization_members owned
+        WHERE owned.user_id = $1
+            AND owned.role >= $2
+            AND NOT EXISTS (
+                SELECT 1
+                FROM organization_members other
+                WHERE other.organization_id = owned.organization_id
+                    AND other.user_id <> owned.user_id
+                    AND other.role >= $2
+            );
+        "#,
+        )
+        .bind(user_id)
+        .bind(owner_role)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn set_custom_role(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+        custom_role_id: &Option<Uuid>,
+    ) -> OrganizationMemberResult<()> {
+        let result = sqlx::query(
+            "UPDATE organization_members SET custom_role_id = $3 WHERE organization_id = $1 AND user_id = $2;",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(custom_role_id)
+        .execute(&self.pg_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationMemberError::NotFound);
+        }
+
+        Ok(())
+    }
+
     pub async fn insert(
         &self,
         organization_id: &Uuid,
@@ -77,4 +128,119 @@ impl OrganizationMemberRepository {
 
         Ok(())
     }
+
+    pub async fn update_role(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+        role: OrganizationMemberRole,
+    ) -> OrganizationMemberResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let owners = lock_owners(&mut transaction, organization_id).await?;
+        if role != OrganizationMemberRole::Owner && owners == [*user_id] {
+            return Err(OrganizationMemberError::LastOwner);
+        }
+
+        let result = sqlx::query(
+            "UPDATE organization_members SET role = $3 WHERE organization_id = $1 AND user_id = $2;",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(&mut *transaction)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationMemberError::NotFound);
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn transfer_ownership(
+        &self,
+        organization_id: &Uuid,
+        from_user_id: &Uuid,
+        to_user_id: &Uuid,
+    ) -> OrganizationMemberResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let owners = lock_owners(&mut transaction, organization_id).await?;
+        if !owners.contains(from_user_id) {
+            return Err(OrganizationMemberError::Forbidden);
+        }
+
+        let result = sqlx::query(
+            "UPDATE organization_members SET role = $3 WHERE organization_id = $1 AND user_id = $2;",
+        )
+        .bind(organization_id)
+        .bind(to_user_id)
+        .bind(OrganizationMemberRole::Owner)
+        .execute(&mut *transaction)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationMemberError::NotFound);
+        }
+
+        sqlx::query(
+            "UPDATE organization_members SET role = $3 WHERE organization_id = $1 AND user_id = $2;",
+        )
+        .bind(organization_id)
+        .bind(from_user_id)
+        .bind(OrganizationMemberRole::Admin)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(
+        &self,
+        organization_id: &Uuid,
+        user_id: &Uuid,
+    ) -> OrganizationMemberResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let owners = lock_owners(&mut transaction, organization_id).await?;
+        if owners == [*user_id] {
+            return Err(OrganizationMemberError::LastOwner);
+        }
+
+        let result = sqlx::query(
+            "DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2;",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(OrganizationMemberError::NotFound);
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
+
+// locking the owner rows serializes concurrent demotions, so two owners can't demote each
+// other at the same time and leave the organization without one
+async fn lock_owners(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    organization_id: &Uuid,
+) -> OrganizationMemberResult<Vec<Uuid>> {
+    Ok(sqlx::query_scalar(
+        "SELECT user_id FROM organization_members WHERE organization_id = $1 AND role = $2 FOR UPDATE;",
+    )
+    .bind(organization_id)
+    .bind(OrganizationMemberRole::Owner)
+    .fetch_all(&mut **transaction)
+    .await?)
 }
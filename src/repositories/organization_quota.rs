@@ -0,0 +1,136 @@
+use crate::consts::{PROXY_CPU_MILLIS, PROXY_MEMORY_MIB};
+use crate::domains::organization_quota::{
+    OrganizationQuota, OrganizationQuotaResult, OrganizationUsage, ProxyUsage,
+};
+use uuid::Uuid;
+
+// proxies of tierless templates count with the namespace LimitRange defaults
+const PROXY_USAGE_QUERY: &str = r#"
+SELECT COUNT(*)                                                   AS proxies,
+       COALESCE(SUM(COALESCE(tiers.cpu_millis, $2)), 0)::BIGINT AS cpu_millis,
+       COALESCE(SUM(COALESCE(tiers.memory_mib, $3)), 0)::BIGINT AS memory_mib
+FROM proxies
+         LEFT JOIN proxy_templates ON proxy_templates.id = proxies.template_id
+         LEFT JOIN tiers ON tiers.id = proxy_templates.tier_id
+WHERE proxies.organization_id = $1;
+"#;
+
+#[derive(Clone)]
+pub struct OrganizationQuotaRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl OrganizationQuotaRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn find(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationQuotaResult<Option<OrganizationQuota>> {
+        Ok(
+            sqlx::query_as("SELECT * FROM organization_quotas WHERE organization_id = $1;")
+                .bind(organization_id)
+                .fetch_optional(&self.pg_pool)
+                .await?,
+        )
+    }
+
+    pub async fn usage(
+        &self,
+        organization_id: &Uuid,
+    ) -> OrganizationQuotaResult<OrganizationUsage> {
+        let proxy_usage: ProxyUsage = sqlx::query_as(PROXY_USAGE_QUERY)
+            .bind(organization_id)
+            .bind(PROXY_CPU_MILLIS)
+            .bind(PROXY_MEMORY_MIB)
+            .fetch_one(&self.pg_pool)
+            .await?;
+        let (proxy_templates, bridges): (i64, i64) = sqlx::query_as(
+            r#"
+        SELECT (SELECT COUNT(*) FROM proxy_templates WHERE organization_id = $1) AS proxy_templates,
+               (SELECT COUNT(*) FROM bridges WHERE organization_id = $1)         AS bridges;
+        "#,
+        )
+        .bind(organization_id)
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(OrganizationUsage {
+            proxies: proxy_usage.proxies,
+            proxy_templates,
+            bridges,
+            cpu_millis: proxy_usage.cpu_millis,
+            memory_mib: proxy_usage.memory_mib,
+        })
+    }
+
+    pub async fn upsert(&self, quota: &OrganizationQuota) -> OrganizationQuotaResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO organization_quotas(organization_id, max_proxies, max_proxy_templates, max_bridges, cpu_millis, memory_mib, updated_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (organization_id) DO UPDATE 
+        SET max_proxies = $2, max_proxy_templates = $3, max_bridges = $4, cpu_millis = $5, memory_mib = $6, updated_at = $7;
+        "#,
+        )
+        .bind(&quota.organization_id)
+        .bind(&quota.max_proxies)
+        .bind(&quota.max_proxy_templates)
+        .bind(&quota.max_bridges)
+        .bind(&quota.cpu_millis)
+        .bind(&quota.memory_mib)
+        .bind(&quota.updated_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Like [`lock_and_count`] for proxies, summing up what they request along with the count.
+pub(crate) async fn lock_and_sum_proxies(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    organization_id: &Uuid,
+) -> sqlx::Result<ProxyUsage> {
+    lock_organization(transaction, organization_id).await?;
+
+    sqlx::query_as(PROXY_USAGE_QUERY)
+        .bind(organization_id)
+        .bind(PROXY_CPU_MILLIS)
+        .bind(PROXY_MEMORY_MIB)
+        .fetch_one(&mut **transaction)
+        .await
+}
+
+/// Locks the organization row for the rest of the transaction and counts its rows in `table`.
+/// Concurrent inserts of the same organization queue up behind the lock, so a count checked
+/// against a quota stays true until the transaction commits.
+pub(crate) async fn lock_and_count(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    organization_id: &Uuid,
+    table: &'static str,
+) -> sqlx::Result<i64> {
+    lock_organization(transaction, organization_id).await?;
+
+    sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM {} WHERE organization_id = $1;",
+        table
+    ))
+    .bind(organization_id)
+    .fetch_one(&mut **transaction)
+    .await
+}
+
+async fn lock_organization(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    organization_id: &Uuid,
+) -> sqlx::Result<()> {
+    sqlx::query("SELECT id FROM organizations WHERE id = $1 FOR UPDATE;")
+        .bind(organization_id)
+        .execute(&mut **transaction)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::domains::personal_access_token::{
+    PersonalAccessToken, PersonalAccessTokenError, PersonalAccessTokenResult,
+};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct PersonalAccessTokenRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl PersonalAccessTokenRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list_by_user_id(
+        &self,
+        user_id: &Uuid,
+    ) -> PersonalAccessTokenResult<Vec<PersonalAccessToken>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM personal_access_tokens WHERE user_id = $1 ORDER BY created_at DESC;",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn find_by_token_hash(
+        &self,
+        token_hash: &String,
+    ) -> PersonalAccessTokenResult<PersonalAccessToken> {
+        sqlx::query_as(
+            r#"
+        SELECT * 
+        FROM personal_access_tokens 
+        WHERE token_hash = $1 
+            AND (expires_at IS NULL OR expires_at > NOW())
+            LIMIT 1;
+        "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(PersonalAccessTokenError::Invalid)
+    }
+
+    pub async fn insert(&self, token: &PersonalAccessToken) -> PersonalAccessTokenResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO personal_access_tokens(id, user_id, name, token_hash, token_hint, scopes, created_at, expires_at) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8);
+        "#,
+        )
+        .bind(&token.id)
+        .bind(&token.user_id)
+        .bind(&token.name)
+        .bind(&token.token_hash)
+        .bind(&token.token_hint)
+        .bind(&token.scopes)
+        .bind(&token.created_at)
+        .bind(&token.expires_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn touch(&self, token_id: &Uuid) -> PersonalAccessTokenResult<()> {
+        sqlx::query("UPDATE personal_access_tokens SET last_used_at = NOW() WHERE id = $1;")
+            .bind(token_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_by_user_id(
+        &self,
+        user_id: &Uuid,
+        token_id: &Uuid,
+    ) -> PersonalAccessTokenResult<()> {
+        let result =
+            sqlx::query("DELETE FROM personal_access_tokens WHERE id = $1 AND user_id = $2;")
+                .bind(token_id)
+                .bind(user_id)
+                .execute(&self.pg_pool)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(PersonalAccessTokenError::NotFound);
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use crate::domains::proxy::{Proxy, ProxyResult};
+use crate::domains::organization_quota::{OrganizationQuota, ProxyResources};
+use crate::domains::proxy::{Proxy, ProxyError, ProxyResult};
+use crate::repositories::organization_quota::lock_and_sum_proxies;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -20,9 +22,29 @@ impl ProxyRepository {
         )
     }
 
-    pub async fn insert(&self, organization_id: &Uuid, proxy: &Proxy) -> ProxyResult<()> {
+    pub async fn find_by_id(&self, organization_id: &Uuid, proxy_id: &Uuid) -> ProxyResult<Proxy> {
+        sqlx::query_as("SELECT * FROM proxies WHERE organization_id = $1 AND id = $2;")
+            .bind(organization_id)
+            .bind(proxy_id)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(ProxyError::NotFound)
+    }
+
+    pub async fn insert(
+        &self,
+        organization_id: &Uuid,
+        proxy: &Proxy,
+        quota: &OrganizationQuota,
+        requested: &ProxyResources,
+    ) -> ProxyResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let usage = lock_and_sum_proxies(&mut transaction, organization_id).await?;
+        quota.check_proxy(&usage, requested)?;
+
         sqlx::query(
-            "INSERT INTO proxies(id, slug, bridge_id, bs_proxy_id, template_id, organization_id) VALUES ($1, $2, $3, $4);",
+            "INSERT INTO proxies(id, slug, bridge_id, bs_proxy_id, template_id, organization_id) VALUES ($1, $2, $3, $4, $5, $6);",
         )
         .bind(&proxy.id)
         .bind(&proxy.slug)
@@ -30,9 +52,21 @@ impl ProxyRepository {
         .bind(&proxy.bs_proxy_id)
         .bind(&proxy.template_id)
         .bind(&organization_id)
-        .execute(&self.pg_pool)
+        .execute(&mut *transaction)
         .await?;
 
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, organization_id: &Uuid, proxy_id: &Uuid) -> ProxyResult<()> {
+        sqlx::query("DELETE FROM proxies WHERE organization_id = $1 AND id = $2;")
+            .bind(organization_id)
+            .bind(proxy_id)
+            .execute(&self.pg_pool)
+            .await?;
+
         Ok(())
     }
 }
@@ -1,4 +1,6 @@
+use crate::domains::organization_quota::{QuotaExceeded, QuotaResource};
 use crate::domains::proxy_template::{ProxyTemplate, ProxyTemplateError, ProxyTemplateResult};
+use crate::repositories::organization_quota::lock_and_count;
 use sqlx::{query, query_as};
 use uuid::Uuid;
 
@@ -21,6 +23,19 @@ impl ProxyTemplateRepository {
         )
     }
 
+    pub async fn find_by_id(
+        &self,
+        organization_id: &Uuid,
+        template_id: &Uuid,
+    ) -> ProxyTemplateResult<ProxyTemplate> {
+        query_as("SELECT * FROM proxy_templates WHERE organization_id = $1 AND id = $2 LIMIT 1;")
+            .bind(organization_id)
+            .bind(template_id)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(ProxyTemplateError::NotFound)
+    }
+
     pub async fn find_by_slug(
         &self,
         organization_id: &Uuid,
@@ -38,14 +53,29 @@ impl ProxyTemplateRepository {
         &self,
         organization_id: &Uuid,
         proxy_template: &ProxyTemplate,
+        limit: i64,
     ) -> ProxyTemplateResult<()> {
-        query("INSERT INTO proxy_templates(id, slug, image, plugins_dir, organization_id) VALUES ($1, $2, $3, $4, $5);")
+        let mut transaction = self.pg_pool.begin().await?;
+
+        if lock_and_count(&mut transaction, organization_id, "proxy_templates").await? >= limit {
+            return Err(QuotaExceeded {
+                resource: QuotaResource::ProxyTemplates,
+                limit,
+            }
+            .into());
+        }
+
+        query("INSERT INTO proxy_templates(id, slug, image, plugins_dir, bridge_id, tier_id, organization_id) VALUES ($1, $2, $3, $4, $5, $6, $7);")
             .bind(&proxy_template.id)
             .bind(&proxy_template.slug)
             .bind(&proxy_template.image)
             .bind(&proxy_template.plugins_dir)
+            .bind(&proxy_template.bridge_id)
+            .bind(&proxy_template.tier_id)
             .bind(&organization_id)
-            .execute(&self.pg_pool).await?;
+            .execute(&mut *transaction).await?;
+
+        transaction.commit().await?;
 
         Ok(())
     }
@@ -1,28 +1,215 @@
-use crate::domains::region::{Region, RegionError, RegionResult};
-use log::info;
-use sqlx::query_as;
+use crate::domains::region::{BridgeConfig, Region, RegionError, RegionOptions, RegionResult};
+use crate::envelope::{EnvelopeCipher, Sealed};
+use kube::config::Kubeconfig;
+use log::{error, info};
+use sqlx::types::Json;
+use sqlx::{query, query_as};
+use uuid::Uuid;
+
+/// How options are kept in the database, secrets are sealed with the region id as context.
+/// Rows written before encryption still hold a plaintext kubeconfig until they are resealed.
+#[derive(serde::Deserialize, serde::Serialize)]
+struct StoredRegionOptions {
+    bridge: BridgeConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    kube: Option<Kubeconfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sealed: Option<Sealed>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct RegionSecrets {
+    kube: Kubeconfig,
+}
+
+#[derive(sqlx::FromRow)]
+struct RegionRow {
+    id: Uuid,
+    slug: String,
+    options: Json<StoredRegionOptions>,
+    disabled: bool,
+}
 
 #[derive(Clone)]
 pub struct RegionRepository {
     pg_pool: sqlx::PgPool,
+    cipher: EnvelopeCipher,
 }
 
 impl RegionRepository {
-    pub fn new(pg_pool: sqlx::PgPool) -> Self {
-        Self { pg_pool }
+    pub fn new(pg_pool: sqlx::PgPool, cipher: EnvelopeCipher) -> Self {
+        Self { pg_pool, cipher }
     }
 
     pub async fn list(&self) -> RegionResult<Vec<Region>> {
-        Ok(query_as("SELECT * FROM regions;")
+        let rows = query_as("SELECT * FROM regions;")
             .fetch_all(&self.pg_pool)
-            .await?)
+            .await?;
+
+        Ok(self.open_all(rows))
+    }
+
+    pub async fn list_enabled(&self) -> RegionResult<Vec<Region>> {
+        let rows = query_as("SELECT * FROM regions WHERE disabled = FALSE;")
+            .fetch_all(&self.pg_pool)
+            .await?;
+
+        Ok(self.open_all(rows))
+    }
+
+    pub async fn find_by_id(&self, region_id: &Uuid) -> RegionResult<Region> {
+        let row = query_as("SELECT * FROM regions WHERE id = $1 LIMIT 1;")
+            .bind(region_id)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(RegionError::NotFound)?;
+
+        self.open(row)
     }
 
     pub async fn find_by_slug(&self, slug: &String) -> RegionResult<Region> {
-        query_as("SELECT * FROM regions WHERE slug = $1 LIMIT 1;")
+        let row = query_as("SELECT * FROM regions WHERE slug = $1 LIMIT 1;")
             .bind(slug)
             .fetch_optional(&self.pg_pool)
             .await?
-            .ok_or(RegionError::NotFound)
+            .ok_or(RegionError::NotFound)?;
+
+        self.open(row)
+    }
+
+    pub async fn insert(&self, region: &Region) -> RegionResult<()> {
+        query("INSERT INTO regions (id, slug, options, disabled) VALUES ($1, $2, $3, $4);")
+            .bind(region.id)
+            .bind(&region.slug)
+            .bind(self.seal(region)?)
+            .bind(region.disabled)
+            .execute(&self.pg_pool)
+            .await?;
+
+        info!("inserted region {}", region.slug);
+
+        Ok(())
+    }
+
+    pub async fn update(&self, region: &Region) -> RegionResult<()> {
+        query("UPDATE regions SET slug = $2, options = $3, disabled = $4 WHERE id = $1;")
+            .bind(region.id)
+            .bind(&region.slug)
+            .bind(self.seal(region)?)
+            .bind(region.disabled)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, region_id: &Uuid) -> RegionResult<()> {
+        query("DELETE FROM regions WHERE id = $1;")
+            .bind(region_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Seals plaintext rows and moves sealed ones to the current master key, returns how many
+    /// rows were rewritten. Only the data keys are re-encrypted for rotated rows.
+    pub async fn reseal_all(&self) -> RegionResult<usize> {
+        let rows: Vec<RegionRow> = query_as("SELECT * FROM regions;")
+            .fetch_all(&self.pg_pool)
+            .await?;
+
+        let mut resealed = 0;
+        for row in rows {
+            let region_id = row.id;
+            let slug = row.slug.clone();
+            let options = match &row.options.sealed {
+                Some(sealed) if !self.cipher.needs_rewrap(sealed) => continue,
+                Some(sealed) => {
+                    self.cipher
+                        .rewrap(sealed)
+                        .map_err(RegionError::from)
+                        .map(|sealed| {
+                            Json(StoredRegionOptions {
+                                bridge: row.options.bridge.clone(),
+                                kube: None,
+                                sealed: Some(sealed),
+                            })
+                        })
+                }
+                None => self.open(row).and_then(|region| self.seal(&region)),
+            };
+            // a row that can't be opened is left as is for the others to be resealed
+            let options = match options {
+                Ok(options) => options,
+                Err(err) => {
+                    error!("failed to reseal region {}: {}", slug, err);
+                    continue;
+                }
+            };
+
+            query("UPDATE regions SET options = $2 WHERE id = $1;")
+                .bind(region_id)
+                .bind(options)
+                .execute(&self.pg_pool)
+                .await?;
+            resealed += 1;
+        }
+
+        Ok(resealed)
+    }
+
+    /// Rows that can't be opened are logged and left out instead of failing the whole list.
+    fn open_all(&self, rows: Vec<RegionRow>) -> Vec<Region> {
+        rows.into_iter()
+            .filter_map(|row| {
+                let slug = row.slug.clone();
+                self.open(row)
+                    .map_err(|err| error!("failed to open region {}: {}", slug, err))
+                    .ok()
+            })
+            .collect()
+    }
+
+    fn open(&self, row: RegionRow) -> RegionResult<Region> {
+        let Json(options) = row.options;
+        let kube = match (options.sealed, options.kube) {
+            (Some(sealed), _) => {
+                let secrets: RegionSecrets =
+                    serde_json::from_slice(&self.cipher.open(row.id.as_bytes(), &sealed)?)
+                        .map_err(|err| RegionError::Unknown(err.to_string()))?;
+                secrets.kube
+            }
+            (None, Some(kube)) => kube,
+            (None, None) => {
+                return Err(RegionError::Unknown(format!(
+                    "region {} has no kubeconfig",
+                    row.slug
+                )))
+            }
+        };
+
+        Ok(Region {
+            id: row.id,
+            slug: row.slug,
+            options: RegionOptions {
+                kube,
+                bridge: options.bridge,
+            },
+            disabled: row.disabled,
+        })
+    }
+
+    fn seal(&self, region: &Region) -> RegionResult<Json<StoredRegionOptions>> {
+        let secrets = serde_json::to_vec(&RegionSecrets {
+            kube: region.options.kube.clone(),
+        })
+        .map_err(|err| RegionError::Unknown(err.to_string()))?;
+
+        Ok(Json(StoredRegionOptions {
+            bridge: region.options.bridge.clone(),
+            kube: None,
+            sealed: Some(self.cipher.seal(region.id.as_bytes(), &secrets)?),
+        }))
     }
 }
@@ -1,5 +1,4 @@
-use crate::domains::session::{Session, SessionError, SessionResult};
-use crate::domains::user::{User, UserError};
+use crate::domains::session::{Session, SessionError, SessionResult, SessionUser};
 use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
@@ -13,15 +12,27 @@ impl SessionRepository {
         Self { pg_pool }
     }
 
-    pub async fn find_user_by_session_id(&self, session_id: &Uuid) -> SessionResult<User> {
-        let user: Option<User> = sqlx::query_as(
+    pub async fn list_by_user_id(&self, user_id: &Uuid) -> SessionResult<Vec<Session>> {
+        Ok(sqlx::query_as(
+            "SELECT * FROM sessions WHERE user_id = $1 AND expires_at > NOW() AND two_factor_pending = FALSE ORDER BY last_seen_at DESC;",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn find_user_by_session_id(&self, session_id: &Uuid) -> SessionResult<SessionUser> {
+        let user: Option<SessionUser> = sqlx::query_as(
             r#"
         SELECT 
             sessions.user_id AS s_user_id,
+            sessions.impersonator_id,
             users.* 
         FROM sessions
             INNER JOIN users ON users.id = sessions.user_id
         WHERE sessions.id = $1
+            AND sessions.expires_at > NOW()
+            AND sessions.two_factor_pending = FALSE
             LIMIT 1;
         "#,
         )
@@ -31,14 +42,131 @@ impl SessionRepository {
 
         user.ok_or(SessionError::Invalid)
     }
+
+    pub async fn find_by_id(&self, session_id: &Uuid) -> SessionResult<Session> {
+        sqlx::query_as(
+            "SELECT * FROM sessions WHERE id = $1 AND expires_at > NOW() AND two_factor_pending = FALSE;",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(SessionError::Invalid)
+    }
+
+    pub async fn find_two_factor_pending(&self, session_id: &Uuid) -> SessionResult<Session> {
+        sqlx::query_as(
+            "SELECT * FROM sessions WHERE id = $1 AND expires_at > NOW() AND two_factor_pending = TRUE;",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(SessionError::Invalid)
+    }
+
     pub async fn insert(&self, session: &Session) -> SessionResult<()> {
-        sqlx::query("INSERT INTO sessions(id, user_id, expires_at) VALUES ($1, $2, $3);")
-            .bind(&session.id)
-            .bind(&session.user.id)
-            .bind(&session.expires_at)
+        sqlx::query(
+            r#"
+        INSERT INTO sessions(id, public_id, user_id, created_at, last_seen_at, expires_at, user_agent, ip_address, two_factor_pending, impersonator_id, impersonator_session_id) 
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11);
+        "#,
+        )
+        .bind(&session.id)
+        .bind(&session.public_id)
+        .bind(&session.user_id)
+        .bind(&session.created_at)
+        .bind(&session.last_seen_at)
+        .bind(&session.expires_at)
+        .bind(&session.user_agent)
+        .bind(&session.ip_address)
+        .bind(&session.two_factor_pending)
+        .bind(&session.impersonator_id)
+        .bind(&session.impersonator_session_id)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn touch(
+        &self,
+        session_id: &Uuid,
+        renew_before: &OffsetDateTime,
+        expires_at: &OffsetDateTime,
+    ) -> SessionResult<bool> {
+        let renewed: Option<bool> = sqlx::query_scalar(
+            r#"
+        UPDATE sessions
+        SET last_seen_at = NOW(),
+            expires_at = CASE 
+                WHEN expires_at < $2 AND impersonator_id IS NULL THEN $3 
+                ELSE expires_at 
+            END
+        WHERE id = $1
+            AND expires_at > NOW()
+            AND two_factor_pending = FALSE
+        RETURNING expires_at = $3;
+        "#,
+        )
+        .bind(session_id)
+        .bind(renew_before)
+        .bind(expires_at)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(renewed.unwrap_or(false))
+    }
+
+    pub async fn increment_two_factor_attempts(&self, session_id: &Uuid) -> SessionResult<i16> {
+        let attempts: Option<i16> = sqlx::query_scalar(
+            "UPDATE sessions SET two_factor_attempts = two_factor_attempts + 1 WHERE id = $1 RETURNING two_factor_attempts;",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        attempts.ok_or(SessionError::Invalid)
+    }
+
+    pub async fn delete(&self, session_id: &Uuid) -> SessionResult<()> {
+        sqlx::query("DELETE FROM sessions WHERE id = $1;")
+            .bind(session_id)
             .execute(&self.pg_pool)
             .await?;
 
         Ok(())
     }
+
+    /// Returns the id of the deleted session.
+    pub async fn delete_by_public_id(
+        &self,
+        user_id: &Uuid,
+        public_id: &Uuid,
+    ) -> SessionResult<Uuid> {
+        let session_id: Option<Uuid> = sqlx::query_scalar(
+            "DELETE FROM sessions WHERE public_id = $1 AND user_id = $2 RETURNING id;",
+        )
+        .bind(public_id)
+        .bind(user_id)
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        session_id.ok_or(SessionError::NotFound)
+    }
+
+    pub async fn delete_all_by_user_id(&self, user_id: &Uuid) -> SessionResult<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE user_id = $1;")
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_expired(&self) -> SessionResult<u64> {
+        let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= NOW();")
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -0,0 +1,40 @@
+use crate::domains::tier::{Tier, TierError, TierResult};
+use sqlx::query_as;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TierRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl TierRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn list_by_region(&self, region_id: &Uuid) -> TierResult<Vec<Tier>> {
+        Ok(
+            query_as("SELECT * FROM tiers WHERE region_id = $1 ORDER BY hourly_price_cents, slug;")
+                .bind(region_id)
+                .fetch_all(&self.pg_pool)
+                .await?,
+        )
+    }
+
+    pub async fn find_by_id(&self, tier_id: &Uuid) -> TierResult<Tier> {
+        query_as("SELECT * FROM tiers WHERE id = $1 LIMIT 1;")
+            .bind(tier_id)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(TierError::NotFound)
+    }
+
+    pub async fn find_by_slug(&self, region_id: &Uuid, slug: &String) -> TierResult<Tier> {
+        query_as("SELECT * FROM tiers WHERE region_id = $1 AND slug = $2 LIMIT 1;")
+            .bind(region_id)
+            .bind(slug)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(TierError::NotFound)
+    }
+}
@@ -0,0 +1,139 @@
+use crate::domains::two_factor::{TwoFactorError, TwoFactorResult, UserTotp};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct TwoFactorRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl TwoFactorRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn find_by_user_id(&self, user_id: &Uuid) -> TwoFactorResult<UserTotp> {
+        sqlx::query_as("SELECT * FROM user_totp WHERE user_id = $1;")
+            .bind(user_id)
+            .fetch_optional(&self.pg_pool)
+            .await?
+            .ok_or(TwoFactorError::NotEnrolled)
+    }
+
+    pub async fn upsert_pending(&self, user_id: &Uuid, secret: &String) -> TwoFactorResult<()> {
+        let result = sqlx::query(
+            r#"
+        INSERT INTO user_totp(user_id, secret) 
+        VALUES ($1, $2)
+        ON CONFLICT (user_id) DO UPDATE 
+            SET secret = EXCLUDED.secret, last_counter = NULL, created_at = NOW()
+            WHERE user_totp.enabled = FALSE;
+        "#,
+        )
+        .bind(user_id)
+        .bind(secret)
+        .execute(&self.pg_pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        Ok(())
+    }
+
+    pub async fn enable(
+        &self,
+        user_id: &Uuid,
+        counter: i64,
+        recovery_code_hashes: &[String],
+    ) -> TwoFactorResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let result = sqlx::query(
+            "UPDATE user_totp SET enabled = TRUE, last_counter = $2 WHERE user_id = $1 AND enabled = FALSE;",
+        )
+        .bind(user_id)
+        .bind(counter)
+        .execute(&mut *transaction)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(TwoFactorError::AlreadyEnabled);
+        }
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1;")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        for code_hash in recovery_code_hashes {
+            sqlx::query(
+                "INSERT INTO user_recovery_codes(id, user_id, code_hash) VALUES ($1, $2, $3);",
+            )
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(code_hash)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn record_counter(&self, user_id: &Uuid, counter: i64) -> TwoFactorResult<bool> {
+        let result = sqlx::query(
+            "UPDATE user_totp SET last_counter = $2 WHERE user_id = $1 AND (last_counter IS NULL OR last_counter < $2);",
+        )
+        .bind(user_id)
+        .bind(counter)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn consume_recovery_code(
+        &self,
+        user_id: &Uuid,
+        code_hash: &String,
+    ) -> TwoFactorResult<bool> {
+        let result = sqlx::query(
+            "UPDATE user_recovery_codes SET used_at = NOW() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL;",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn count_recovery_codes(&self, user_id: &Uuid) -> TwoFactorResult<i64> {
+        Ok(sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_recovery_codes WHERE user_id = $1 AND used_at IS NULL;",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn delete(&self, user_id: &Uuid) -> TwoFactorResult<()> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1;")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM user_totp WHERE user_id = $1;")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
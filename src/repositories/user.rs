@@ -1,4 +1,6 @@
 use crate::domains::user::{User, UserError, UserResult};
+use time::OffsetDateTime;
+use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -10,6 +12,15 @@ impl UserRepository {
         Self { pg_pool }
     }
 
+    pub async fn find_by_id(&self, user_id: &Uuid) -> UserResult<User> {
+        let user: Option<User> = sqlx::query_as("SELECT * FROM users WHERE id = $1;")
+            .bind(user_id)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+
+        user.ok_or(UserError::NotFound)
+    }
+
     pub async fn find_by_email(&self, email: &String) -> UserResult<User> {
         let user: Option<User> = sqlx::query_as("SELECT * FROM users WHERE email = $1;")
             .bind(email)
@@ -19,15 +30,121 @@ impl UserRepository {
         user.ok_or(UserError::NotFound)
     }
 
-    pub async fn insert(&self, user: &User) -> UserResult<()> {
-        sqlx::query("INSERT INTO users(id, name, email, password_hash) VALUES ($1, $2, $3, $4);")
-            .bind(&user.id)
-            .bind(&user.name)
-            .bind(&user.email)
-            .bind(&user.password_hash)
+    pub async fn search(
+        &self,
+        pattern: Option<&String>,
+        limit: i64,
+        offset: i64,
+    ) -> UserResult<Vec<User>> {
+        Ok(sqlx::query_as(
+            r#"
+        SELECT * 
+        FROM users 
+        WHERE $1::VARCHAR IS NULL 
+            OR name ILIKE $1 
+            OR email ILIKE $1
+        ORDER BY email
+        LIMIT $2 OFFSET $3;
+        "#,
+        )
+        .bind(pattern)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pg_pool)
+        .await?)
+    }
+
+    pub async fn set_suspended_at(
+        &self,
+        user_id: &Uuid,
+        suspended_at: &Option<OffsetDateTime>,
+    ) -> UserResult<()> {
+        let result = sqlx::query("UPDATE users SET suspended_at = $2 WHERE id = $1;")
+            .bind(user_id)
+            .bind(suspended_at)
+            .execute(&self.pg_pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_role(&self, user_id: &Uuid, role: i16) -> UserResult<()> {
+        let result = sqlx::query("UPDATE users SET role = $2 WHERE id = $1;")
+            .bind(user_id)
+            .bind(role)
+            .execute(&self.pg_pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    pub async fn update(&self, user: &User) -> UserResult<()> {
+        sqlx::query(
+            "UPDATE users SET name = $2, email = $3, password_hash = $4, email_verified = $5 WHERE id = $1;",
+        )
+        .bind(&user.id)
+        .bind(&user.name)
+        .bind(&user.email)
+        .bind(&user.password_hash)
+        .bind(&user.email_verified)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, user_id: &Uuid) -> UserResult<()> {
+        sqlx::query("DELETE FROM users WHERE id = $1;")
+            .bind(user_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_email_verified(&self, user_id: &Uuid) -> UserResult<()> {
+        sqlx::query("UPDATE users SET email_verified = TRUE WHERE id = $1;")
+            .bind(user_id)
             .execute(&self.pg_pool)
             .await?;
 
         Ok(())
     }
+
+    pub async fn update_password_hash(
+        &self,
+        user_id: &Uuid,
+        password_hash: &String,
+    ) -> UserResult<()> {
+        sqlx::query("UPDATE users SET password_hash = $2 WHERE id = $1;")
+            .bind(user_id)
+            .bind(password_hash)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn insert(&self, user: &User) -> UserResult<()> {
+        sqlx::query(
+            "INSERT INTO users(id, name, email, password_hash, email_verified) VALUES ($1, $2, $3, $4, $5);",
+        )
+        .bind(&user.id)
+        .bind(&user.name)
+        .bind(&user.email)
+        .bind(&user.password_hash)
+        .bind(&user.email_verified)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
 }
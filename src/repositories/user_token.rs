@@ -0,0 +1,67 @@
+use crate::domains::user_token::{UserToken, UserTokenError, UserTokenKind, UserTokenResult};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct UserTokenRepository {
+    pg_pool: sqlx::PgPool,
+}
+
+impl UserTokenRepository {
+    pub fn new(pg_pool: sqlx::PgPool) -> Self {
+        Self { pg_pool }
+    }
+
+    pub async fn insert(&self, token: &UserToken) -> UserTokenResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO user_tokens(id, user_id, kind, token_hash, created_at, expires_at) 
+        VALUES ($1, $2, $3, $4, $5, $6);
+        "#,
+        )
+        .bind(&token.id)
+        .bind(&token.user_id)
+        .bind(&token.kind)
+        .bind(&token.token_hash)
+        .bind(&token.created_at)
+        .bind(&token.expires_at)
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn consume(
+        &self,
+        kind: UserTokenKind,
+        token_hash: &String,
+    ) -> UserTokenResult<UserToken> {
+        sqlx::query_as(
+            r#"
+        UPDATE user_tokens 
+        SET used_at = NOW() 
+        WHERE kind = $1 
+            AND token_hash = $2 
+            AND used_at IS NULL 
+            AND expires_at > NOW()
+        RETURNING *;
+        "#,
+        )
+        .bind(kind.as_str())
+        .bind(token_hash)
+        .fetch_optional(&self.pg_pool)
+        .await?
+        .ok_or(UserTokenError::Invalid)
+    }
+
+    pub async fn delete_unused(&self, user_id: &Uuid, kind: UserTokenKind) -> UserTokenResult<()> {
+        sqlx::query(
+            "DELETE FROM user_tokens WHERE user_id = $1 AND kind = $2 AND used_at IS NULL;",
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+}
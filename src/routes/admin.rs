@@ -0,0 +1,432 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::Redirect;
+use axum::routing::{get, post, put};
+use axum::Json;
+use axum_extra::extract::CookieJar;
+use time::OffsetDateTime;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::consts::IMPERSONATION_TTL;
+use crate::domains::admin::{
+    AdminError, AdminOrganization, AdminResult, AdminSearchQuery, ImpersonationAuditQuery,
+    UpdateUserRoleData,
+};
+use crate::domains::impersonation::ImpersonationAuditEntry;
+use crate::domains::organization::Organization;
+use crate::domains::organization_quota::{
+    OrganizationQuota, OrganizationQuotaView, UpdateOrganizationQuotaData,
+};
+use crate::domains::proxy::Proxy;
+use crate::domains::region::{CreateRegionData, RegionDetails, UpdateRegionData};
+use crate::domains::region_capacity::RegionCapacity;
+use crate::domains::session::{session_cookie, Session};
+use crate::domains::user::{User, STAFF_ROLE};
+use crate::extractors::authenticated_user::{AdminRole, AuthenticatedUser, Credential, StaffRole};
+use crate::extractors::client_info::ClientInfo;
+use crate::managers::impersonation::ImpersonationManager;
+use crate::managers::organization::OrganizationManager;
+use crate::managers::organization_member::OrganizationMemberManager;
+use crate::managers::organization_quota::OrganizationQuotaManager;
+use crate::managers::proxy::ProxyManager;
+use crate::managers::region::RegionManager;
+use crate::managers::region_capacity::RegionCapacityManager;
+use crate::managers::session::SessionManager;
+use crate::managers::user::UserManager;
+
+pub fn router(
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    organization_manager: OrganizationManager,
+    organization_member_manager: OrganizationMemberManager,
+    proxy_manager: ProxyManager,
+    impersonation_manager: ImpersonationManager,
+    organization_quota_manager: OrganizationQuotaManager,
+    region_manager: RegionManager,
+    region_capacity_manager: RegionCapacityManager,
+) -> axum::Router {
+    let state = AdminState {
+        user_manager,
+        session_manager,
+        organization_manager,
+        organization_member_manager,
+        proxy_manager,
+        impersonation_manager,
+        organization_quota_manager,
+        region_manager,
+        region_capacity_manager,
+    };
+
+    axum::Router::new()
+        .route("/users", get(list_users))
+        .route("/users/:user_id", get(get_user))
+        .route("/users/:user_id/role", put(update_user_role))
+        .route("/users/:user_id/suspend", post(suspend_user))
+        .route("/users/:user_id/unsuspend", post(unsuspend_user))
+        .route("/users/:user_id/logout", post(logout_user))
+        .route("/users/:user_id/impersonate", post(impersonate_user))
+        .route("/impersonations", get(list_impersonation_audit))
+        .route("/organizations", get(list_organizations))
+        .route("/organizations/:org_id", get(get_organization))
+        .route(
+            "/organizations/:org_id/proxies",
+            get(list_organization_proxies),
+        )
+        .route(
+            "/organizations/:org_id/quota",
+            get(get_organization_quota).put(update_organization_quota),
+        )
+        .route("/regions", get(list_regions).post(create_region))
+        .route(
+            "/regions/:region_id",
+            get(get_region).patch(update_region).delete(delete_region),
+        )
+        .route("/regions/:region_id/disable", post(disable_region))
+        .route("/regions/:region_id/enable", post(enable_region))
+        .route("/regions/:region_id/capacity", get(get_region_capacity))
+        .with_state(state)
+}
+
+async fn list_users(
+    State(AdminState { user_manager, .. }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Query(query): Query<AdminSearchQuery>,
+) -> AdminResult<Json<Vec<User>>> {
+    let users = user_manager
+        .search(query.query(), query.limit(), query.offset())
+        .await?;
+
+    Ok(Json(users))
+}
+
+async fn get_user(
+    State(AdminState { user_manager, .. }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Path((user_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<User>> {
+    Ok(Json(user_manager.find_by_id(&user_id).await?))
+}
+
+async fn update_user_role(
+    State(AdminState { user_manager, .. }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((user_id,)): Path<(Uuid,)>,
+    Json(data): Json<UpdateUserRoleData>,
+) -> AdminResult<StatusCode> {
+    data.validate()?;
+
+    // demoting yourself could leave the platform without any admin
+    if user_id == admin.id {
+        return Err(AdminError::SelfTarget);
+    }
+
+    user_manager.set_role(&user_id, data.role).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn suspend_user(
+    State(AdminState {
+        user_manager,
+        session_manager,
+        ..
+    }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((user_id,)): Path<(Uuid,)>,
+) -> AdminResult<StatusCode> {
+    if user_id == admin.id {
+        return Err(AdminError::SelfTarget);
+    }
+
+    user_manager.suspend(&user_id).await?;
+    session_manager.delete_all_by_user_id(&user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn unsuspend_user(
+    State(AdminState { user_manager, .. }): State<AdminState>,
+    _admin: AuthenticatedUser<AdminRole>,
+    Path((user_id,)): Path<(Uuid,)>,
+) -> AdminResult<StatusCode> {
+    user_manager.unsuspend(&user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn logout_user(
+    State(AdminState {
+        user_manager,
+        session_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<AdminRole>,
+    Path((user_id,)): Path<(Uuid,)>,
+) -> AdminResult<StatusCode> {
+    user_manager.find_by_id(&user_id).await?;
+    session_manager.delete_all_by_user_id(&user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn impersonate_user(
+    State(AdminState {
+        user_manager,
+        session_manager,
+        ..
+    }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((user_id,)): Path<(Uuid,)>,
+    client: ClientInfo,
+    jar: CookieJar,
+) -> AdminResult<(CookieJar, Redirect)> {
+    // no chaining impersonations and no tokens, the trail must lead back to a logged in admin
+    let Credential::Session(admin_session_id) = admin.credential() else {
+        return Err(AdminError::SessionRequired);
+    };
+    if user_id == admin.id {
+        return Err(AdminError::SelfTarget);
+    }
+
+    let user = user_manager.find_by_id(&user_id).await?;
+    if user.role >= STAFF_ROLE {
+        return Err(AdminError::ImpersonationNotAllowed);
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let session = Session {
+        id: Uuid::new_v4(),
+        public_id: Uuid::new_v4(),
+        user_id: user.id,
+        created_at: now,
+        last_seen_at: now,
+        expires_at: now + IMPERSONATION_TTL,
+        user_agent: client.user_agent,
+        ip_address: client.ip_address,
+        two_factor_pending: false,
+        two_factor_attempts: 0,
+        impersonator_id: Some(admin.id),
+        impersonator_session_id: Some(*admin_session_id),
+    };
+
+    session_manager.insert(&session).await?;
+
+    info!("{} started impersonating {}", admin.id, user.id);
+
+    let jar = jar.add(session_cookie(&session.id, session.expires_at));
+
+    Ok((jar, Redirect::to("/auth/me")))
+}
+
+async fn list_impersonation_audit(
+    State(AdminState {
+        impersonation_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<AdminRole>,
+    Query(query): Query<ImpersonationAuditQuery>,
+) -> AdminResult<Json<Vec<ImpersonationAuditEntry>>> {
+    let entries = impersonation_manager
+        .list_audit_entries(
+            query.user_id.as_ref(),
+            query.impersonator_id.as_ref(),
+            query.limit(),
+            query.offset(),
+        )
+        .await?;
+
+    Ok(Json(entries))
+}
+
+async fn list_organizations(
+    State(AdminState {
+        organization_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Query(query): Query<AdminSearchQuery>,
+) -> AdminResult<Json<Vec<Organization>>> {
+    let organizations = organization_manager
+        .search(query.query(), query.limit(), query.offset())
+        .await?;
+
+    Ok(Json(organizations))
+}
+
+async fn get_organization(
+    State(AdminState {
+        organization_manager,
+        organization_member_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Path((organization_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<AdminOrganization>> {
+    let organization = organization_manager.find_by_id(&organization_id).await?;
+    let members = organization_member_manager.list(&organization_id).await?;
+
+    Ok(Json(AdminOrganization {
+        organization,
+        members,
+    }))
+}
+
+async fn list_organization_proxies(
+    State(AdminState {
+        organization_manager,
+        proxy_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Path((organization_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<Vec<Proxy>>> {
+    organization_manager.find_by_id(&organization_id).await?;
+
+    Ok(Json(proxy_manager.list(&organization_id).await?))
+}
+
+async fn get_organization_quota(
+    State(AdminState {
+        organization_quota_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Path((organization_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<OrganizationQuotaView>> {
+    Ok(Json(
+        organization_quota_manager.view(&organization_id).await?,
+    ))
+}
+
+async fn update_organization_quota(
+    State(AdminState {
+        organization_manager,
+        organization_quota_manager,
+        ..
+    }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((organization_id,)): Path<(Uuid,)>,
+    Json(data): Json<UpdateOrganizationQuotaData>,
+) -> AdminResult<Json<OrganizationQuota>> {
+    data.validate()?;
+
+    let organization = organization_manager.find_by_id(&organization_id).await?;
+    let quota = organization_quota_manager
+        .update(&organization, data)
+        .await?;
+
+    info!("{} updated the quota of {}", admin.id, organization.id);
+
+    Ok(Json(quota))
+}
+
+async fn list_regions(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+) -> AdminResult<Json<Vec<RegionDetails>>> {
+    let regions = region_manager.list().await?;
+
+    Ok(Json(regions.into_iter().map(RegionDetails::from).collect()))
+}
+
+async fn get_region(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Path((region_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<RegionDetails>> {
+    Ok(Json(region_manager.find_by_id(&region_id).await?.into()))
+}
+
+async fn create_region(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Json(data): Json<CreateRegionData>,
+) -> AdminResult<Json<RegionDetails>> {
+    data.validate()?;
+
+    let region = region_manager.create(data).await?;
+
+    info!("{} created region {}", admin.id, region.slug);
+
+    Ok(Json(region.into()))
+}
+
+async fn update_region(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((region_id,)): Path<(Uuid,)>,
+    Json(data): Json<UpdateRegionData>,
+) -> AdminResult<Json<RegionDetails>> {
+    data.validate()?;
+
+    let region = region_manager.update(&region_id, data).await?;
+
+    info!("{} updated region {}", admin.id, region.slug);
+
+    Ok(Json(region.into()))
+}
+
+async fn disable_region(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((region_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<RegionDetails>> {
+    let region = region_manager.set_disabled(&region_id, true).await?;
+
+    info!("{} disabled region {}", admin.id, region.slug);
+
+    Ok(Json(region.into()))
+}
+
+async fn enable_region(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((region_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<RegionDetails>> {
+    let region = region_manager.set_disabled(&region_id, false).await?;
+
+    info!("{} enabled region {}", admin.id, region.slug);
+
+    Ok(Json(region.into()))
+}
+
+async fn get_region_capacity(
+    State(AdminState {
+        region_manager,
+        region_capacity_manager,
+        ..
+    }): State<AdminState>,
+    _admin: AuthenticatedUser<StaffRole>,
+    Path((region_id,)): Path<(Uuid,)>,
+) -> AdminResult<Json<RegionCapacity>> {
+    let region = region_manager.find_by_id(&region_id).await?;
+
+    Ok(Json(region_capacity_manager.capacity(&region.id).await?))
+}
+
+async fn delete_region(
+    State(AdminState { region_manager, .. }): State<AdminState>,
+    admin: AuthenticatedUser<AdminRole>,
+    Path((region_id,)): Path<(Uuid,)>,
+) -> AdminResult<StatusCode> {
+    region_manager.delete(&region_id).await?;
+
+    info!("{} deleted region {}", admin.id, region_id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Clone)]
+struct AdminState {
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    organization_manager: OrganizationManager,
+    organization_member_manager: OrganizationMemberManager,
+    proxy_manager: ProxyManager,
+    impersonation_manager: ImpersonationManager,
+    organization_quota_manager: OrganizationQuotaManager,
+    region_manager: RegionManager,
+    region_capacity_manager: RegionCapacityManager,
+}
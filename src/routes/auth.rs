@@ -1,33 +1,65 @@
-use crate::domains::auth::{AuthResult, LoginData, RegisterData};
-use crate::domains::session::Session;
-use crate::domains::user::User;
+use crate::consts::{
+    DUMMY_PASSWORD_HASH, SESSION_COOKIE_NAME, SESSION_TTL, TWO_FACTOR_MAX_ATTEMPTS,
+    TWO_FACTOR_PENDING_TTL,
+};
+use crate::domains::auth::{
+    AuthError, AuthResult, DeleteMeData, LoginData, MeView, RegisterData, UpdateMeData,
+};
+use crate::domains::impersonation::Impersonator;
+use crate::domains::session::{csrf_token, session_cookie, Session, SessionError, SessionView};
+use crate::domains::two_factor::{TwoFactorChallenge, TwoFactorCodeData, TwoFactorError};
+use crate::domains::user::{User, UserError};
 use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::extractors::client_info::ClientInfo;
+use crate::managers::login_attempt::LoginAttemptManager;
+use crate::managers::organization_invitation::OrganizationInvitationManager;
+use crate::managers::organization_member::OrganizationMemberManager;
 use crate::managers::session::SessionManager;
+use crate::managers::two_factor::TwoFactorManager;
 use crate::managers::user::UserManager;
-use argon2::password_hash::rand_core::OsRng;
-use argon2::password_hash::SaltString;
-use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
-use axum::extract::State;
-use axum::response::Redirect;
-use axum::routing::{get, post};
+use crate::managers::user_token::UserTokenManager;
+use crate::utils::{hash_password, verify_password};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::{delete, get, post};
 use axum::Json;
 use axum_extra::extract::cookie::Cookie;
 use axum_extra::extract::CookieJar;
-use time::{Duration, OffsetDateTime};
+use time::OffsetDateTime;
+use tracing::error;
 use uuid::Uuid;
 use validator::Validate;
 
-pub fn router(user_manager: UserManager, session_manager: SessionManager) -> axum::Router {
+pub fn router(
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    two_factor_manager: TwoFactorManager,
+    user_token_manager: UserTokenManager,
+    organization_member_manager: OrganizationMemberManager,
+    organization_invitation_manager: OrganizationInvitationManager,
+    login_attempt_manager: LoginAttemptManager,
+) -> axum::Router {
     let auth_state = AuthState {
         user_manager,
         session_manager,
+        two_factor_manager,
+        user_token_manager,
+        organization_member_manager,
+        organization_invitation_manager,
+        login_attempt_manager,
     };
 
     axum::Router::new()
         .route("/register", post(register))
         .route("/login", post(login))
-        .route("/me", get(me))
-        .route("/logout", get(logout))
+        .route("/login/2fa", post(login_two_factor))
+        .route("/me", get(me).patch(update_me).delete(delete_me))
+        .route("/logout", post(logout))
+        .route("/logout/everywhere", post(logout_everywhere))
+        .route("/impersonation", delete(stop_impersonation))
+        .route("/sessions", get(list_sessions))
+        .route("/sessions/:public_id", delete(revoke_session))
         .with_state(auth_state)
 }
 
@@ -35,7 +67,10 @@ async fn register(
     State(AuthState {
         user_manager,
         session_manager,
+        user_token_manager,
+        ..
     }): State<AuthState>,
+    client: ClientInfo,
     jar: CookieJar,
     Json(data): Json<RegisterData>,
 ) -> AuthResult<(CookieJar, Redirect)> {
@@ -47,15 +82,18 @@ async fn register(
         email: data.email,
         password_hash: hash_password(&data.password),
         role: 0,
+        email_verified: false,
+        suspended_at: None,
     };
 
     user_manager.insert(&user).await?;
 
-    let session = Session {
-        id: Uuid::new_v4(),
-        user,
-        expires_at: OffsetDateTime::now_utc() + Duration::hours(12),
-    };
+    // the account is usable without a verified email, a failed mail must not fail the sign up
+    if let Err(err) = user_token_manager.send_email_verification(&user).await {
+        error!("failed to send email verification to {}: {}", user.id, err);
+    }
+
+    let session = new_session(&user, client);
 
     session_manager.insert(&session).await?;
 
@@ -68,22 +106,144 @@ async fn login(
     State(AuthState {
         user_manager,
         session_manager,
+        two_factor_manager,
+        login_attempt_manager,
+        ..
     }): State<AuthState>,
+    client: ClientInfo,
     jar: CookieJar,
     Json(data): Json<LoginData>,
-) -> AuthResult<(CookieJar, Redirect)> {
+) -> AuthResult<Response> {
     data.validate()?;
 
-    let user = user_manager.find_by_email(&data.email).await?;
+    let account = data.email.trim().to_lowercase();
+    let ip_address = client.ip_address.clone();
+
+    login_attempt_manager
+        .ensure_unlocked(&account, ip_address.as_deref())
+        .await?;
+
+    let user = match user_manager.find_by_email(&data.email).await {
+        Ok(user) => Some(user),
+        Err(UserError::NotFound) => None,
+        Err(err) => return Err(err.into()),
+    };
+
+    // unknown emails still pay for a full hash verification and fail the same way as a wrong password
+    let password_hash = user
+        .as_ref()
+        .map_or(&*DUMMY_PASSWORD_HASH, |user| &user.password_hash);
+    let verified = match verify_password(&data.password, password_hash) {
+        Ok(()) => true,
+        Err(password_hash::Error::Password) => false,
+        Err(err) => return Err(err.into()),
+    };
+
+    let Some(user) = user.filter(|_| verified) else {
+        login_attempt_manager
+            .record_failure(&account, ip_address.as_deref())
+            .await?;
+        return Err(AuthError::InvalidCredentials);
+    };
+
+    if user.suspended_at.is_some() {
+        return Err(AuthError::AccountSuspended);
+    }
+
+    if two_factor_manager.is_enabled(&user.id).await? {
+        let session = Session {
+            expires_at: OffsetDateTime::now_utc() + TWO_FACTOR_PENDING_TTL,
+            two_factor_pending: true,
+            ..new_session(&user, client)
+        };
+
+        session_manager.insert(&session).await?;
+
+        let jar = jar_with_session(jar, session);
+        let challenge = TwoFactorChallenge {
+            two_factor_required: true,
+        };
+
+        return Ok((StatusCode::ACCEPTED, jar, Json(challenge)).into_response());
+    }
+
+    login_attempt_manager.record_success(&account).await?;
+
+    let session = new_session(&user, client);
+
+    session_manager.insert(&session).await?;
+
+    let jar = jar_with_session(jar, session);
+
+    Ok((jar, Redirect::to("/auth/me")).into_response())
+}
+
+async fn login_two_factor(
+    State(AuthState {
+        user_manager,
+        session_manager,
+        two_factor_manager,
+        login_attempt_manager,
+        ..
+    }): State<AuthState>,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(data): Json<TwoFactorCodeData>,
+) -> AuthResult<(CookieJar, Redirect)> {
+    let Some(pending_session_id) = jar
+        .get(SESSION_COOKIE_NAME)
+        .and_then(|cookie| Uuid::parse_str(cookie.value()).ok())
+    else {
+        return Err(AuthError::InvalidSession);
+    };
 
-    let hash = PasswordHash::new(&user.password_hash).unwrap();
-    let argon2 = Argon2::default();
-    argon2.verify_password(data.password.as_bytes(), &hash)?;
+    let pending_session = session_manager
+        .find_two_factor_pending(&pending_session_id)
+        .await?;
 
+    // codes are throttled per account like passwords, a new pending session must not reset that
+    let user = user_manager.find_by_id(&pending_session.user_id).await?;
+    let account = user.email.trim().to_lowercase();
+    let ip_address = client.ip_address.as_deref();
+    login_attempt_manager
+        .ensure_unlocked(&account, ip_address)
+        .await?;
+
+    match two_factor_manager
+        .verify(&pending_session.user_id, &data.code)
+        .await
+    {
+        Ok(()) => {}
+        Err(TwoFactorError::InvalidCode) => {
+            login_attempt_manager
+                .record_failure(&account, ip_address)
+                .await?;
+            let attempts = session_manager
+                .increment_two_factor_attempts(&pending_session_id)
+                .await?;
+            if attempts >= TWO_FACTOR_MAX_ATTEMPTS {
+                session_manager.delete(&pending_session_id).await?;
+            }
+            return Err(TwoFactorError::InvalidCode.into());
+        }
+        Err(err) => return Err(err.into()),
+    }
+
+    login_attempt_manager.record_success(&account).await?;
+
+    // the pending session id was handed out before the second factor, never promote it
+    session_manager.delete(&pending_session_id).await?;
+
+    let now = OffsetDateTime::now_utc();
     let session = Session {
         id: Uuid::new_v4(),
-        user,
-        expires_at: OffsetDateTime::now_utc() + Duration::hours(12),
+        public_id: Uuid::new_v4(),
+        created_at: now,
+        last_seen_at: now,
+        expires_at: now + SESSION_TTL,
+        two_factor_pending: false,
+        two_factor_attempts: 0,
+        ..pending_session
     };
 
     session_manager.insert(&session).await?;
@@ -93,43 +253,261 @@ async fn login(
     Ok((jar, Redirect::to("/auth/me")))
 }
 
-async fn me(user: AuthenticatedUser) -> AuthResult<Json<User>> {
-    Ok(Json(user.into()))
+async fn me(
+    State(AuthState {
+        user_manager,
+        organization_invitation_manager,
+        ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+) -> AuthResult<Json<MeView>> {
+    let impersonated_by = match user.impersonator_id() {
+        Some(impersonator_id) => {
+            let impersonator = user_manager.find_by_id(impersonator_id).await?;
+            Some(Impersonator {
+                id: impersonator.id,
+                name: impersonator.name,
+                email: impersonator.email,
+            })
+        }
+        None => None,
+    };
+
+    let pending_invitations = organization_invitation_manager.list_pending(&user).await?;
+    let csrf_token = user.session_id().map(csrf_token);
+
+    Ok(Json(MeView {
+        user: user.into(),
+        impersonated_by,
+        pending_invitations,
+        csrf_token,
+    }))
 }
 
-async fn logout(_user: AuthenticatedUser, jar: CookieJar) -> AuthResult<CookieJar> {
-    // TODO: remove from db
-    let jar = jar.remove(
-        Cookie::build("ork_session_id", "")
-            .http_only(true)
-            .secure(true)
-            .path("/")
-            .finish(),
-    );
+async fn stop_impersonation(
+    State(AuthState {
+        session_manager, ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+    jar: CookieJar,
+) -> AuthResult<CookieJar> {
+    let (Some(session_id), Some(_)) = (user.session_id(), user.impersonator_id()) else {
+        return Err(AuthError::NotImpersonating);
+    };
+
+    let session = session_manager.find_by_id(session_id).await?;
+    session_manager.delete(session_id).await?;
+
+    // hand the admin their own session back if it is still alive
+    let Some(impersonator_session_id) = session.impersonator_session_id else {
+        return Ok(jar_without_session(jar));
+    };
+    match session_manager.find_by_id(&impersonator_session_id).await {
+        Ok(impersonator_session) => Ok(jar_with_session(jar, impersonator_session)),
+        Err(SessionError::Invalid) => Ok(jar_without_session(jar)),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn update_me(
+    State(AuthState {
+        user_manager,
+        session_manager,
+        user_token_manager,
+        ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+    jar: CookieJar,
+    Json(data): Json<UpdateMeData>,
+) -> AuthResult<(CookieJar, Json<User>)> {
+    data.validate()?;
+
+    let mut updated_user: User = (*user).clone();
+    let email_changed = data
+        .email
+        .as_ref()
+        .is_some_and(|email| email != &updated_user.email);
+    let password_changed = data.password.is_some();
+
+    if email_changed || password_changed {
+        let Some(current_password) = &data.current_password else {
+            return Err(AuthError::CurrentPasswordRequired);
+        };
+        verify_password(current_password, &updated_user.password_hash)?;
+    }
+
+    if let Some(name) = data.name {
+        updated_user.name = name;
+    }
+    if email_changed {
+        updated_user.email = data.email.unwrap();
+        updated_user.email_verified = false;
+    }
+    if let Some(password) = &data.password {
+        updated_user.password_hash = hash_password(password);
+    }
+
+    user_manager.update(&updated_user).await?;
+
+    if email_changed {
+        if let Err(err) = user_token_manager
+            .send_email_verification(&updated_user)
+            .await
+        {
+            error!(
+                "failed to send email verification to {}: {}",
+                updated_user.id, err
+            );
+        }
+    }
+
+    // a new password invalidates every existing session, including the one used for this request
+    let jar = if password_changed {
+        session_manager
+            .delete_all_by_user_id(&updated_user.id)
+            .await?;
+
+        let session = new_session(&updated_user, client);
+
+        session_manager.insert(&session).await?;
+
+        jar_with_session(jar, session)
+    } else {
+        jar
+    };
+
+    Ok((jar, Json(updated_user)))
+}
+
+async fn delete_me(
+    State(AuthState {
+        user_manager,
+        organization_member_manager,
+        ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+    jar: CookieJar,
+    Json(data): Json<DeleteMeData>,
+) -> AuthResult<(StatusCode, CookieJar)> {
+    verify_password(&data.password, &user.password_hash)?;
+
+    let organization_ids = organization_member_manager
+        .list_solely_owned_organization_ids(&user.id)
+        .await?;
+    if !organization_ids.is_empty() {
+        return Err(AuthError::SoleOrganizationOwner(organization_ids));
+    }
+
+    // memberships, sessions and tokens are removed by the foreign key cascades
+    user_manager.delete(&user.id).await?;
+
+    Ok((StatusCode::NO_CONTENT, jar_without_session(jar)))
+}
+
+async fn list_sessions(
+    State(AuthState {
+        session_manager, ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+) -> AuthResult<Json<Vec<SessionView>>> {
+    let sessions = session_manager
+        .list_by_user_id(&user.id)
+        .await?
+        .into_iter()
+        .map(|session| SessionView {
+            current: Some(&session.id) == user.session_id(),
+            session,
+        })
+        .collect();
+
+    Ok(Json(sessions))
+}
+
+async fn revoke_session(
+    State(AuthState {
+        session_manager, ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+    Path((public_id,)): Path<(Uuid,)>,
+    jar: CookieJar,
+) -> AuthResult<CookieJar> {
+    let session_id = session_manager
+        .delete_by_public_id(&user.id, &public_id)
+        .await?;
+
+    if Some(&session_id) == user.session_id() {
+        return Ok(jar_without_session(jar));
+    }
+
     Ok(jar)
 }
+
+async fn logout(
+    State(AuthState {
+        session_manager, ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+    jar: CookieJar,
+) -> AuthResult<CookieJar> {
+    if let Some(session_id) = user.session_id() {
+        session_manager.delete(session_id).await?;
+    }
+
+    Ok(jar_without_session(jar))
+}
+
+async fn logout_everywhere(
+    State(AuthState {
+        session_manager, ..
+    }): State<AuthState>,
+    user: AuthenticatedUser,
+    jar: CookieJar,
+) -> AuthResult<CookieJar> {
+    session_manager.delete_all_by_user_id(&user.id).await?;
+
+    Ok(jar_without_session(jar))
+}
+
 #[derive(Clone)]
 struct AuthState {
     user_manager: UserManager,
     session_manager: SessionManager,
+    two_factor_manager: TwoFactorManager,
+    user_token_manager: UserTokenManager,
+    organization_member_manager: OrganizationMemberManager,
+    organization_invitation_manager: OrganizationInvitationManager,
+    login_attempt_manager: LoginAttemptManager,
 }
 
-fn hash_password(password: &String) -> String {
-    let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
+pub(super) fn new_session(user: &User, client: ClientInfo) -> Session {
+    let now = OffsetDateTime::now_utc();
+
+    Session {
+        id: Uuid::new_v4(),
+        public_id: Uuid::new_v4(),
+        user_id: user.id,
+        created_at: now,
+        last_seen_at: now,
+        expires_at: now + SESSION_TTL,
+        user_agent: client.user_agent,
+        ip_address: client.ip_address,
+        two_factor_pending: false,
+        two_factor_attempts: 0,
+        impersonator_id: None,
+        impersonator_session_id: None,
+    }
+}
 
-    argon2
-        .hash_password(password.as_bytes(), &salt)
-        .unwrap()
-        .to_string()
+pub(super) fn jar_with_session(jar: CookieJar, session: Session) -> CookieJar {
+    jar.add(session_cookie(&session.id, session.expires_at))
 }
 
-fn jar_with_session(jar: CookieJar, session: Session) -> CookieJar {
-    jar.add(
-        Cookie::build("ork_session_id", session.id.to_string())
+pub(super) fn jar_without_session(jar: CookieJar) -> CookieJar {
+    jar.remove(
+        Cookie::build(SESSION_COOKIE_NAME, "")
             .http_only(true)
             .secure(true)
-            .expires(session.expires_at)
             .path("/")
             .finish(),
     )
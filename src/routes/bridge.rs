@@ -5,7 +5,8 @@ use ork_bridge_service::domains::namespace::CreateNamespaceData;
 use validator::Validate;
 
 use crate::domains::bridge::{Bridge, BridgeResult, CreateBridgeData};
-use crate::extractors::authenticated_org_member::AuthenticatedOrgMember;
+use crate::extractors::authenticated_org_member::{AuthenticatedOrgMember, CanWriteBridge};
+use crate::extractors::authenticated_user::AnyUserRole;
 use crate::managers::bridge::BridgeManager;
 use crate::managers::region_connection::RegionConnectionManager;
 
@@ -28,21 +29,23 @@ async fn create(
         bridge_manager,
         region_connection_manager,
     }): State<BridgeState>,
-    org_member: AuthenticatedOrgMember,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanWriteBridge>,
     Json(data): Json<CreateBridgeData>,
 ) -> BridgeResult<Json<Bridge>> {
     data.validate()?;
 
     let organization = org_member.org();
 
+    bridge_manager.ensure_quota(organization).await?;
+
     let bs_client = region_connection_manager
         .find_bridge_service_client_by_id(&organization.region_id)
-        .await
-        .unwrap();
+        .await?;
 
     let namespace = bs_client
         .create_namespace(&CreateNamespaceData {
-            slug: format!("{}-{}-{}", &organization.slug, data.slug, "00000000"),
+            // keyed by the namespace, a renamed organization's slug can be taken by another
+            slug: format!("{}-{}", &organization.namespace, data.slug),
         })
         .await?;
 
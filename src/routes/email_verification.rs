@@ -0,0 +1,59 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::Json;
+
+use crate::domains::auth::AuthResult;
+use crate::domains::user_token::{ConfirmEmailVerificationData, UserTokenKind};
+use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::managers::user::UserManager;
+use crate::managers::user_token::UserTokenManager;
+
+pub fn router(user_manager: UserManager, user_token_manager: UserTokenManager) -> axum::Router {
+    let state = EmailVerificationState {
+        user_manager,
+        user_token_manager,
+    };
+
+    axum::Router::new()
+        .route("/", post(request))
+        .route("/confirm", post(confirm))
+        .with_state(state)
+}
+
+async fn request(
+    State(EmailVerificationState {
+        user_token_manager, ..
+    }): State<EmailVerificationState>,
+    user: AuthenticatedUser,
+) -> AuthResult<StatusCode> {
+    if user.email_verified {
+        return Ok(StatusCode::NO_CONTENT);
+    }
+
+    user_token_manager.send_email_verification(&user).await?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn confirm(
+    State(EmailVerificationState {
+        user_manager,
+        user_token_manager,
+    }): State<EmailVerificationState>,
+    Json(data): Json<ConfirmEmailVerificationData>,
+) -> AuthResult<StatusCode> {
+    let token = user_token_manager
+        .consume(UserTokenKind::EmailVerification, &data.token)
+        .await?;
+
+    user_manager.set_email_verified(&token.user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Clone)]
+struct EmailVerificationState {
+    user_manager: UserManager,
+    user_token_manager: UserTokenManager,
+}
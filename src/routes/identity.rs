@@ -0,0 +1,39 @@
+use axum::extract::{Path, State};
+use axum::routing::{delete, get};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::domains::identity::{Identity, IdentityResult};
+use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::managers::identity::IdentityManager;
+
+pub fn router(identity_manager: IdentityManager) -> axum::Router {
+    let state = IdentityState { identity_manager };
+
+    axum::Router::new()
+        .route("/", get(list))
+        .route("/:identity_id", delete(unlink))
+        .with_state(state)
+}
+
+async fn list(
+    State(IdentityState { identity_manager }): State<IdentityState>,
+    user: AuthenticatedUser,
+) -> IdentityResult<Json<Vec<Identity>>> {
+    identity_manager.list_by_user_id(&user.id).await.map(Json)
+}
+
+async fn unlink(
+    State(IdentityState { identity_manager }): State<IdentityState>,
+    user: AuthenticatedUser,
+    Path((identity_id,)): Path<(Uuid,)>,
+) -> IdentityResult<()> {
+    identity_manager
+        .delete_by_user_id(&user.id, &identity_id)
+        .await
+}
+
+#[derive(Clone)]
+struct IdentityState {
+    identity_manager: IdentityManager,
+}
@@ -0,0 +1,54 @@
+use axum::extract::{Path, State};
+use axum::routing::post;
+use axum::Json;
+use uuid::Uuid;
+
+use crate::domains::organization_invitation::{
+    AcceptInvitationData, OrganizationInvitation, OrganizationInvitationResult,
+};
+use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::managers::organization_invitation::OrganizationInvitationManager;
+
+pub fn router(organization_invitation_manager: OrganizationInvitationManager) -> axum::Router {
+    let state = InvitationState {
+        organization_invitation_manager,
+    };
+
+    axum::Router::new()
+        .route("/accept", post(accept_token))
+        .route("/:invitation_id/accept", post(accept))
+        .with_state(state)
+}
+
+async fn accept_token(
+    State(InvitationState {
+        organization_invitation_manager,
+        ..
+    }): State<InvitationState>,
+    user: AuthenticatedUser,
+    Json(data): Json<AcceptInvitationData>,
+) -> OrganizationInvitationResult<Json<OrganizationInvitation>> {
+    organization_invitation_manager
+        .accept_token(&user, &data.token)
+        .await
+        .map(Json)
+}
+
+async fn accept(
+    State(InvitationState {
+        organization_invitation_manager,
+        ..
+    }): State<InvitationState>,
+    user: AuthenticatedUser,
+    Path((invitation_id,)): Path<(Uuid,)>,
+) -> OrganizationInvitationResult<Json<OrganizationInvitation>> {
+    organization_invitation_manager
+        .accept_by_id(&user, &invitation_id)
+        .await
+        .map(Json)
+}
+
+#[derive(Clone)]
+struct InvitationState {
+    organization_invitation_manager: OrganizationInvitationManager,
+}
@@ -1,7 +1,19 @@
+pub mod admin;
 pub mod auth;
 pub mod bridge;
+pub mod email_verification;
+pub mod identity;
+pub mod invitation;
+pub mod oidc;
 pub mod organization;
+pub mod organization_api_key;
+pub mod organization_custom_role;
+pub mod organization_invitation;
 pub mod organization_member;
+pub mod organization_quota;
+pub mod password_reset;
+pub mod personal_access_token;
 pub mod proxy;
 pub mod proxy_template;
 pub mod region;
+pub mod two_factor;
@@ -0,0 +1,325 @@
+use axum::extract::{Path, Query, State};
+use axum::response::Redirect;
+use axum::routing::{get, post};
+use axum::{Form, Json};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum_extra::extract::CookieJar;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::clients::oidc::ExternalIdentity;
+use crate::consts::{
+    OIDC_AUTH_TIME_LEEWAY, OIDC_STATE_COOKIE_NAME, OIDC_STATE_TTL, SPACE_REGEX,
+    TWO_FACTOR_PENDING_TTL, WEBSITE_URL,
+};
+use crate::domains::identity::{Identity, IdentityError};
+use crate::domains::oidc::{
+    OidcCallbackQuery, OidcDeleteAccountData, OidcError, OidcIntent, OidcResult,
+};
+use crate::domains::session::{verify_csrf_token, Session};
+use crate::domains::user::{User, UserError};
+use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::extractors::client_info::ClientInfo;
+use crate::managers::identity::IdentityManager;
+use crate::managers::oidc::OidcManager;
+use crate::managers::organization_member::OrganizationMemberManager;
+use crate::managers::session::SessionManager;
+use crate::managers::two_factor::TwoFactorManager;
+use crate::managers::user::UserManager;
+use crate::routes::auth::{jar_with_session, jar_without_session, new_session};
+use crate::utils::{generate_secret, hash_password};
+
+pub fn router(
+    oidc_manager: OidcManager,
+    identity_manager: IdentityManager,
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    two_factor_manager: TwoFactorManager,
+    organization_member_manager: OrganizationMemberManager,
+) -> axum::Router {
+    let state = OidcState {
+        oidc_manager,
+        identity_manager,
+        user_manager,
+        session_manager,
+        two_factor_manager,
+        organization_member_manager,
+    };
+
+    axum::Router::new()
+        .route("/", get(list_providers))
+        .route("/:provider", get(authorize))
+        .route("/:provider/link", get(authorize_link))
+        .route("/:provider/delete-account", post(authorize_delete_account))
+        .route("/:provider/callback", get(callback))
+        .with_state(state)
+}
+
+async fn list_providers(
+    State(OidcState { oidc_manager, .. }): State<OidcState>,
+) -> Json<Vec<String>> {
+    Json(oidc_manager.provider_names())
+}
+
+async fn authorize(
+    State(OidcState { oidc_manager, .. }): State<OidcState>,
+    Path((provider,)): Path<(String,)>,
+    jar: CookieJar,
+) -> OidcResult<(CookieJar, Redirect)> {
+    let authorization = oidc_manager
+        .authorize(&provider, OidcIntent::Login, None)
+        .await?;
+
+    Ok((
+        jar.add(state_cookie(authorization.state)),
+        Redirect::to(&authorization.url),
+    ))
+}
+
+async fn authorize_link(
+    State(OidcState { oidc_manager, .. }): State<OidcState>,
+    user: AuthenticatedUser,
+    Path((provider,)): Path<(String,)>,
+    jar: CookieJar,
+) -> OidcResult<(CookieJar, Redirect)> {
+    let authorization = oidc_manager
+        .authorize(&provider, OidcIntent::Link, Some(user.id))
+        .await?;
+
+    Ok((
+        jar.add(state_cookie(authorization.state)),
+        Redirect::to(&authorization.url),
+    ))
+}
+
+/// Accounts created through a provider have no password to confirm `DELETE /auth/me` with,
+/// signing in again with a linked identity confirms the deletion instead. Submitted as a form
+/// carrying the CSRF token from `/auth/me` so another site can't start it.
+async fn authorize_delete_account(
+    State(OidcState { oidc_manager, .. }): State<OidcState>,
+    user: AuthenticatedUser,
+    Path((provider,)): Path<(String,)>,
+    jar: CookieJar,
+    Form(data): Form<OidcDeleteAccountData>,
+) -> OidcResult<(CookieJar, Redirect)> {
+    if !user
+        .session_id()
+        .is_some_and(|session_id| verify_csrf_token(session_id, &data.csrf_token))
+    {
+        return Err(OidcError::InvalidCsrfToken);
+    }
+
+    let authorization = oidc_manager
+        .authorize(&provider, OidcIntent::DeleteAccount, Some(user.id))
+        .await?;
+
+    Ok((
+        jar.add(state_cookie(authorization.state)),
+        Redirect::to(&authorization.url),
+    ))
+}
+
+async fn callback(
+    State(OidcState {
+        oidc_manager,
+        identity_manager,
+        user_manager,
+        session_manager,
+        two_factor_manager,
+        organization_member_manager,
+    }): State<OidcState>,
+    Path((provider,)): Path<(String,)>,
+    Query(query): Query<OidcCallbackQuery>,
+    client: ClientInfo,
+    jar: CookieJar,
+) -> OidcResult<(CookieJar, Redirect)> {
+    // the state must come back to the browser that started the flow, not just exist
+    let state_matches = jar
+        .get(OIDC_STATE_COOKIE_NAME)
+        .is_some_and(|cookie| cookie.value() == query.state);
+    let jar = jar.remove(state_cookie(String::new()));
+
+    if !state_matches {
+        return Err(OidcError::InvalidState);
+    }
+    if let Some(error) = query.error {
+        return Err(OidcError::Denied(error));
+    }
+    let Some(code) = query.code else {
+        return Err(OidcError::InvalidState);
+    };
+
+    let (login_state, external) = oidc_manager
+        .complete(&provider, &query.state, &code)
+        .await?;
+
+    if login_state.intent == OidcIntent::DeleteAccount.as_str() {
+        // only an identity already linked to the account can confirm its deletion
+        let user_id = identity_manager
+            .find_by_subject(&provider, &external.subject)
+            .await?
+            .map(|identity| identity.user_id)
+            .filter(|user_id| Some(user_id) == login_state.user_id.as_ref())
+            .ok_or(IdentityError::NotFound)?;
+
+        // prompt=login is only a request, the provider has to vouch for a sign in made after the
+        // deletion was started, providers without an id_token (GitHub, Discord) never can
+        if !external
+            .auth_time
+            .is_some_and(|auth_time| auth_time + OIDC_AUTH_TIME_LEEWAY >= login_state.created_at)
+        {
+            return Err(OidcError::ReauthenticationRequired);
+        }
+
+        let organization_ids = organization_member_manager
+            .list_solely_owned_organization_ids(&user_id)
+            .await?;
+        if !organization_ids.is_empty() {
+            return Err(OidcError::SoleOrganizationOwner(organization_ids));
+        }
+
+        // memberships, sessions and tokens are removed by the foreign key cascades
+        user_manager.delete(&user_id).await?;
+
+        return Ok((jar_without_session(jar), Redirect::to(&WEBSITE_URL)));
+    }
+
+    let user = match identity_manager
+        .find_by_subject(&provider, &external.subject)
+        .await?
+    {
+        Some(identity) => {
+            if login_state
+                .user_id
+                .is_some_and(|user_id| user_id != identity.user_id)
+            {
+                return Err(IdentityError::AlreadyLinked.into());
+            }
+
+            identity_manager
+                .touch(&identity.id, &external.email)
+                .await?;
+            user_manager.find_by_id(&identity.user_id).await?
+        }
+        None => {
+            let user = match login_state.user_id {
+                Some(user_id) => user_manager.find_by_id(&user_id).await?,
+                None => find_or_create_user(&user_manager, &external).await?,
+            };
+
+            let now = OffsetDateTime::now_utc();
+            identity_manager
+                .insert(&Identity {
+                    id: Uuid::new_v4(),
+                    user_id: user.id,
+                    provider: provider.clone(),
+                    subject: external.subject,
+                    email: external.email,
+                    created_at: now,
+                    last_login_at: Some(now),
+                })
+                .await?;
+
+            user
+        }
+    };
+
+    if login_state.user_id.is_some() {
+        return Ok((jar, Redirect::to(&format!("{}/settings", *WEBSITE_URL))));
+    }
+
+    if user.suspended_at.is_some() {
+        return Err(OidcError::AccountSuspended);
+    }
+
+    if two_factor_manager.is_enabled(&user.id).await? {
+        let session = Session {
+            expires_at: OffsetDateTime::now_utc() + TWO_FACTOR_PENDING_TTL,
+            two_factor_pending: true,
+            ..new_session(&user, client)
+        };
+
+        session_manager.insert(&session).await?;
+
+        let jar = jar_with_session(jar, session);
+
+        return Ok((jar, Redirect::to(&format!("{}/login/2fa", *WEBSITE_URL))));
+    }
+
+    let session = new_session(&user, client);
+
+    session_manager.insert(&session).await?;
+
+    let jar = jar_with_session(jar, session);
+
+    Ok((jar, Redirect::to(&WEBSITE_URL)))
+}
+
+/// Links to an existing account only when both sides vouch for the email, otherwise whoever
+/// registered the address first (or the provider account) could take over the other.
+async fn find_or_create_user(
+    user_manager: &UserManager,
+    external: &ExternalIdentity,
+) -> OidcResult<User> {
+    let Some(email) = &external.email else {
+        return Err(OidcError::EmailRequired);
+    };
+
+    match user_manager.find_by_email(email).await {
+        Ok(user) => {
+            if !external.email_verified || !user.email_verified {
+                return Err(OidcError::EmailNotVerified);
+            }
+            Ok(user)
+        }
+        Err(UserError::NotFound) => {
+            let user = User {
+                id: Uuid::new_v4(),
+                name: display_name(external, email),
+                email: email.clone(),
+                // nobody knows this password, a password reset sets a usable one
+                password_hash: hash_password(&generate_secret("", 32)),
+                role: 0,
+                email_verified: external.email_verified,
+                suspended_at: None,
+            };
+
+            user_manager.insert(&user).await?;
+
+            Ok(user)
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn display_name(external: &ExternalIdentity, email: &str) -> String {
+    let name = external
+        .name
+        .as_deref()
+        .map(|name| SPACE_REGEX.replace_all(name.trim(), " ").to_string())
+        .filter(|name| name.chars().count() >= 2)
+        .unwrap_or_else(|| email.split('@').next().unwrap_or(email).to_string());
+
+    name.chars().take(255).collect()
+}
+
+fn state_cookie(state: String) -> Cookie<'static> {
+    // lax, the callback is a cross-site top level navigation from the provider
+    Cookie::build(OIDC_STATE_COOKIE_NAME, state)
+        .http_only(true)
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(OIDC_STATE_TTL)
+        .path("/auth/oidc")
+        .finish()
+}
+
+#[derive(Clone)]
+struct OidcState {
+    oidc_manager: OidcManager,
+    identity_manager: IdentityManager,
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    two_factor_manager: TwoFactorManager,
+    organization_member_manager: OrganizationMemberManager,
+}
@@ -1,12 +1,19 @@
 use axum::extract::State;
-use axum::routing::{get, post};
+use axum::routing::{get, patch, post, put};
 use axum::Json;
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::domains::organization::{CreateOrganizationData, Organization, OrganizationResult};
-use crate::domains::organization_member::OrganizationMember;
-use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::consts::AsNamespaceName;
+use crate::domains::organization::{
+    CreateOrganizationData, Organization, OrganizationError, OrganizationResult,
+    RenameOrganizationData, UpdateOrganizationData,
+};
+use crate::domains::organization_member::{OrganizationMember, OrganizationMemberRole};
+use crate::extractors::authenticated_org_member::{
+    AuthenticatedOrgMember, CanManageOrganization, OwnerOrgRole,
+};
+use crate::extractors::authenticated_user::{AnyUserRole, AuthenticatedUser};
 use crate::managers::organization::OrganizationManager;
 use crate::managers::organization_member::OrganizationMemberManager;
 use crate::managers::region::RegionManager;
@@ -25,6 +32,9 @@ pub fn router(
     axum::Router::new()
         .route("/", get(list))
         .route("/", post(create))
+        .route("/:org_id", patch(update).delete(schedule_deletion))
+        .route("/:org_id/slug", put(rename))
+        .route("/:org_id/restore", post(restore))
         .with_state(state)
 }
 
@@ -53,12 +63,25 @@ async fn create(
 ) -> OrganizationResult<Json<Organization>> {
     data.validate()?;
 
-    let region = region_manager.find_by_slug(&data.region_slug).await?;
+    if !user.email_verified {
+        return Err(OrganizationError::EmailNotVerified);
+    }
 
+    let region = region_manager
+        .find_enabled_by_slug(&data.region_slug)
+        .await?;
+
+    let id = Uuid::new_v4();
     let organization = Organization {
-        id: Uuid::new_v4(),
+        id,
+        display_name: data.display_name.unwrap_or_else(|| data.slug.clone()),
         slug: data.slug,
         region_id: region.id,
+        namespace: id.as_namespace_name(),
+        description: None,
+        avatar_url: None,
+        contact_email: None,
+        deletion_scheduled_at: None,
     };
 
     organization_manager.create(&organization).await?;
@@ -66,7 +89,8 @@ async fn create(
     let organization_member = OrganizationMember {
         organization_id: organization.id,
         user_id: user.id,
-        role: 2,
+        role: OrganizationMemberRole::Owner,
+        custom_role_id: None,
     };
 
     organization_member_manager
@@ -76,6 +100,71 @@ async fn create(
     Ok(Json(organization))
 }
 
+async fn update(
+    State(OrganizationState {
+        organization_manager,
+        ..
+    }): State<OrganizationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageOrganization>,
+    Json(data): Json<UpdateOrganizationData>,
+) -> OrganizationResult<Json<Organization>> {
+    data.validate()?;
+
+    let mut organization = org_member.org().clone();
+    data.apply(&mut organization);
+
+    organization_manager
+        .update_settings(&organization)
+        .await
+        .map(Json)
+}
+
+async fn rename(
+    State(OrganizationState {
+        organization_manager,
+        ..
+    }): State<OrganizationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, OwnerOrgRole>,
+    Json(data): Json<RenameOrganizationData>,
+) -> OrganizationResult<Json<Organization>> {
+    data.validate()?;
+
+    organization_manager
+        .rename_slug(&org_member.org().id, &data.slug)
+        .await
+        .map(Json)
+}
+
+async fn schedule_deletion(
+    State(OrganizationState {
+        organization_manager,
+        ..
+    }): State<OrganizationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, OwnerOrgRole>,
+) -> OrganizationResult<Json<Organization>> {
+    if org_member.api_key_id().is_some() {
+        return Err(OrganizationError::Forbidden);
+    }
+
+    organization_manager
+        .schedule_deletion(&org_member.org().id, &org_member.id)
+        .await
+        .map(Json)
+}
+
+async fn restore(
+    State(OrganizationState {
+        organization_manager,
+        ..
+    }): State<OrganizationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, OwnerOrgRole>,
+) -> OrganizationResult<Json<Organization>> {
+    organization_manager
+        .cancel_deletion(&org_member.org().id)
+        .await
+        .map(Json)
+}
+
 #[derive(Clone)]
 struct OrganizationState {
     organization_manager: OrganizationManager,
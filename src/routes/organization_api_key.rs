@@ -0,0 +1,105 @@
+use axum::extract::{Path, State};
+use axum::routing::{delete, get, post};
+use axum::Json;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domains::organization_api_key::{
+    CreateOrganizationApiKeyData, CreatedOrganizationApiKey, OrganizationApiKey,
+    OrganizationApiKeyError, OrganizationApiKeyResult, ORGANIZATION_API_KEY_PREFIX,
+};
+use crate::extractors::authenticated_org_member::{AuthenticatedOrgMember, CanManageApiKeys};
+use crate::extractors::authenticated_user::AnyUserRole;
+use crate::managers::organization_api_key::OrganizationApiKeyManager;
+use crate::utils::{generate_secret, hash_secret};
+
+pub fn router(organization_api_key_manager: OrganizationApiKeyManager) -> axum::Router {
+    let state = OrganizationApiKeyState {
+        organization_api_key_manager,
+    };
+
+    axum::Router::new()
+        .route("/", get(list))
+        .route("/", post(create))
+        .route("/:api_key_id", delete(revoke))
+        .with_state(state)
+}
+
+async fn list(
+    State(OrganizationApiKeyState {
+        organization_api_key_manager,
+        ..
+    }): State<OrganizationApiKeyState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageApiKeys>,
+) -> OrganizationApiKeyResult<Json<Vec<OrganizationApiKey>>> {
+    if org_member.api_key_id().is_some() {
+        return Err(OrganizationApiKeyError::Forbidden);
+    }
+
+    organization_api_key_manager
+        .list(&org_member.org().id)
+        .await
+        .map(Json)
+}
+
+async fn create(
+    State(OrganizationApiKeyState {
+        organization_api_key_manager,
+        ..
+    }): State<OrganizationApiKeyState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageApiKeys>,
+    Json(data): Json<CreateOrganizationApiKeyData>,
+) -> OrganizationApiKeyResult<Json<CreatedOrganizationApiKey>> {
+    data.validate()?;
+
+    if org_member.api_key_id().is_some() {
+        return Err(OrganizationApiKeyError::Forbidden);
+    }
+
+    if data.role > org_member.role() {
+        return Err(OrganizationApiKeyError::RoleTooHigh);
+    }
+
+    let secret = generate_secret(ORGANIZATION_API_KEY_PREFIX, 40);
+    let now = OffsetDateTime::now_utc();
+
+    let api_key = OrganizationApiKey {
+        id: Uuid::new_v4(),
+        organization_id: org_member.org().id,
+        name: data.name,
+        key_hash: hash_secret(&secret),
+        key_hint: secret[secret.len() - 4..].to_string(),
+        role: data.role,
+        created_by: Some(org_member.id),
+        created_at: now,
+        last_used_at: None,
+        expires_at: data.expires_in_days.map(|days| now + Duration::days(days)),
+    };
+
+    organization_api_key_manager.insert(&api_key).await?;
+
+    Ok(Json(CreatedOrganizationApiKey { api_key, secret }))
+}
+
+async fn revoke(
+    State(OrganizationApiKeyState {
+        organization_api_key_manager,
+        ..
+    }): State<OrganizationApiKeyState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageApiKeys>,
+    Path((_, api_key_id)): Path<(Uuid, Uuid)>,
+) -> OrganizationApiKeyResult<()> {
+    if org_member.api_key_id().is_some() {
+        return Err(OrganizationApiKeyError::Forbidden);
+    }
+
+    organization_api_key_manager
+        .delete(&org_member.org().id, &api_key_id)
+        .await
+}
+
+#[derive(Clone)]
+struct OrganizationApiKeyState {
+    organization_api_key_manager: OrganizationApiKeyManager,
+}
@@ -0,0 +1,148 @@
+use axum::extract::{Path, State};
+use axum::routing::{get, put};
+use axum::Json;
+use time::OffsetDateTime;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domains::organization_custom_role::{
+    OrganizationCustomRole, OrganizationCustomRoleData, OrganizationCustomRoleError,
+    OrganizationCustomRoleResult, OrganizationPermission,
+};
+use crate::extractors::authenticated_org_member::{
+    AnyOrganizationRole, AuthenticatedOrgMember, CanManageMembers,
+};
+use crate::extractors::authenticated_user::AnyUserRole;
+use crate::managers::organization_custom_role::OrganizationCustomRoleManager;
+
+pub fn router(organization_custom_role_manager: OrganizationCustomRoleManager) -> axum::Router {
+    let state = OrganizationCustomRoleState {
+        organization_custom_role_manager,
+    };
+
+    axum::Router::new()
+        .route("/", get(list).post(create))
+        .route("/permissions", get(list_permissions))
+        .route("/:role_id", put(update).delete(delete))
+        .with_state(state)
+}
+
+async fn list_permissions(
+    _org_member: AuthenticatedOrgMember<AnyUserRole, AnyOrganizationRole>,
+) -> Json<Vec<OrganizationPermission>> {
+    Json(OrganizationPermission::ALL.to_vec())
+}
+
+async fn list(
+    State(OrganizationCustomRoleState {
+        organization_custom_role_manager,
+        ..
+    }): State<OrganizationCustomRoleState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, AnyOrganizationRole>,
+) -> OrganizationCustomRoleResult<Json<Vec<OrganizationCustomRole>>> {
+    organization_custom_role_manager
+        .list(&org_member.org().id)
+        .await
+        .map(Json)
+}
+
+async fn create(
+    State(OrganizationCustomRoleState {
+        organization_custom_role_manager,
+        ..
+    }): State<OrganizationCustomRoleState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Json(data): Json<OrganizationCustomRoleData>,
+) -> OrganizationCustomRoleResult<Json<OrganizationCustomRole>> {
+    data.validate()?;
+    ensure_grantable(org_member.permissions(), &data.permissions)?;
+
+    let custom_role = OrganizationCustomRole {
+        id: Uuid::new_v4(),
+        organization_id: org_member.org().id,
+        name: data.name,
+        permissions: permission_names(&data.permissions),
+        created_at: OffsetDateTime::now_utc(),
+    };
+
+    organization_custom_role_manager
+        .insert(&custom_role)
+        .await?;
+
+    Ok(Json(custom_role))
+}
+
+async fn update(
+    State(OrganizationCustomRoleState {
+        organization_custom_role_manager,
+        ..
+    }): State<OrganizationCustomRoleState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Path((_, role_id)): Path<(Uuid, Uuid)>,
+    Json(data): Json<OrganizationCustomRoleData>,
+) -> OrganizationCustomRoleResult<Json<OrganizationCustomRole>> {
+    data.validate()?;
+
+    let mut custom_role = organization_custom_role_manager
+        .find_by_id(&org_member.org().id, &role_id)
+        .await?;
+
+    // both the old and the new permission set must be within reach, otherwise a
+    // member could strip permissions they were never able to hand out
+    ensure_grantable(org_member.permissions(), &custom_role.permissions())?;
+    ensure_grantable(org_member.permissions(), &data.permissions)?;
+
+    custom_role.name = data.name;
+    custom_role.permissions = permission_names(&data.permissions);
+
+    organization_custom_role_manager
+        .update(&custom_role)
+        .await?;
+
+    Ok(Json(custom_role))
+}
+
+async fn delete(
+    State(OrganizationCustomRoleState {
+        organization_custom_role_manager,
+        ..
+    }): State<OrganizationCustomRoleState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Path((_, role_id)): Path<(Uuid, Uuid)>,
+) -> OrganizationCustomRoleResult<()> {
+    let custom_role = organization_custom_role_manager
+        .find_by_id(&org_member.org().id, &role_id)
+        .await?;
+
+    ensure_grantable(org_member.permissions(), &custom_role.permissions())?;
+
+    organization_custom_role_manager
+        .delete(&org_member.org().id, &role_id)
+        .await
+}
+
+pub(super) fn ensure_grantable(
+    held: &[OrganizationPermission],
+    requested: &[OrganizationPermission],
+) -> OrganizationCustomRoleResult<()> {
+    if requested.iter().all(|permission| held.contains(permission)) {
+        Ok(())
+    } else {
+        Err(OrganizationCustomRoleError::PermissionNotHeld)
+    }
+}
+
+fn permission_names(permissions: &[OrganizationPermission]) -> Vec<String> {
+    let mut names: Vec<String> = permissions
+        .iter()
+        .map(|permission| permission.as_str().to_string())
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[derive(Clone)]
+struct OrganizationCustomRoleState {
+    organization_custom_role_manager: OrganizationCustomRoleManager,
+}
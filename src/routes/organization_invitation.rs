@@ -0,0 +1,80 @@
+use axum::extract::{Path, State};
+use axum::routing::{delete, get};
+use axum::Json;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domains::organization_invitation::{
+    CreateOrganizationInvitationData, CreatedOrganizationInvitation, OrganizationInvitation,
+    OrganizationInvitationError, OrganizationInvitationResult,
+};
+use crate::extractors::authenticated_org_member::{AuthenticatedOrgMember, CanManageMembers};
+use crate::extractors::authenticated_user::AnyUserRole;
+use crate::managers::organization_invitation::OrganizationInvitationManager;
+
+pub fn router(organization_invitation_manager: OrganizationInvitationManager) -> axum::Router {
+    let state = OrganizationInvitationState {
+        organization_invitation_manager,
+    };
+
+    axum::Router::new()
+        .route("/", get(list).post(create))
+        .route("/:invitation_id", delete(revoke))
+        .with_state(state)
+}
+
+async fn list(
+    State(OrganizationInvitationState {
+        organization_invitation_manager,
+        ..
+    }): State<OrganizationInvitationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+) -> OrganizationInvitationResult<Json<Vec<OrganizationInvitation>>> {
+    organization_invitation_manager
+        .list(&org_member.org().id)
+        .await
+        .map(Json)
+}
+
+async fn create(
+    State(OrganizationInvitationState {
+        organization_invitation_manager,
+        ..
+    }): State<OrganizationInvitationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Json(data): Json<CreateOrganizationInvitationData>,
+) -> OrganizationInvitationResult<Json<CreatedOrganizationInvitation>> {
+    data.validate()?;
+
+    // invitations are signed by a person, an api key has nobody to vouch for the invitee
+    if org_member.api_key_id().is_some() {
+        return Err(OrganizationInvitationError::Forbidden);
+    }
+
+    if data.role > org_member.role() {
+        return Err(OrganizationInvitationError::RoleTooHigh);
+    }
+
+    organization_invitation_manager
+        .create(org_member.org(), &org_member, data)
+        .await
+        .map(Json)
+}
+
+async fn revoke(
+    State(OrganizationInvitationState {
+        organization_invitation_manager,
+        ..
+    }): State<OrganizationInvitationState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Path((_, invitation_id)): Path<(Uuid, Uuid)>,
+) -> OrganizationInvitationResult<()> {
+    organization_invitation_manager
+        .delete(&org_member.org().id, &invitation_id)
+        .await
+}
+
+#[derive(Clone)]
+struct OrganizationInvitationState {
+    organization_invitation_manager: OrganizationInvitationManager,
+}
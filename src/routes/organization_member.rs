@@ -1,16 +1,37 @@
-use crate::domains::organization_member::{OrganizationMember, OrganizationMemberResult};
+use crate::domains::organization_custom_role::OrganizationCustomRoleResult;
+use crate::domains::organization_member::{
+    AssignCustomRoleData, OrganizationMember, OrganizationMemberError, OrganizationMemberResult,
+    OrganizationMemberView, TransferOwnershipData, UpdateOrganizationMemberRoleData,
+};
+use crate::extractors::authenticated_org_member::{
+    AnyOrganizationRole, AuthenticatedOrgMember, CanManageMembers, OwnerOrgRole,
+};
+use crate::extractors::authenticated_user::AnyUserRole;
+use crate::managers::organization_custom_role::OrganizationCustomRoleManager;
 use crate::managers::organization_member::OrganizationMemberManager;
+use crate::routes::organization_custom_role::ensure_grantable;
 use axum::extract::{Path, State};
-use axum::routing::get;
+use axum::routing::{delete, get, post, put};
 use axum::Json;
 use uuid::Uuid;
 
-pub fn router(organization_member_manager: OrganizationMemberManager) -> axum::Router {
+pub fn router(
+    organization_member_manager: OrganizationMemberManager,
+    organization_custom_role_manager: OrganizationCustomRoleManager,
+) -> axum::Router {
     let state = OrganizationMemberState {
         organization_member_manager,
+        organization_custom_role_manager,
     };
 
-    axum::Router::new().route("/", get(list)).with_state(state)
+    axum::Router::new()
+        .route("/", get(list))
+        .route("/leave", post(leave))
+        .route("/transfer-ownership", post(transfer_ownership))
+        .route("/:user_id", delete(remove))
+        .route("/:user_id/role", put(update_role))
+        .route("/:user_id/custom-role", put(assign_custom_role))
+        .with_state(state)
 }
 
 async fn list(
@@ -18,12 +39,138 @@ async fn list(
         organization_member_manager,
         ..
     }): State<OrganizationMemberState>,
-    Path((org_id,)): Path<(Uuid,)>,
-) -> OrganizationMemberResult<Json<Vec<OrganizationMember>>> {
-    organization_member_manager.list(&org_id).await.map(Json)
+    org_member: AuthenticatedOrgMember<AnyUserRole, AnyOrganizationRole>,
+) -> OrganizationMemberResult<Json<Vec<OrganizationMemberView>>> {
+    organization_member_manager
+        .list(&org_member.org().id)
+        .await
+        .map(Json)
+}
+
+async fn update_role(
+    State(OrganizationMemberState {
+        organization_member_manager,
+        ..
+    }): State<OrganizationMemberState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Path((_, user_id)): Path<(Uuid, Uuid)>,
+    Json(data): Json<UpdateOrganizationMemberRoleData>,
+) -> OrganizationMemberResult<()> {
+    let target = find_manageable(&organization_member_manager, &org_member, &user_id).await?;
+
+    if data.role > org_member.role() {
+        return Err(OrganizationMemberError::RoleTooHigh);
+    }
+
+    organization_member_manager
+        .update_role(&target.organization_id, &target.user_id, data.role)
+        .await
+}
+
+async fn remove(
+    State(OrganizationMemberState {
+        organization_member_manager,
+        ..
+    }): State<OrganizationMemberState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Path((_, user_id)): Path<(Uuid, Uuid)>,
+) -> OrganizationMemberResult<()> {
+    let target = find_manageable(&organization_member_manager, &org_member, &user_id).await?;
+
+    organization_member_manager
+        .delete(&target.organization_id, &target.user_id)
+        .await
+}
+
+async fn leave(
+    State(OrganizationMemberState {
+        organization_member_manager,
+        ..
+    }): State<OrganizationMemberState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, AnyOrganizationRole>,
+) -> OrganizationMemberResult<()> {
+    if org_member.api_key_id().is_some() {
+        return Err(OrganizationMemberError::Forbidden);
+    }
+
+    organization_member_manager
+        .delete(&org_member.org().id, &org_member.id)
+        .await
+}
+
+async fn transfer_ownership(
+    State(OrganizationMemberState {
+        organization_member_manager,
+        ..
+    }): State<OrganizationMemberState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, OwnerOrgRole>,
+    Json(data): Json<TransferOwnershipData>,
+) -> OrganizationMemberResult<()> {
+    if org_member.api_key_id().is_some() || data.user_id == org_member.id {
+        return Err(OrganizationMemberError::Forbidden);
+    }
+
+    organization_member_manager
+        .transfer_ownership(&org_member.org().id, &org_member.id, &data.user_id)
+        .await
+}
+
+/// Members can only manage others, and never anyone ranked above them.
+async fn find_manageable(
+    organization_member_manager: &OrganizationMemberManager,
+    org_member: &AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    user_id: &Uuid,
+) -> OrganizationMemberResult<OrganizationMember> {
+    if *user_id == org_member.id {
+        return Err(OrganizationMemberError::Forbidden);
+    }
+
+    let target = organization_member_manager
+        .find_by_user_id(&org_member.org().id, user_id)
+        .await?;
+
+    if target.role > org_member.role() {
+        return Err(OrganizationMemberError::Forbidden);
+    }
+
+    Ok(target)
+}
+
+async fn assign_custom_role(
+    State(OrganizationMemberState {
+        organization_member_manager,
+        organization_custom_role_manager,
+    }): State<OrganizationMemberState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanManageMembers>,
+    Path((_, user_id)): Path<(Uuid, Uuid)>,
+    Json(data): Json<AssignCustomRoleData>,
+) -> OrganizationCustomRoleResult<()> {
+    let organization_id = org_member.org().id;
+
+    let target = find_manageable(&organization_member_manager, &org_member, &user_id).await?;
+    if let Some(current_role_id) = &target.custom_role_id {
+        let current_role = organization_custom_role_manager
+            .find_by_id(&organization_id, current_role_id)
+            .await?;
+        ensure_grantable(org_member.permissions(), &current_role.permissions())?;
+    }
+
+    if let Some(custom_role_id) = &data.custom_role_id {
+        let custom_role = organization_custom_role_manager
+            .find_by_id(&organization_id, custom_role_id)
+            .await?;
+        ensure_grantable(org_member.permissions(), &custom_role.permissions())?;
+    }
+
+    organization_member_manager
+        .set_custom_role(&organization_id, &target.user_id, &data.custom_role_id)
+        .await?;
+
+    Ok(())
 }
 
 #[derive(Clone)]
 struct OrganizationMemberState {
     organization_member_manager: OrganizationMemberManager,
+    organization_custom_role_manager: OrganizationCustomRoleManager,
 }
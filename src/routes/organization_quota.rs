@@ -0,0 +1,36 @@
+use axum::extract::State;
+use axum::routing::get;
+use axum::Json;
+
+use crate::domains::organization_quota::{OrganizationQuotaResult, OrganizationQuotaView};
+use crate::extractors::authenticated_org_member::{AnyOrganizationRole, AuthenticatedOrgMember};
+use crate::extractors::authenticated_user::AnyUserRole;
+use crate::managers::organization_quota::OrganizationQuotaManager;
+
+pub fn router(organization_quota_manager: OrganizationQuotaManager) -> axum::Router {
+    let state = OrganizationQuotaState {
+        organization_quota_manager,
+    };
+
+    axum::Router::new()
+        .route("/", get(get_quota))
+        .with_state(state)
+}
+
+async fn get_quota(
+    State(OrganizationQuotaState {
+        organization_quota_manager,
+        ..
+    }): State<OrganizationQuotaState>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, AnyOrganizationRole>,
+) -> OrganizationQuotaResult<Json<OrganizationQuotaView>> {
+    organization_quota_manager
+        .view(&org_member.org().id)
+        .await
+        .map(Json)
+}
+
+#[derive(Clone)]
+struct OrganizationQuotaState {
+    organization_quota_manager: OrganizationQuotaManager,
+}
@@ -0,0 +1,93 @@
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::Json;
+use tracing::error;
+use validator::Validate;
+
+use crate::domains::auth::AuthResult;
+use crate::domains::user::UserError;
+use crate::domains::user_token::{
+    ConfirmPasswordResetData, RequestPasswordResetData, UserTokenKind,
+};
+use crate::managers::session::SessionManager;
+use crate::managers::user::UserManager;
+use crate::managers::user_token::UserTokenManager;
+use crate::utils::hash_password;
+
+pub fn router(
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    user_token_manager: UserTokenManager,
+) -> axum::Router {
+    let state = PasswordResetState {
+        user_manager,
+        session_manager,
+        user_token_manager,
+    };
+
+    axum::Router::new()
+        .route("/", post(request))
+        .route("/confirm", post(confirm))
+        .with_state(state)
+}
+
+async fn request(
+    State(PasswordResetState {
+        user_manager,
+        user_token_manager,
+        ..
+    }): State<PasswordResetState>,
+    Json(data): Json<RequestPasswordResetData>,
+) -> AuthResult<StatusCode> {
+    data.validate()?;
+
+    // always answer the same way and right away, the lookup and the mail happen in the
+    // background so neither the body nor the timing tells who has an account
+    tokio::spawn(async move {
+        match user_manager.find_by_email(&data.email).await {
+            Ok(user) => {
+                if let Err(err) = user_token_manager.send_password_reset(&user).await {
+                    error!("failed to send password reset to {}: {}", user.id, err);
+                }
+            }
+            Err(UserError::NotFound) => {}
+            Err(err) => error!("failed to look up a password reset request: {}", err),
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn confirm(
+    State(PasswordResetState {
+        user_manager,
+        session_manager,
+        user_token_manager,
+    }): State<PasswordResetState>,
+    Json(data): Json<ConfirmPasswordResetData>,
+) -> AuthResult<StatusCode> {
+    data.validate()?;
+
+    let token = user_token_manager
+        .consume(UserTokenKind::PasswordReset, &data.token)
+        .await?;
+
+    user_manager
+        .update_password_hash(&token.user_id, &hash_password(&data.password))
+        .await?;
+
+    // whoever knew the old password must not keep their sessions
+    session_manager
+        .delete_all_by_user_id(&token.user_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Clone)]
+struct PasswordResetState {
+    user_manager: UserManager,
+    session_manager: SessionManager,
+    user_token_manager: UserTokenManager,
+}
@@ -0,0 +1,95 @@
+use axum::extract::{Path, State};
+use axum::routing::{delete, get, post};
+use axum::Json;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domains::personal_access_token::{
+    CreatePersonalAccessTokenData, CreatedPersonalAccessToken, PersonalAccessToken,
+    PersonalAccessTokenResult, PERSONAL_ACCESS_TOKEN_PREFIX,
+};
+use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::managers::personal_access_token::PersonalAccessTokenManager;
+use crate::utils::{generate_secret, hash_secret};
+
+pub fn router(personal_access_token_manager: PersonalAccessTokenManager) -> axum::Router {
+    let state = PersonalAccessTokenState {
+        personal_access_token_manager,
+    };
+
+    axum::Router::new()
+        .route("/", get(list))
+        .route("/", post(create))
+        .route("/:token_id", delete(revoke))
+        .with_state(state)
+}
+
+async fn list(
+    State(PersonalAccessTokenState {
+        personal_access_token_manager,
+        ..
+    }): State<PersonalAccessTokenState>,
+    user: AuthenticatedUser,
+) -> PersonalAccessTokenResult<Json<Vec<PersonalAccessToken>>> {
+    personal_access_token_manager
+        .list_by_user_id(&user.id)
+        .await
+        .map(Json)
+}
+
+async fn create(
+    State(PersonalAccessTokenState {
+        personal_access_token_manager,
+        ..
+    }): State<PersonalAccessTokenState>,
+    user: AuthenticatedUser,
+    Json(data): Json<CreatePersonalAccessTokenData>,
+) -> PersonalAccessTokenResult<Json<CreatedPersonalAccessToken>> {
+    data.validate()?;
+
+    let secret = generate_secret(PERSONAL_ACCESS_TOKEN_PREFIX, 40);
+    let now = OffsetDateTime::now_utc();
+
+    let mut scopes: Vec<String> = data
+        .scopes
+        .iter()
+        .map(|scope| scope.as_str().to_string())
+        .collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let token = PersonalAccessToken {
+        id: Uuid::new_v4(),
+        user_id: user.id,
+        name: data.name,
+        token_hash: hash_secret(&secret),
+        token_hint: secret[secret.len() - 4..].to_string(),
+        scopes,
+        created_at: now,
+        last_used_at: None,
+        expires_at: Some(now + Duration::days(data.expires_in_days)),
+    };
+
+    personal_access_token_manager.insert(&token).await?;
+
+    Ok(Json(CreatedPersonalAccessToken { token, secret }))
+}
+
+async fn revoke(
+    State(PersonalAccessTokenState {
+        personal_access_token_manager,
+        ..
+    }): State<PersonalAccessTokenState>,
+    user: AuthenticatedUser,
+    Path((token_id,)): Path<(Uuid,)>,
+) -> PersonalAccessTokenResult<()> {
+    personal_access_token_manager
+        .delete_by_user_id(&user.id, &token_id)
+        .await
+}
+
+#[derive(Clone)]
+struct PersonalAccessTokenState {
+    personal_access_token_manager: PersonalAccessTokenManager,
+}
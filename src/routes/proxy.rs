@@ -1,31 +1,45 @@
 use axum::extract::{Path, State};
-use axum::routing::{get, post};
+use axum::routing::{delete, get, post};
 use axum::Json;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
+use tracing::error;
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::domains::proxy::{CreateProxyData, Proxy, ProxyResult};
-use crate::extractors::authenticated_org_member::AuthenticatedOrgMember;
+use crate::domains::organization::Organization;
+use crate::domains::proxy::{CreateProxyData, Proxy, ProxyError, ProxyResult};
+use crate::domains::tier::Tier;
+use crate::extractors::authenticated_org_member::{
+    AuthenticatedOrgMember, CanCreateProxy, CanDeleteProxy, CanRestartProxy,
+};
+use crate::extractors::authenticated_user::AnyUserRole;
 use crate::managers::proxy::ProxyManager;
 use crate::managers::proxy_template::ProxyTemplateManager;
+use crate::managers::region_capacity::RegionCapacityManager;
 use crate::managers::region_connection::RegionConnectionManager;
+use crate::managers::tier::TierManager;
 
 pub fn router(
     proxy_manager: ProxyManager,
     proxy_template_manager: ProxyTemplateManager,
     region_connection_manager: RegionConnectionManager,
+    tier_manager: TierManager,
+    region_capacity_manager: RegionCapacityManager,
 ) -> axum::Router {
     let state = ProxyState {
         proxy_manager,
         proxy_template_manager,
         region_connection_manager,
+        tier_manager,
+        region_capacity_manager,
     };
 
     axum::Router::new()
         .route("/", get(list))
         .route("/", post(create))
+        .route("/:proxy_id", delete(remove))
+        .route("/:proxy_id/restart", post(restart))
         .with_state(state)
 }
 
@@ -42,10 +56,11 @@ async fn create(
         proxy_manager,
         proxy_template_manager,
         region_connection_manager,
-        ..
+        tier_manager,
+        region_capacity_manager,
     }): State<ProxyState>,
     Path((organization_id,)): Path<(Uuid,)>,
-    org_member: AuthenticatedOrgMember,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanCreateProxy>,
     Json(data): Json<CreateProxyData>,
 ) -> ProxyResult<Json<Proxy>> {
     data.validate()?;
@@ -53,6 +68,18 @@ async fn create(
     let template = proxy_template_manager
         .find_by_slug(&organization_id, &data.template_slug)
         .await?;
+    let tier = match &template.tier_id {
+        Some(tier_id) => Some(tier_manager.find_by_id(tier_id).await?),
+        None => None,
+    };
+    let resources = tier.as_ref().map(Tier::resources).unwrap_or_default();
+
+    proxy_manager
+        .ensure_quota(org_member.org(), &resources)
+        .await?;
+    region_capacity_manager
+        .ensure_schedulable(&org_member.org().region_id, tier.as_ref(), &resources)
+        .await?;
 
     let proxy_slug = data.slug.unwrap_or_else(|| {
         format!(
@@ -68,20 +95,23 @@ async fn create(
     });
 
     let region_id = org_member.org().region_id;
+    // looked up before anything is declared so a degraded region fails the request up front
+    let kube_client = region_connection_manager
+        .find_kube_wrapped_client_by_id(&region_id)
+        .await?;
     let (bridge_id, bs_proxy_id) = if template.bridge_id.is_none() {
         (None, None)
     } else {
         let bs_client = region_connection_manager
             .find_bridge_service_client_by_id(&region_id)
-            .await
-            .unwrap();
+            .await?;
         let bs_proxy = bs_client
             .declare_proxy(&ork_bridge_service::domains::proxy::CreateProxyData {
                 slug: proxy_slug.clone(),
             })
             .await
-            .unwrap();
-        (Some(template.bridge_id.unwrap()), Some(bs_proxy.id))
+            .map_err(|err| ProxyError::Unknown(err.to_string()))?;
+        (template.bridge_id, Some(bs_proxy.id))
     };
 
     let proxy = Proxy {
@@ -92,22 +122,139 @@ async fn create(
         template_id: template.id,
     };
 
-    proxy_manager
-        .create(org_member.org(), &template, &proxy)
-        .await?;
+    if let Err(err) = proxy_manager
+        .create(org_member.org(), &proxy, &resources)
+        .await
+    {
+        if let Some(bs_proxy_id) = &proxy.bs_proxy_id {
+            if let Err(cleanup_err) =
+                undeclare(&region_connection_manager, &region_id, bs_proxy_id).await
+            {
+                error!(
+                    "failed to undeclare bridge proxy {}: {}",
+                    bs_proxy_id, cleanup_err
+                );
+            }
+        }
+        return Err(err);
+    }
 
-    region_connection_manager
-        .find_kube_wrapped_client_by_id(&region_id)
+    // the namespace ResourceQuota can still reject the pod, nothing of the proxy is kept then
+    if let Err(err) = kube_client
+        .create_proxy_pod(org_member.org(), &template, tier.as_ref(), &proxy)
         .await
-        .unwrap()
-        .create_proxy_pod(org_member.org(), &template, &proxy)
-        .await;
+    {
+        if let Err(cleanup_err) = teardown(
+            &proxy_manager,
+            &region_connection_manager,
+            org_member.org(),
+            &proxy,
+        )
+        .await
+        {
+            error!("failed to clean up proxy {}: {}", proxy.id, cleanup_err);
+        }
+        return Err(err.into());
+    }
 
     Ok(Json(proxy))
 }
+
+async fn restart(
+    State(ProxyState {
+        proxy_manager,
+        proxy_template_manager,
+        region_connection_manager,
+        tier_manager,
+        ..
+    }): State<ProxyState>,
+    Path((organization_id, proxy_id)): Path<(Uuid, Uuid)>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanRestartProxy>,
+) -> ProxyResult<()> {
+    let proxy = proxy_manager
+        .find_by_id(&organization_id, &proxy_id)
+        .await?;
+    let template = proxy_template_manager
+        .find_by_id(&organization_id, &proxy.template_id)
+        .await?;
+    let tier = match &template.tier_id {
+        Some(tier_id) => Some(tier_manager.find_by_id(tier_id).await?),
+        None => None,
+    };
+
+    region_connection_manager
+        .find_kube_wrapped_client_by_id(&org_member.org().region_id)
+        .await?
+        .restart_proxy_pod(org_member.org(), &template, tier.as_ref(), &proxy)
+        .await?;
+
+    Ok(())
+}
+
+async fn remove(
+    State(ProxyState {
+        proxy_manager,
+        region_connection_manager,
+        ..
+    }): State<ProxyState>,
+    Path((organization_id, proxy_id)): Path<(Uuid, Uuid)>,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanDeleteProxy>,
+) -> ProxyResult<()> {
+    let proxy = proxy_manager
+        .find_by_id(&organization_id, &proxy_id)
+        .await?;
+
+    teardown(
+        &proxy_manager,
+        &region_connection_manager,
+        org_member.org(),
+        &proxy,
+    )
+    .await
+}
+
+// remote resources go first, a failure leaves the row behind so the delete can be retried
+async fn teardown(
+    proxy_manager: &ProxyManager,
+    region_connection_manager: &RegionConnectionManager,
+    organization: &Organization,
+    proxy: &Proxy,
+) -> ProxyResult<()> {
+    region_connection_manager
+        .find_kube_wrapped_client_by_id(&organization.region_id)
+        .await?
+        .delete_proxy(organization, proxy)
+        .await?;
+    if let Some(bs_proxy_id) = &proxy.bs_proxy_id {
+        undeclare(
+            region_connection_manager,
+            &organization.region_id,
+            bs_proxy_id,
+        )
+        .await?;
+    }
+
+    proxy_manager.delete(&organization.id, &proxy.id).await
+}
+
+async fn undeclare(
+    region_connection_manager: &RegionConnectionManager,
+    region_id: &Uuid,
+    bs_proxy_id: &Uuid,
+) -> ProxyResult<()> {
+    region_connection_manager
+        .find_bridge_service_client_by_id(region_id)
+        .await?
+        .delete_proxy(bs_proxy_id)
+        .await
+        .map_err(|err| ProxyError::Unknown(err.to_string()))
+}
+
 #[derive(Clone)]
 struct ProxyState {
     proxy_manager: ProxyManager,
     proxy_template_manager: ProxyTemplateManager,
     region_connection_manager: RegionConnectionManager,
+    tier_manager: TierManager,
+    region_capacity_manager: RegionCapacityManager,
 }
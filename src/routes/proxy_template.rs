@@ -5,17 +5,21 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::domains::proxy_template::{CreateProxyTemplateData, ProxyTemplate, ProxyTemplateResult};
-use crate::extractors::authenticated_org_member::AuthenticatedOrgMember;
+use crate::extractors::authenticated_org_member::{AuthenticatedOrgMember, CanWriteTemplate};
+use crate::extractors::authenticated_user::AnyUserRole;
 use crate::managers::bridge::BridgeManager;
 use crate::managers::proxy_template::ProxyTemplateManager;
+use crate::managers::tier::TierManager;
 
 pub fn router(
     bridge_manager: BridgeManager,
     proxy_template_manager: ProxyTemplateManager,
+    tier_manager: TierManager,
 ) -> axum::Router {
     let state = ProxyTemplateState {
         bridge_manager,
         proxy_template_manager,
+        tier_manager,
     };
 
     axum::Router::new()
@@ -41,9 +45,9 @@ async fn create(
     State(ProxyTemplateState {
         bridge_manager,
         proxy_template_manager,
-        ..
+        tier_manager,
     }): State<ProxyTemplateState>,
-    org_member: AuthenticatedOrgMember,
+    org_member: AuthenticatedOrgMember<AnyUserRole, CanWriteTemplate>,
     Json(data): Json<CreateProxyTemplateData>,
 ) -> ProxyTemplateResult<Json<ProxyTemplate>> {
     data.validate()?;
@@ -60,12 +64,24 @@ async fn create(
         None
     };
 
+    let tier_id = if let Some(tier_slug) = data.tier_slug {
+        Some(
+            tier_manager
+                .find_by_slug(&organization.region_id, &tier_slug)
+                .await?
+                .id,
+        )
+    } else {
+        None
+    };
+
     let proxy_template = ProxyTemplate {
         id: Uuid::new_v4(),
         slug: data.slug,
         image: data.image,
         plugins_dir: data.plugins_dir,
         bridge_id,
+        tier_id,
     };
 
     proxy_template_manager
@@ -79,4 +95,5 @@ async fn create(
 struct ProxyTemplateState {
     bridge_manager: BridgeManager,
     proxy_template_manager: ProxyTemplateManager,
+    tier_manager: TierManager,
 }
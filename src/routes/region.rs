@@ -1,24 +1,46 @@
-use crate::domains::region::{Region, RegionResult};
+use crate::domains::region::{RegionResult, RegionView};
+use crate::domains::tier::{Tier, TierResult};
 use crate::extractors::authenticated_user::AuthenticatedUser;
 use crate::managers::region::RegionManager;
-use axum::extract::State;
+use crate::managers::tier::TierManager;
+use axum::extract::{Path, State};
 use axum::routing::get;
 use axum::Json;
 
-pub fn router(region_manager: RegionManager) -> axum::Router {
-    let state = RegionState { region_manager };
+pub fn router(region_manager: RegionManager, tier_manager: TierManager) -> axum::Router {
+    let state = RegionState {
+        region_manager,
+        tier_manager,
+    };
 
-    axum::Router::new().route("/", get(list)).with_state(state)
+    axum::Router::new()
+        .route("/", get(list))
+        .route("/:region_slug/tiers", get(list_tiers))
+        .with_state(state)
 }
 
 async fn list(
     State(RegionState { region_manager, .. }): State<RegionState>,
     _user: AuthenticatedUser,
-) -> RegionResult<Json<Vec<Region>>> {
-    region_manager.list().await.map(Json)
+) -> RegionResult<Json<Vec<RegionView>>> {
+    region_manager.list_enabled().await.map(Json)
+}
+
+async fn list_tiers(
+    State(RegionState {
+        region_manager,
+        tier_manager,
+    }): State<RegionState>,
+    _user: AuthenticatedUser,
+    Path((region_slug,)): Path<(String,)>,
+) -> TierResult<Json<Vec<Tier>>> {
+    let region = region_manager.find_by_slug(&region_slug).await?;
+
+    tier_manager.list_by_region(&region.id).await.map(Json)
 }
 
 #[derive(Clone)]
 struct RegionState {
     region_manager: RegionManager,
+    tier_manager: TierManager,
 }
@@ -0,0 +1,66 @@
+use axum::extract::State;
+use axum::routing::{delete, get, post};
+use axum::Json;
+
+use crate::domains::two_factor::{
+    RecoveryCodes, TwoFactorCodeData, TwoFactorEnrolment, TwoFactorResult, TwoFactorStatus,
+};
+use crate::extractors::authenticated_user::AuthenticatedUser;
+use crate::managers::two_factor::TwoFactorManager;
+
+pub fn router(two_factor_manager: TwoFactorManager) -> axum::Router {
+    let state = TwoFactorState { two_factor_manager };
+
+    axum::Router::new()
+        .route("/", get(status))
+        .route("/", delete(disable))
+        .route("/enrol", post(enrol))
+        .route("/confirm", post(confirm))
+        .with_state(state)
+}
+
+async fn status(
+    State(TwoFactorState {
+        two_factor_manager, ..
+    }): State<TwoFactorState>,
+    user: AuthenticatedUser,
+) -> TwoFactorResult<Json<TwoFactorStatus>> {
+    two_factor_manager.status(&user.id).await.map(Json)
+}
+
+async fn enrol(
+    State(TwoFactorState {
+        two_factor_manager, ..
+    }): State<TwoFactorState>,
+    user: AuthenticatedUser,
+) -> TwoFactorResult<Json<TwoFactorEnrolment>> {
+    two_factor_manager.enrol(&user).await.map(Json)
+}
+
+async fn confirm(
+    State(TwoFactorState {
+        two_factor_manager, ..
+    }): State<TwoFactorState>,
+    user: AuthenticatedUser,
+    Json(data): Json<TwoFactorCodeData>,
+) -> TwoFactorResult<Json<RecoveryCodes>> {
+    two_factor_manager
+        .confirm(&user.id, &data.code)
+        .await
+        .map(Json)
+}
+
+async fn disable(
+    State(TwoFactorState {
+        two_factor_manager, ..
+    }): State<TwoFactorState>,
+    user: AuthenticatedUser,
+    Json(data): Json<TwoFactorCodeData>,
+) -> TwoFactorResult<()> {
+    two_factor_manager.disable(&user.id, &data.code).await
+}
+
+#[derive(Clone)]
+struct TwoFactorState {
+    two_factor_manager: TwoFactorManager,
+}
@@ -0,0 +1,184 @@
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha1::Sha1;
+use time::OffsetDateTime;
+use url::Url;
+
+const ISSUER: &str = "ork";
+const SECRET_LENGTH: usize = 20;
+const DIGITS: u32 = 6;
+const STEP: u64 = 30;
+// accept codes from one step before and after the current one to tolerate clock drift
+const SKEW: u64 = 1;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    rand::thread_rng().fill_bytes(&mut secret);
+
+    encode_base32(&secret)
+}
+
+pub fn provisioning_uri(secret: &str, account: &str) -> String {
+    let mut url = Url::parse("otpauth://totp/").unwrap();
+    url.set_path(&format!("/{}:{}", ISSUER, account));
+    url.query_pairs_mut()
+        .append_pair("secret", secret)
+        .append_pair("issuer", ISSUER)
+        .append_pair("algorithm", "SHA1")
+        .append_pair("digits", &DIGITS.to_string())
+        .append_pair("period", &STEP.to_string());
+
+    url.to_string()
+}
+
+// returns the time step the code matched so callers can refuse to accept it twice
+pub fn verify(secret: &str, code: &str, now: OffsetDateTime) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize {
+        return None;
+    }
+    let code: u32 = code.parse().ok()?;
+    let secret = decode_base32(secret)?;
+
+    let counter = now.unix_timestamp().max(0) as u64 / STEP;
+    (counter.saturating_sub(SKEW)..=counter + SKEW).find(|step| code_at(&secret, *step) == code)
+}
+
+fn code_at(secret: &[u8], counter: u64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("hmac accepts keys of any size");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset],
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]) & 0x7fff_ffff;
+
+    binary % 10u32.pow(DIGITS)
+}
+
+fn encode_base32(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for byte in bytes {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+
+    encoded
+}
+
+fn decode_base32(value: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(value.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for char in value.trim_end_matches('=').bytes() {
+        let index = BASE32_ALPHABET
+            .iter()
+            .position(|candidate| *candidate == char.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | index as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+    }
+
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, the SHA-1 seed is the ASCII string "12345678901234567890"
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+    const RFC_VECTORS: [(i64, u32); 6] = [
+        (59, 94287082),
+        (1111111109, 7081804),
+        (1111111111, 14050471),
+        (1234567890, 89005924),
+        (2000000000, 69279037),
+        (20000000000, 65353130),
+    ];
+
+    #[test]
+    fn matches_rfc_6238_vectors() {
+        for (time, expected) in RFC_VECTORS {
+            // the vectors have 8 digits, ours are their last 6
+            assert_eq!(
+                code_at(RFC_SECRET, time as u64 / STEP),
+                expected % 10u32.pow(DIGITS),
+                "at {}",
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn verifies_codes_within_skew() {
+        let secret = encode_base32(RFC_SECRET);
+        let now = OffsetDateTime::from_unix_timestamp(1111111109).unwrap();
+
+        assert_eq!(verify(&secret, "081804", now), Some(1111111109 / STEP));
+        assert_eq!(
+            verify(&secret, "081804", now + time::Duration::seconds(30)),
+            Some(1111111109 / STEP)
+        );
+        assert_eq!(
+            verify(&secret, "081804", now + time::Duration::seconds(90)),
+            None
+        );
+        assert_eq!(verify(&secret, "81804", now), None);
+        assert_eq!(verify(&secret, "000000", now), None);
+    }
+
+    #[test]
+    fn encodes_rfc_4648_base32_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ];
+
+        for (plain, encoded) in vectors {
+            assert_eq!(encode_base32(plain.as_bytes()), encoded);
+            assert_eq!(decode_base32(encoded).unwrap(), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn decodes_padded_and_lowercase_base32() {
+        assert_eq!(decode_base32("MZXW6YQ=").unwrap(), b"foob");
+        assert_eq!(decode_base32("mzxw6ytboi").unwrap(), b"foobar");
+        assert_eq!(decode_base32("MZXW1"), None);
+    }
+
+    #[test]
+    fn round_trips_generated_secrets() {
+        let secret = generate_secret();
+
+        assert_eq!(secret.len(), 32);
+        assert_eq!(decode_base32(&secret).unwrap().len(), SECRET_LENGTH);
+        assert_eq!(encode_base32(&decode_base32(&secret).unwrap()), secret);
+    }
+}
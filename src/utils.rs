@@ -1,3 +1,9 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::SaltString;
+use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use sha2::{Digest, Sha256};
 use std::fmt::{Debug, Display};
 
 #[inline]
@@ -18,3 +24,44 @@ pub fn handle_sqlx_unique<E: Debug>(
         _ => otherwise(sqlx_err.to_string()),
     }
 }
+
+pub fn generate_secret(prefix: &str, length: usize) -> String {
+    let secret: String = rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect();
+
+    format!("{}{}", prefix, secret)
+}
+
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+pub fn hash_password(password: &String) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    let argon2 = Argon2::default();
+
+    argon2
+        .hash_password(password.as_bytes(), &salt)
+        .unwrap()
+        .to_string()
+}
+
+pub fn verify_password(password: &String, password_hash: &String) -> password_hash::Result<()> {
+    let hash = PasswordHash::new(password_hash)?;
+    let argon2 = Argon2::default();
+
+    argon2.verify_password(password.as_bytes(), &hash)
+}
+
+// substring match for ILIKE, user input must not smuggle in wildcards
+pub fn like_pattern(query: &str) -> String {
+    let escaped = query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{}%", escaped)
+}